@@ -1,49 +1,140 @@
 //! Parses the arguments to the program, if present.
 
-use crate::util;
-use std::{borrow::Cow, env, ffi::OsStr, fs, io, path::Path};
+use crate::{
+    grid::{
+        palette::{ColorBlindMode, ColorSupport},
+        Difficulty,
+    },
+    mode::Mode,
+    util,
+};
+use std::{
+    borrow::Cow,
+    env,
+    ffi::OsStr,
+    fs, io,
+    path::{Path, PathBuf},
+    time::Duration,
+};
 use terminal::util::Size;
 
-/// The maximum grid size must not have more than 2 digits
-/// because such numbers cannot be displayed correctly on the grid
+/// The left clues are as wide as the largest of them so the grid can be much wider than it can be high.
+pub const MAX_GRID_WIDTH: u16 = 999;
+/// The maximum grid height must not have more than 2 digits
+/// because the top clues cannot be displayed correctly otherwise
 /// due to the grid being based on two characters for numbers.
-const MAX_GRID_SIZE: u16 = 99;
+pub const MAX_GRID_HEIGHT: u16 = 99;
 
 /// The filename extension that grid files need to have.
 pub const FILE_EXTENSION: &str = "yaya";
 
-pub fn valid_extension(path: &str) -> bool {
-    let path = Path::new(path);
-    path.extension() == Some(OsStr::new(FILE_EXTENSION))
+pub fn valid_extension(path: impl AsRef<Path>) -> bool {
+    path.as_ref().extension() == Some(OsStr::new(FILE_EXTENSION))
 }
 
 /// The values that can be created out of the arguments.
 #[derive(Debug)]
 pub enum Arg {
-    File { name: String, content: String },
+    File {
+        name: String,
+        content: String,
+    },
+    /// The grid file content is to be read from the standard input.
+    Stdin,
+    /// The grid files in a directory, sorted by name.
+    Directory {
+        name: String,
+        paths: Vec<PathBuf>,
+    },
     GridSize(Size),
+    /// The stats of the grid file at the path are to be printed.
+    Stats(String),
     Help,
     Version,
 }
 
+/// The options that can be passed in addition to the other arguments.
+///
+/// Options that are also [settings](crate::config::Settings) are only present if they were passed.
+#[derive(Debug, Default)]
+pub struct Options {
+    /// How long alerts are shown before they are cleared.
+    pub alert_clear_delay: Option<Duration>,
+    /// If present, the grid is solved and exported as an SVG file to this path instead of being played.
+    pub export_svg_path: Option<String>,
+    /// Whether flood-filled cells appear ring by ring instead of all at once.
+    pub animate_fill: Option<bool>,
+    /// How many colors to draw with.
+    pub color_support: Option<ColorSupport>,
+    /// How the game is played.
+    pub mode: Option<Mode>,
+    /// Whether empty rows and columns have a single `0` clue instead of none.
+    pub zero_clues: Option<bool>,
+    /// Whether the mouse can be used.
+    pub mouse: Option<bool>,
+    /// Whether only colors that stand out strongly against each other are used.
+    pub high_contrast: Option<bool>,
+    /// Whether random grids may have rows and columns without any filled cells.
+    pub empty_lines: Option<bool>,
+    /// The kind of color blindness that the colors accommodate.
+    pub color_blind_mode: Option<ColorBlindMode>,
+    /// Whether the terminal bell rings when the grid is solved, when a row or column contradicts its clues and when a mistake is marked.
+    pub bell: Option<bool>,
+    /// Whether dragging with filled cells overwrites maybed and crossed out cells.
+    pub drag_overwrite: Option<bool>,
+    /// Whether placed cells that contradict the solution are marked.
+    pub show_mistakes: Option<bool>,
+    /// Whether a legend of the cells is shown below the basic controls help.
+    pub legend: Option<bool>,
+    /// Whether the empty cells of rows and columns that a placement solves are crossed out.
+    pub auto_cross: Option<bool>,
+    /// Whether only ASCII characters are drawn.
+    pub ascii: Option<bool>,
+    /// Whether the terminal cursor is shown on the selected cell.
+    pub show_cursor: Option<bool>,
+    /// If present, random grids of this estimated difficulty are preferred.
+    pub difficulty: Option<Difficulty>,
+    /// If present, the grid is generated from the image at this path.
+    pub image_path: Option<String>,
+    /// The width of the grid generated from an image.
+    pub image_grid_width: Option<u16>,
+    /// Pixels of the image at most as bright as this become filled cells. If not present, it is determined automatically.
+    pub image_threshold: Option<u8>,
+    /// If present, the settings are loaded from this config file instead of the default one.
+    pub config_path: Option<String>,
+    /// Whether to write the settings to the config file instead of playing.
+    pub write_config: bool,
+    /// Whether the games start in the editor. Instead of a random grid, an empty one is edited.
+    pub editor: bool,
+    /// Whether the bot solves the first grid instead of the player.
+    pub bot: bool,
+    /// Whether a small grid that explains the rules and controls step by step is played first.
+    pub tutorial: bool,
+}
+
+/// The width of the grid generated from an image if no width was passed.
+pub const DEFAULT_IMAGE_GRID_WIDTH: u16 = 20;
+
 #[derive(Debug)]
 enum SizeError {
-    OutOfRange(&'static str),
+    /// What is out of range together with the maximum.
+    OutOfRange(&'static str, u16),
     FileNotFound,
 }
 
 fn parse_squared_size(size_str: &str) -> Result<Option<Arg>, SizeError> {
     if let Ok(parsed_size) = size_str.parse::<u16>() {
         match parsed_size {
-            1..=MAX_GRID_SIZE => Ok(Some(Arg::GridSize(Size {
+            // Squared grids can't be higher than they can be wide
+            1..=MAX_GRID_HEIGHT => Ok(Some(Arg::GridSize(Size {
                 width: parsed_size,
                 height: parsed_size,
             }))),
-            _ => Err(SizeError::OutOfRange("size")),
+            _ => Err(SizeError::OutOfRange("size", MAX_GRID_HEIGHT)),
         }
     } else if util::is_numeric(size_str) {
         // A value >u16::MAX will not parse but might still be a number
-        Err(SizeError::OutOfRange("size"))
+        Err(SizeError::OutOfRange("size", MAX_GRID_HEIGHT))
     } else {
         Err(SizeError::FileNotFound)
     }
@@ -52,11 +143,11 @@ fn parse_squared_size(size_str: &str) -> Result<Option<Arg>, SizeError> {
 fn parse_size(width_str: &str, height_str: &str) -> Result<Option<Arg>, SizeError> {
     if let Ok(parsed_width) = width_str.parse::<u16>() {
         if let Ok(parsed_height) = height_str.parse::<u16>() {
-            if !(1..=MAX_GRID_SIZE).contains(&parsed_width) {
-                return Err(SizeError::OutOfRange("width"));
+            if !(1..=MAX_GRID_WIDTH).contains(&parsed_width) {
+                return Err(SizeError::OutOfRange("width", MAX_GRID_WIDTH));
             }
-            if !(1..=MAX_GRID_SIZE).contains(&parsed_height) {
-                return Err(SizeError::OutOfRange("height"));
+            if !(1..=MAX_GRID_HEIGHT).contains(&parsed_height) {
+                return Err(SizeError::OutOfRange("height", MAX_GRID_HEIGHT));
             }
             return Ok(Some(Arg::GridSize(Size {
                 width: parsed_width,
@@ -64,20 +155,48 @@ fn parse_size(width_str: &str, height_str: &str) -> Result<Option<Arg>, SizeErro
             })));
         } else if util::is_numeric(height_str) {
             // A value >u16::MAX will not parse but might still be a number
-            return Err(SizeError::OutOfRange("height"));
+            return Err(SizeError::OutOfRange("height", MAX_GRID_HEIGHT));
         }
     } else if util::is_numeric(width_str) {
         // A value >u16::MAX will not parse but might still be a number
-        return Err(SizeError::OutOfRange("width"));
+        return Err(SizeError::OutOfRange("width", MAX_GRID_WIDTH));
     }
 
     Err(SizeError::FileNotFound)
 }
 
+fn parse_directory(name: String) -> Result<Arg, Cow<'static, str>> {
+    let mut paths = Vec::<PathBuf>::new();
+
+    for entry in fs::read_dir(&name).map_err(|_| "Directory reading error")? {
+        let path = entry.map_err(|_| "Directory reading error")?.path();
+
+        if path.is_file() && valid_extension(&path) {
+            paths.push(path);
+        }
+    }
+
+    if paths.is_empty() {
+        return Err(format!("Directory contains no \"{}\" files", FILE_EXTENSION).into());
+    }
+
+    paths.sort();
+
+    Ok(Arg::Directory { name, paths })
+}
+
 fn parse_strings(
     first_string: String,
     second_string: Option<String>,
 ) -> Result<Option<Arg>, Cow<'static, str>> {
+    if first_string == "-" {
+        return Ok(Some(Arg::Stdin));
+    }
+
+    if Path::new(&first_string).is_dir() {
+        return parse_directory(first_string).map(Some);
+    }
+
     // Check for a file first so that filenames consisting of numbers can be accepted too
     let mut open_options = fs::OpenOptions::new();
     open_options.read(true).write(true);
@@ -110,11 +229,9 @@ fn parse_strings(
 
                     match result {
                         Ok(size) => Ok(size),
-                        Err(SizeError::OutOfRange(thing)) => Err(format!(
-                            "Grid {} must be in range 1 to {}",
-                            thing, MAX_GRID_SIZE
-                        )
-                        .into()),
+                        Err(SizeError::OutOfRange(thing, max)) => {
+                            Err(format!("Grid {} must be in range 1 to {}", thing, max).into())
+                        }
                         Err(SizeError::FileNotFound) => Err("File not found".into()),
                     }
                 }
@@ -124,30 +241,135 @@ fn parse_strings(
     }
 }
 
-pub fn parse() -> Result<Option<Arg>, Cow<'static, str>> {
+pub fn parse_seconds(option: &str, seconds_str: &str) -> Result<Duration, Cow<'static, str>> {
+    // Values that are negative, not finite or too large for a `Duration` are rejected
+    seconds_str
+        .parse::<f64>()
+        .ok()
+        .and_then(|seconds| Duration::try_from_secs_f64(seconds).ok())
+        .ok_or_else(|| format!("{} must be a non-negative amount of seconds", option).into())
+}
+
+/// Separates the options from the other arguments and parses both.
+fn parse_args(
+    mut strings: impl Iterator<Item = String>,
+) -> Result<(Option<Arg>, Options), Cow<'static, str>> {
+    let mut options = Options::default();
+    let mut other_strings = Vec::<String>::new();
+    let mut stats_path: Option<String> = None;
+
+    while let Some(string) = strings.next() {
+        match string.as_str() {
+            "--alert-delay" => {
+                let value = strings.next().ok_or("--alert-delay requires a value")?;
+                options.alert_clear_delay = Some(parse_seconds(&string, &value)?);
+            }
+            "--export-svg" => {
+                let path = strings.next().ok_or("--export-svg requires a path")?;
+                options.export_svg_path = Some(path);
+            }
+            "--animate-fill" => options.animate_fill = Some(true),
+            "--colors" => {
+                let value = strings.next().ok_or("--colors requires a value")?;
+                let color_support = ColorSupport::parse(&value)
+                    .ok_or("--colors must be one of none, 16, 256 and truecolor")?;
+                options.color_support = Some(color_support);
+            }
+            "--no-color" => options.color_support = Some(ColorSupport::None),
+            "--color-blind" => {
+                let value = strings.next().ok_or("--color-blind requires a value")?;
+                let color_blind_mode = ColorBlindMode::parse(&value).ok_or(
+                    "--color-blind must be one of none, deuteranopia, protanopia and tritanopia",
+                )?;
+                options.color_blind_mode = Some(color_blind_mode);
+            }
+            "--zero-clues" => options.zero_clues = Some(true),
+            "--no-mouse" => options.mouse = Some(false),
+            "--high-contrast" => options.high_contrast = Some(true),
+            "--no-empty-lines" => options.empty_lines = Some(false),
+            "--bell" => options.bell = Some(true),
+            "--drag-overwrite" => options.drag_overwrite = Some(true),
+            "--show-mistakes" => options.show_mistakes = Some(true),
+            "--no-legend" => options.legend = Some(false),
+            "--auto-cross" => options.auto_cross = Some(true),
+            "--ascii" => options.ascii = Some(true),
+            "--show-cursor" => options.show_cursor = Some(true),
+            "--mode" => {
+                let value = strings.next().ok_or("--mode requires a value")?;
+                let mode = Mode::parse(&value)
+                    .ok_or("--mode must be one of normal, timeattack and zen")?;
+                options.mode = Some(mode);
+            }
+            "--difficulty" => {
+                let value = strings.next().ok_or("--difficulty requires a value")?;
+                let difficulty = Difficulty::parse(&value)
+                    .ok_or("--difficulty must be one of easy, medium and hard")?;
+                options.difficulty = Some(difficulty);
+            }
+            "--from-image" => {
+                let path = strings.next().ok_or("--from-image requires a path")?;
+                options.image_path = Some(path);
+            }
+            "--size" => {
+                let value = strings.next().ok_or("--size requires a value")?;
+                options.image_grid_width = match value.parse::<u16>() {
+                    Ok(width @ 1..=MAX_GRID_WIDTH) => Some(width),
+                    _ => {
+                        return Err(
+                            format!("--size must be in range 1 to {}", MAX_GRID_WIDTH).into()
+                        )
+                    }
+                };
+            }
+            "--threshold" => {
+                let value = strings.next().ok_or("--threshold requires a value")?;
+                let threshold = value
+                    .parse::<u8>()
+                    .map_err(|_| "--threshold must be in range 0 to 255")?;
+                options.image_threshold = Some(threshold);
+            }
+            "--stats" => {
+                let path = strings.next().ok_or("--stats requires a path")?;
+                stats_path = Some(path);
+            }
+            "--config" => {
+                let path = strings.next().ok_or("--config requires a path")?;
+                options.config_path = Some(path);
+            }
+            "--write-config" => options.write_config = true,
+            "--editor" => options.editor = true,
+            "--bot" => options.bot = true,
+            "--tutorial" => options.tutorial = true,
+            _ => other_strings.push(string),
+        }
+    }
+
+    let mut other_strings = other_strings.into_iter();
+
+    let arg = if let Some(path) = stats_path {
+        Some(Arg::Stats(path))
+    } else if let Some(first_string) = other_strings.next() {
+        parse_strings(first_string, other_strings.next())?
+    } else {
+        None
+    };
+
+    Ok((arg, options))
+}
+
+pub fn parse() -> Result<(Option<Arg>, Options), Cow<'static, str>> {
     // See https://github.com/rust-lang/rust/pull/84551#discussion_r620728070
     // on why it's better to use `env::args_os` than `env::args`.
     let mut args = env::args_os();
 
     args.next(); // This is usually the program name
 
-    if let Some(arg) = args.next() {
-        if let Ok(first_string) = arg.into_string() {
-            if let Some(arg) = args.next() {
-                if let Ok(second_string) = arg.into_string() {
-                    parse_strings(first_string, Some(second_string))
-                } else {
-                    Err("Second argument is not valid UTF-8".into())
-                }
-            } else {
-                parse_strings(first_string, None)
-            }
-        } else {
-            Err("First argument is not valid UTF-8".into())
-        }
-    } else {
-        Ok(None)
-    }
+    let strings = args
+        .map(|arg| arg.into_string())
+        .collect::<Result<Vec<String>, _>>()
+        .map_err(|_| "Argument is not valid UTF-8")?;
+
+    parse_args(strings.into_iter())
 }
 
 #[cfg(test)]
@@ -163,6 +385,169 @@ mod tests {
                 content: _
             }))
         ));
+        assert!(matches!(
+            parse_strings("-".into(), None),
+            Ok(Some(Arg::Stdin))
+        ));
+    }
+
+    #[test]
+    fn test_parse_directory() {
+        let directory = env::temp_dir().join("yayagram_test_parse_directory");
+        fs::create_dir_all(&directory).unwrap();
+        for filename in ["b.yaya", "a.yaya", "notes.txt"] {
+            fs::write(directory.join(filename), "").unwrap();
+        }
+
+        let name = directory.to_str().unwrap().to_string();
+        if let Ok(Some(Arg::Directory { paths, .. })) = parse_strings(name, None) {
+            assert_eq!(paths, [directory.join("a.yaya"), directory.join("b.yaya")]);
+        } else {
+            panic!("the directory was not recognized");
+        }
+
+        fs::remove_dir_all(&directory).unwrap();
+        fs::create_dir_all(&directory).unwrap();
+        let name = directory.to_str().unwrap().to_string();
+        assert!(parse_strings(name, None).is_err());
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn test_parse_args() {
+        fn parse(strings: &[&str]) -> Result<(Option<Arg>, Options), Cow<'static, str>> {
+            parse_args(strings.iter().map(|string| string.to_string()))
+        }
+
+        assert!(matches!(parse(&[]), Ok((None, _))));
+
+        let (arg, options) = parse(&["--alert-delay", "1.5", "10", "5"]).unwrap();
+        assert!(matches!(
+            arg,
+            Some(Arg::GridSize(Size {
+                width: 10,
+                height: 5
+            }))
+        ));
+        assert_eq!(options.alert_clear_delay, Some(Duration::from_millis(1500)));
+
+        let (arg, options) = parse(&["10", "--alert-delay", "0"]).unwrap();
+        assert!(matches!(
+            arg,
+            Some(Arg::GridSize(Size {
+                width: 10,
+                height: 10
+            }))
+        ));
+        assert_eq!(options.alert_clear_delay, Some(Duration::ZERO));
+
+        let (arg, options) = parse(&["--export-svg", "grid.svg", "example.yaya"]).unwrap();
+        assert!(matches!(arg, Some(Arg::File { .. })));
+        assert_eq!(options.export_svg_path.as_deref(), Some("grid.svg"));
+        assert_eq!(options.animate_fill, None);
+
+        let (arg, options) = parse(&["--animate-fill"]).unwrap();
+        assert!(arg.is_none());
+        assert_eq!(options.animate_fill, Some(true));
+
+        let (_, options) = parse(&[
+            "--from-image",
+            "a.pgm",
+            "--size",
+            "30",
+            "--threshold",
+            "100",
+        ])
+        .unwrap();
+        assert_eq!(options.image_path.as_deref(), Some("a.pgm"));
+        assert_eq!(options.image_grid_width, Some(30));
+        assert_eq!(options.image_threshold, Some(100));
+        assert!(parse(&["--size", "1000"]).is_err());
+        assert!(parse(&["--threshold", "256"]).is_err());
+
+        let (_, options) = parse(&["--colors", "16"]).unwrap();
+        assert_eq!(options.color_support, Some(ColorSupport::Ansi16));
+        assert!(parse(&["--colors"]).is_err());
+        assert!(parse(&["--colors", "many"]).is_err());
+        let (_, options) = parse(&["--no-color"]).unwrap();
+        assert_eq!(options.color_support, Some(ColorSupport::None));
+
+        let (_, options) = parse(&["--color-blind", "deuteranopia"]).unwrap();
+        assert_eq!(options.color_blind_mode, Some(ColorBlindMode::Deuteranopia));
+        assert!(parse(&["--color-blind"]).is_err());
+        assert!(parse(&["--color-blind", "red"]).is_err());
+
+        let (_, options) = parse(&[
+            "--mode",
+            "zen",
+            "--zero-clues",
+            "--no-mouse",
+            "--high-contrast",
+            "--no-empty-lines",
+            "--bell",
+            "--show-mistakes",
+            "--no-legend",
+            "--auto-cross",
+            "--ascii",
+            "--show-cursor",
+        ])
+        .unwrap();
+        assert_eq!(options.mode, Some(Mode::Zen));
+        assert_eq!(options.zero_clues, Some(true));
+        assert_eq!(options.mouse, Some(false));
+        assert_eq!(options.high_contrast, Some(true));
+        assert_eq!(options.empty_lines, Some(false));
+        assert_eq!(options.bell, Some(true));
+        assert_eq!(options.show_mistakes, Some(true));
+        assert_eq!(options.legend, Some(false));
+        assert_eq!(options.auto_cross, Some(true));
+        assert_eq!(options.ascii, Some(true));
+        assert_eq!(options.show_cursor, Some(true));
+
+        let (_, options) = parse(&["--difficulty", "hard"]).unwrap();
+        assert_eq!(options.difficulty, Some(Difficulty::Hard));
+        assert!(parse(&["--difficulty"]).is_err());
+        assert!(parse(&["--difficulty", "extreme"]).is_err());
+        assert!(parse(&["--mode"]).is_err());
+        assert!(parse(&["--mode", "hard"]).is_err());
+
+        let (_, options) = parse(&["--config", "yayagram.conf", "--write-config"]).unwrap();
+        assert_eq!(options.config_path.as_deref(), Some("yayagram.conf"));
+        assert!(options.write_config);
+        assert!(parse(&["--config"]).is_err());
+
+        let (arg, options) = parse(&["--bot", "example.yaya"]).unwrap();
+        assert!(matches!(arg, Some(Arg::File { .. })));
+        assert!(options.bot);
+
+        let (arg, options) = parse(&["--editor", "15", "10"]).unwrap();
+        assert!(matches!(
+            arg,
+            Some(Arg::GridSize(Size {
+                width: 15,
+                height: 10
+            }))
+        ));
+        assert!(options.editor);
+
+        let (arg, options) = parse(&["--tutorial"]).unwrap();
+        assert!(arg.is_none());
+        assert!(options.tutorial);
+
+        let (arg, _) = parse(&["--stats", "grid.yaya"]).unwrap();
+        assert!(matches!(arg, Some(Arg::Stats(path)) if path == "grid.yaya"));
+        assert!(parse(&["--stats"]).is_err());
+
+        assert!(parse(&["--alert-delay"]).is_err());
+        assert!(parse(&["--export-svg"]).is_err());
+        assert_eq!(
+            parse(&["--alert-delay", "-1"]).err().as_deref(),
+            Some("--alert-delay must be a non-negative amount of seconds")
+        );
+        assert!(parse(&["--alert-delay", "soon"]).is_err());
+        assert!(parse(&["--alert-delay", "1e30"]).is_err());
+        assert!(parse(&["--alert-delay", "inf"]).is_err());
     }
 
     #[test]
@@ -202,14 +587,24 @@ mod tests {
             })))
         ));
 
-        assert!(!matches!(
-            parse_size("100", "99"),
+        assert!(matches!(
+            parse_size("999", "99"),
             Ok(Some(Arg::GridSize(Size {
-                width: 100,
+                width: 999,
                 height: 99
             })))
         ));
 
+        assert!(matches!(
+            parse_size("1000", "99"),
+            Err(SizeError::OutOfRange("width", MAX_GRID_WIDTH))
+        ));
+
+        assert!(matches!(
+            parse_size("99", "100"),
+            Err(SizeError::OutOfRange("height", MAX_GRID_HEIGHT))
+        ));
+
         assert!(!matches!(
             parse_size("0", "0"),
             Ok(Some(Arg::GridSize(Size {
@@ -0,0 +1,93 @@
+//! Rings the terminal bell as audible feedback, for players that want it.
+
+use std::{
+    cell::Cell,
+    time::{Duration, Instant},
+};
+use terminal::Terminal;
+
+/// Bells within this duration after the last one are suppressed because bells quickly get annoying.
+const MIN_RING_INTERVAL: Duration = Duration::from_millis(500);
+
+/// The bell character, which makes the terminal play a sound or flash.
+///
+/// NOTE: this should be replaced with a `Terminal::bell` method once tanmatsu has one.
+const BELL: &str = "\u{7}";
+
+#[derive(Clone, Debug, Default)]
+pub struct Bell {
+    enabled: bool,
+    /// The time of when the bell last rang, which is kept in a `Cell` so that the bell can ring wherever the builder is borrowed.
+    last_ring_time: Cell<Option<Instant>>,
+}
+
+/// Checks whether the terminal of this `TERM` value is one where the bell is typically muted.
+fn is_muted_terminal(term: Option<&str>) -> bool {
+    term == Some("dumb")
+}
+
+impl Bell {
+    pub const fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            last_ring_time: Cell::new(None),
+        }
+    }
+
+    /// Creates a bell that is only enabled if the terminal doesn't typically mute it anyway.
+    pub fn for_terminal(enabled: bool) -> Self {
+        let term = std::env::var("TERM").ok();
+        Self::new(enabled && !is_muted_terminal(term.as_deref()))
+    }
+
+    /// Checks whether the bell rings at the given time, which is not the case if it is disabled or rang just before.
+    fn try_ring(&self, now: Instant) -> bool {
+        if !self.enabled {
+            return false;
+        }
+
+        if let Some(last_ring_time) = self.last_ring_time.get() {
+            if now.duration_since(last_ring_time) < MIN_RING_INTERVAL {
+                return false;
+            }
+        }
+
+        self.last_ring_time.set(Some(now));
+        true
+    }
+
+    /// Rings the bell if it is enabled and didn't ring just before.
+    pub fn ring(&self, terminal: &mut Terminal) {
+        if self.try_ring(Instant::now()) {
+            terminal.write(BELL);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_try_ring() {
+        let now = Instant::now();
+
+        let bell = Bell::new(false);
+        assert!(!bell.try_ring(now));
+
+        let bell = Bell::new(true);
+        assert!(bell.try_ring(now));
+        assert!(!bell.try_ring(now + Duration::from_millis(100)));
+        assert!(!bell.try_ring(now + Duration::from_millis(499)));
+        assert!(bell.try_ring(now + MIN_RING_INTERVAL));
+        // The interval starts again with every bell that rang
+        assert!(!bell.try_ring(now + Duration::from_millis(800)));
+    }
+
+    #[test]
+    fn test_is_muted_terminal() {
+        assert!(is_muted_terminal(Some("dumb")));
+        assert!(!is_muted_terminal(Some("xterm-256color")));
+        assert!(!is_muted_terminal(None));
+    }
+}
@@ -0,0 +1,149 @@
+//! Plays a grid by itself through the normal event handling, as fast as possible, to test and benchmark the game.
+
+use crate::{
+    event::source::Stats,
+    grid::{builder::Builder, Cell},
+};
+use std::{collections::VecDeque, fmt, time::Duration};
+use terminal::{
+    event::{Event, Key, MouseButton, MouseEvent, MouseEventKind},
+    util::Point,
+};
+
+/// What happened while the bot played.
+pub struct Report {
+    pub stats: Stats,
+    pub duration: Duration,
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Handled {} events with {} flushes in {:?}",
+            self.stats.event_count, self.stats.flush_count, self.duration
+        )
+    }
+}
+
+fn key(char: char) -> Event {
+    Event::Key(Key::Char(char))
+}
+
+fn mouse(kind: MouseEventKind, point: Point) -> Event {
+    Event::Mouse(MouseEvent { kind, point })
+}
+
+/// Gets the events that solve the grid with the given solution, starting from an empty grid.
+///
+/// Before that, every row is filled by dragging the mouse over it and undone again and the grid is flood-filled and undone again.
+/// The events also include a resize and some alerts.
+pub fn get_script(builder: &Builder, solution: &[Cell]) -> VecDeque<Event> {
+    let size = builder.grid.size;
+    let get_cursor_point = |x: u16, y: u16| builder.get_cell_screen_rect(Point { x, y }).0;
+
+    let mut events = VecDeque::<Event>::new();
+
+    events.push_back(Event::Resize);
+
+    // Enabling and disabling the eraser alerts both times
+    events.push_back(Event::Key(Key::Backspace));
+    events.push_back(Event::Key(Key::Backspace));
+
+    events.push_back(mouse(MouseEventKind::Move, get_cursor_point(0, 0)));
+
+    for y in 0..size.height {
+        events.push_back(mouse(
+            MouseEventKind::Press(MouseButton::Left),
+            get_cursor_point(0, y),
+        ));
+        for x in 1..size.width {
+            events.push_back(mouse(
+                MouseEventKind::Drag(MouseButton::Left),
+                get_cursor_point(x, y),
+            ));
+        }
+        events.push_back(mouse(
+            MouseEventKind::Release(MouseButton::Left),
+            get_cursor_point(size.width - 1, y),
+        ));
+
+        // Every cell is undone separately
+        for _ in 0..size.width {
+            events.push_back(key('a'));
+        }
+    }
+
+    // The rest is done with the keyboard so that no presses on the same cell are taken as a double-click
+    events.push_back(key('g'));
+
+    // Crossing out all cells with a flood fill
+    events.push_back(key('f'));
+    events.push_back(key('e'));
+    events.push_back(key('a'));
+
+    for (y, row) in solution.chunks(size.width as usize).enumerate() {
+        for (x, cell) in row.iter().enumerate() {
+            if *cell == Cell::Filled {
+                events.push_back(key('q'));
+            }
+            if x != row.len() - 1 {
+                events.push_back(Event::Key(Key::Right));
+            }
+        }
+        if y != size.height as usize - 1 {
+            events.push_back(key('^'));
+            events.push_back(Event::Key(Key::Down));
+        }
+    }
+
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::Settings,
+        event::{self, source::EventSource, State},
+        grid::{palette::Palette, Grid},
+    };
+    use terminal::Terminal;
+
+    #[test]
+    fn test_get_script() {
+        let stdout = std::io::stdout();
+        let mut terminal = Terminal::new(stdout.lock()).unwrap();
+
+        #[rustfmt::skip]
+        let grid = Grid::from_lines(&[
+            "1 1 ",
+            " 11 ",
+            "1111",
+        ]).unwrap();
+        let solution = grid.solution.clone().unwrap();
+        let settings = Settings::default();
+        let mut builder = Builder::new(&terminal, grid, Palette::default(), &settings);
+        let _ = builder.draw_all(&mut terminal);
+
+        let script = get_script(&builder, &solution);
+        let script_len = script.len();
+        let mut event_source = EventSource::scripted(script);
+
+        let state = event::r#loop(
+            &mut terminal,
+            &mut builder,
+            &settings,
+            false,
+            None,
+            false,
+            &mut event_source,
+        );
+
+        assert!(matches!(state, State::Solved(_)));
+        assert_eq!(builder.grid.cells, solution);
+        // The grid is solved by the last event
+        assert!(event_source.is_exhausted());
+        assert_eq!(event_source.take_stats().unwrap().event_count, script_len);
+    }
+}
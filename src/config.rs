@@ -0,0 +1,395 @@
+//! Loads the settings from a config file so that they don't have to be passed as arguments every time.
+//!
+//! The config file consists of `key = value` lines. Empty lines and lines starting with `#` are ignored.
+
+use crate::{
+    args,
+    grid::palette::{ColorBlindMode, ColorSupport},
+    mode::Mode,
+};
+use std::{
+    borrow::Cow,
+    env, fs, io,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+/// The settings that apply to every game, combined from the config file and the arguments.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Settings {
+    /// How long alerts are shown before they are cleared.
+    pub alert_clear_delay: Duration,
+    /// Whether flood-filled cells appear ring by ring instead of all at once.
+    pub animate_fill: bool,
+    /// How many colors to draw with. If not present, it is detected.
+    pub color_support: Option<ColorSupport>,
+    /// How the game is played.
+    pub mode: Mode,
+    /// Whether empty rows and columns have a single `0` clue instead of none.
+    pub zero_clues: bool,
+    /// Whether the mouse can be used.
+    pub mouse: bool,
+    /// Whether only colors that stand out strongly against each other are used.
+    pub high_contrast: bool,
+    /// Whether random grids may have rows and columns without any filled cells.
+    pub empty_lines: bool,
+    /// The kind of color blindness that the colors accommodate.
+    pub color_blind_mode: ColorBlindMode,
+    /// Whether the terminal bell rings when the grid is solved, when a row or column contradicts its clues and when a mistake is marked.
+    pub bell: bool,
+    /// Whether dragging with filled cells overwrites maybed and crossed out cells.
+    pub drag_overwrite: bool,
+    /// Whether placed cells that contradict the solution are marked.
+    pub show_mistakes: bool,
+    /// Whether a legend of the cells is shown below the basic controls help.
+    pub legend: bool,
+    /// Whether the empty cells of rows and columns that a placement solves are crossed out.
+    pub auto_cross: bool,
+    /// Whether only ASCII characters are drawn.
+    pub ascii: bool,
+    /// Whether the terminal cursor is shown on the selected cell.
+    pub show_cursor: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            alert_clear_delay: Duration::from_secs(3),
+            animate_fill: false,
+            color_support: None,
+            mode: Mode::Normal,
+            zero_clues: false,
+            mouse: true,
+            high_contrast: false,
+            empty_lines: true,
+            color_blind_mode: ColorBlindMode::None,
+            bell: false,
+            drag_overwrite: false,
+            show_mistakes: false,
+            legend: true,
+            auto_cross: false,
+            ascii: false,
+            show_cursor: false,
+        }
+    }
+}
+
+fn parse_bool(key: &str, value: &str) -> Result<bool, Cow<'static, str>> {
+    match value {
+        "true" => Ok(true),
+        "false" => Ok(false),
+        _ => Err(format!("{} must be true or false", key).into()),
+    }
+}
+
+impl Settings {
+    /// Parses the content of the config file with the given name.
+    ///
+    /// Invalid lines are skipped and returned as errors, keeping the defaults for them.
+    pub fn parse(name: &str, content: &str) -> (Self, Vec<String>) {
+        let mut settings = Self::default();
+        let mut errors = Vec::<String>::new();
+
+        for (index, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let result = if let Some((key, value)) = line.split_once('=') {
+                settings.set(key.trim(), value.trim())
+            } else {
+                Err("Expected key = value".into())
+            };
+
+            if let Err(err) = result {
+                errors.push(format!("{}:{}: {}", name, index + 1, err));
+            }
+        }
+
+        (settings, errors)
+    }
+
+    fn set(&mut self, key: &str, value: &str) -> Result<(), Cow<'static, str>> {
+        match key {
+            "alert_delay" => self.alert_clear_delay = args::parse_seconds(key, value)?,
+            "animate_fill" => self.animate_fill = parse_bool(key, value)?,
+            "zero_clues" => self.zero_clues = parse_bool(key, value)?,
+            "mouse" => self.mouse = parse_bool(key, value)?,
+            "high_contrast" => self.high_contrast = parse_bool(key, value)?,
+            "empty_lines" => self.empty_lines = parse_bool(key, value)?,
+            "bell" => self.bell = parse_bool(key, value)?,
+            "drag_overwrite" => self.drag_overwrite = parse_bool(key, value)?,
+            "show_mistakes" => self.show_mistakes = parse_bool(key, value)?,
+            "legend" => self.legend = parse_bool(key, value)?,
+            "auto_cross" => self.auto_cross = parse_bool(key, value)?,
+            "ascii" => self.ascii = parse_bool(key, value)?,
+            "show_cursor" => self.show_cursor = parse_bool(key, value)?,
+            "colors" => {
+                self.color_support = match value {
+                    "auto" => None,
+                    _ => Some(
+                        ColorSupport::parse(value)
+                            .ok_or("colors must be one of auto, none, 16, 256 and truecolor")?,
+                    ),
+                }
+            }
+            "color_blind" => {
+                self.color_blind_mode = ColorBlindMode::parse(value).ok_or(
+                    "color_blind must be one of none, deuteranopia, protanopia and tritanopia",
+                )?
+            }
+            "mode" => {
+                self.mode =
+                    Mode::parse(value).ok_or("mode must be one of normal, timeattack and zen")?
+            }
+            _ => return Err(format!("Unknown key \"{}\"", key).into()),
+        }
+
+        Ok(())
+    }
+
+    /// Overrides the settings with the ones given as arguments.
+    pub fn apply(&mut self, options: &args::Options) {
+        if let Some(alert_clear_delay) = options.alert_clear_delay {
+            self.alert_clear_delay = alert_clear_delay;
+        }
+        if let Some(animate_fill) = options.animate_fill {
+            self.animate_fill = animate_fill;
+        }
+        if let Some(color_support) = options.color_support {
+            self.color_support = Some(color_support);
+        }
+        if let Some(mode) = options.mode {
+            self.mode = mode;
+        }
+        if let Some(zero_clues) = options.zero_clues {
+            self.zero_clues = zero_clues;
+        }
+        if let Some(mouse) = options.mouse {
+            self.mouse = mouse;
+        }
+        if let Some(high_contrast) = options.high_contrast {
+            self.high_contrast = high_contrast;
+        }
+        if let Some(empty_lines) = options.empty_lines {
+            self.empty_lines = empty_lines;
+        }
+        if let Some(color_blind_mode) = options.color_blind_mode {
+            self.color_blind_mode = color_blind_mode;
+        }
+        if let Some(bell) = options.bell {
+            self.bell = bell;
+        }
+        if let Some(drag_overwrite) = options.drag_overwrite {
+            self.drag_overwrite = drag_overwrite;
+        }
+        if let Some(show_mistakes) = options.show_mistakes {
+            self.show_mistakes = show_mistakes;
+        }
+        if let Some(legend) = options.legend {
+            self.legend = legend;
+        }
+        if let Some(auto_cross) = options.auto_cross {
+            self.auto_cross = auto_cross;
+        }
+        if let Some(ascii) = options.ascii {
+            self.ascii = ascii;
+        }
+        if let Some(show_cursor) = options.show_cursor {
+            self.show_cursor = show_cursor;
+        }
+    }
+
+    /// Converts the settings to the content of a config file that parses to the same settings.
+    pub fn to_config(&self) -> String {
+        format!(
+            "alert_delay = {}\nanimate_fill = {}\ncolors = {}\nmode = {}\nzero_clues = {}\nmouse = {}\nhigh_contrast = {}\nempty_lines = {}\ncolor_blind = {}\nbell = {}\ndrag_overwrite = {}\nshow_mistakes = {}\nlegend = {}\nauto_cross = {}\nascii = {}\nshow_cursor = {}\n",
+            self.alert_clear_delay.as_secs_f64(),
+            self.animate_fill,
+            self.color_support.map_or("auto", ColorSupport::name),
+            self.mode.name(),
+            self.zero_clues,
+            self.mouse,
+            self.high_contrast,
+            self.empty_lines,
+            self.color_blind_mode.name(),
+            self.bell,
+            self.drag_overwrite,
+            self.show_mistakes,
+            self.legend,
+            self.auto_cross,
+            self.ascii,
+            self.show_cursor
+        )
+    }
+}
+
+/// Gets the directory of yayagram in the platform's config directory, if there is one.
+pub fn get_directory() -> Option<PathBuf> {
+    let config_directory = if cfg!(windows) {
+        PathBuf::from(env::var_os("APPDATA")?)
+    } else if let Some(config_home) = env::var_os("XDG_CONFIG_HOME").filter(|dir| !dir.is_empty()) {
+        PathBuf::from(config_home)
+    } else {
+        PathBuf::from(env::var_os("HOME")?).join(".config")
+    };
+
+    Some(config_directory.join("yayagram"))
+}
+
+/// Gets the path of the config file in the platform's config directory, if there is one.
+pub fn get_default_path() -> Option<PathBuf> {
+    Some(get_directory()?.join("config"))
+}
+
+/// Loads the settings from the config file at the path.
+///
+/// A missing config file is only an error if the path was given explicitly.
+pub fn load(path: &Path, explicit: bool) -> Result<(Settings, Vec<String>), Cow<'static, str>> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(err) if err.kind() == io::ErrorKind::NotFound && !explicit => {
+            return Ok((Settings::default(), Vec::new()))
+        }
+        Err(_) => return Err("Config file reading error".into()),
+    };
+
+    let name = path
+        .file_name()
+        .unwrap_or(path.as_os_str())
+        .to_string_lossy();
+
+    Ok(Settings::parse(&name, &content))
+}
+
+/// Writes the settings to the config file at the path, creating its directory if necessary.
+pub fn write(path: &Path, settings: &Settings) -> Result<(), Cow<'static, str>> {
+    if let Some(directory) = path.parent() {
+        fs::create_dir_all(directory).map_err(|_| "Config directory creation error")?;
+    }
+
+    fs::write(path, settings.to_config()).map_err(|_| "Config file writing error")?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let (settings, errors) = Settings::parse(
+            "config",
+            "# A comment\n\nalert_delay = 1.5\nanimate_fill=true\ncolors = 16\nmode = zen\n",
+        );
+        assert!(errors.is_empty());
+        assert_eq!(
+            settings,
+            Settings {
+                alert_clear_delay: Duration::from_millis(1500),
+                animate_fill: true,
+                color_support: Some(ColorSupport::Ansi16),
+                mode: Mode::Zen,
+                zero_clues: false,
+                mouse: true,
+                high_contrast: false,
+                empty_lines: true,
+                color_blind_mode: ColorBlindMode::None,
+                bell: false,
+                drag_overwrite: false,
+                show_mistakes: false,
+                legend: true,
+                auto_cross: false,
+                ascii: false,
+                show_cursor: false,
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let (settings, errors) = Settings::parse(
+            "config",
+            "theme = dark\nanimate_fill = yes\nalert_delay\ncolors = none\nalert_delay = 1e30\n",
+        );
+        assert_eq!(
+            errors,
+            [
+                "config:1: Unknown key \"theme\"",
+                "config:2: animate_fill must be true or false",
+                "config:3: Expected key = value",
+                "config:5: alert_delay must be a non-negative amount of seconds",
+            ]
+        );
+
+        // The valid lines still apply and the invalid ones keep their defaults
+        assert_eq!(
+            settings,
+            Settings {
+                color_support: Some(ColorSupport::None),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_to_config() {
+        for settings in [
+            Settings::default(),
+            Settings {
+                alert_clear_delay: Duration::from_millis(250),
+                animate_fill: true,
+                color_support: Some(ColorSupport::TrueColor),
+                mode: Mode::TimeAttack,
+                zero_clues: true,
+                mouse: false,
+                high_contrast: true,
+                empty_lines: false,
+                color_blind_mode: ColorBlindMode::Protanopia,
+                bell: true,
+                drag_overwrite: true,
+                show_mistakes: true,
+                legend: false,
+                auto_cross: true,
+                ascii: true,
+                show_cursor: true,
+            },
+        ] {
+            let (parsed_settings, errors) = Settings::parse("config", &settings.to_config());
+            assert!(errors.is_empty());
+            assert_eq!(parsed_settings, settings);
+        }
+    }
+
+    #[test]
+    fn test_apply() {
+        let (mut settings, _) = Settings::parse("config", "alert_delay = 5\ncolors = 256\n");
+        settings.apply(&args::Options {
+            alert_clear_delay: Some(Duration::from_secs(1)),
+            ..Default::default()
+        });
+
+        assert_eq!(settings.alert_clear_delay, Duration::from_secs(1));
+        assert_eq!(settings.color_support, Some(ColorSupport::Ansi256));
+    }
+
+    #[test]
+    fn test_load() {
+        let path = env::temp_dir().join("yayagram_test_load_config");
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(load(&path, false), Ok((Settings::default(), Vec::new())));
+        assert!(load(&path, true).is_err());
+
+        let settings = Settings {
+            animate_fill: true,
+            ..Default::default()
+        };
+        write(&path, &settings).unwrap();
+        assert_eq!(load(&path, true), Ok((settings, Vec::new())));
+
+        fs::remove_file(path).unwrap();
+    }
+}
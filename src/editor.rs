@@ -1,8 +1,9 @@
 use crate::{
     args::FILE_EXTENSION,
-    grid::{builder::Builder, Cell, Grid},
+    grid::{self, builder::Builder, Cell, Clue, Clues, Grid, Metadata},
     util,
 };
+use itertools::Itertools;
 use std::{
     fs,
     io::{self, Write},
@@ -10,11 +11,16 @@ use std::{
 };
 use terminal::util::Size;
 
+/// The window title while the editor is toggled.
+pub const WINDOW_TITLE: &str = "yayagram Editor";
+
 #[derive(Default)]
 pub struct Editor {
     pub toggled: bool,
     writer: Option<io::BufWriter<fs::File>>,
     pub filename: String,
+    /// The content of the file as it was last saved, if the grid was saved yet.
+    saved_content: Option<Vec<u8>>,
 }
 
 impl Editor {
@@ -22,8 +28,18 @@ impl Editor {
         self.toggled = !self.toggled;
     }
 
-    fn serialize(grid: &Grid, writer: &mut io::BufWriter<fs::File>) -> io::Result<()> {
-        fn write_dash_line(writer: &mut io::BufWriter<fs::File>, width: u16) -> io::Result<()> {
+    /// Sets the metadata of the grid that is saved along with it. An empty value removes the key.
+    pub fn set_metadata(grid: &mut Grid, key: &str, value: &str) {
+        let value = value.trim();
+        if value.is_empty() {
+            grid.metadata.remove(key);
+        } else {
+            grid.metadata.insert(key.to_string(), value.to_string());
+        }
+    }
+
+    fn serialize(grid: &Grid, writer: &mut impl Write) -> io::Result<()> {
+        fn write_dash_line(writer: &mut impl Write, width: u16) -> io::Result<()> {
             writer.write_all(b"+")?;
             for _ in 0..width {
                 writer.write_all(b"----")?;
@@ -35,6 +51,18 @@ impl Editor {
 
         let mut help: [Option<&str>; 4] = [None; 4];
 
+        for (key, value) in &grid.metadata {
+            writeln!(writer, "{}: {}", key, value)?;
+        }
+
+        // The clues only need to be recorded if they can't be derived from the picture
+        let picture_matches_clues = (0..grid.size.height).all(|y| grid.get_row_progress(y).solved)
+            && (0..grid.size.width).all(|x| grid.get_column_progress(x).solved);
+        if !picture_matches_clues {
+            write_clues_header(writer, ROWS_HEADER, &grid.horizontal_clues_solutions)?;
+            write_clues_header(writer, COLUMNS_HEADER, &grid.vertical_clues_solutions)?;
+        }
+
         write_dash_line(writer, grid.size.width)?;
 
         for cells in grid.cells.chunks(grid.size.width as usize) {
@@ -80,9 +108,7 @@ impl Editor {
     }
 
     #[allow(unstable_name_collisions)] // in the future `intersperse` will be in the std
-    fn write_help(writer: &mut io::BufWriter<fs::File>, help: [Option<&str>; 4]) -> io::Result<()> {
-        use itertools::Itertools;
-
+    fn write_help(writer: &mut impl Write, help: [Option<&str>; 4]) -> io::Result<()> {
         for part in help.iter().filter_map(|part| *part).intersperse(", ") {
             writer.write_all(part.as_bytes())?;
         }
@@ -91,27 +117,9 @@ impl Editor {
     }
 
     fn new_writer(&mut self, builder: &Builder) -> Result<io::BufWriter<fs::File>, &'static str> {
-        let mut open_options = fs::OpenOptions::new();
-        open_options.create_new(true).write(true);
-
-        let mut index = 1;
-        let file = loop {
-            self.filename = format!("grid-{}.{}", index, FILE_EXTENSION);
-            let file = open_options.open(&self.filename);
-            match file {
-                Err(err) => match err.kind() {
-                    io::ErrorKind::AlreadyExists => {
-                        if index == 9 {
-                            return Err("Too many grid files");
-                        }
-                        index += 1;
-                    }
-                    io::ErrorKind::PermissionDenied => return Err("Permission denied"),
-                    _ => return Err("File saving error"),
-                },
-                Ok(file) => break file,
-            }
-        };
+        let (filename, file) =
+            util::create_numbered_file("grid", FILE_EXTENSION, "Too many grid files")?;
+        self.filename = filename;
 
         let writer = io::BufWriter::with_capacity(builder.grid.size.product() as usize, file);
 
@@ -152,14 +160,56 @@ impl Editor {
             }
         };
 
-        if Self::serialize(&builder.grid, &mut writer).is_err() {
+        let mut content = Vec::new();
+        if Self::serialize(&builder.grid, &mut content).is_err()
+            || writer.write_all(&content).is_err()
+            || writer.flush().is_err()
+        {
             return Err("Save failed");
         }
 
         self.writer = Some(writer);
+        self.saved_content = Some(content);
 
         Ok(())
     }
+
+    /// Checks whether the grid changed since it was last saved or, if it wasn't saved yet, whether there is anything to save.
+    pub fn has_unsaved_changes(&self, grid: &Grid) -> bool {
+        if let Some(saved_content) = &self.saved_content {
+            let mut content = Vec::new();
+            // Writing to a `Vec` can't fail
+            let _ = Self::serialize(grid, &mut content);
+            content != *saved_content
+        } else {
+            !grid.is_blank() || !grid.metadata.is_empty()
+        }
+    }
+}
+
+/// The optional header line that records the horizontal clue solutions.
+const ROWS_HEADER: &str = "rows:";
+/// The optional header line that records the vertical clue solutions.
+const COLUMNS_HEADER: &str = "columns:";
+
+/// Writes a header line with the clue solutions, for example `rows: 2 1, 0, 3` where `0` stands for a line without clues.
+fn write_clues_header(
+    writer: &mut impl Write,
+    header: &str,
+    clues_solutions: &[Clues],
+) -> io::Result<()> {
+    let clues_solutions = clues_solutions
+        .iter()
+        .map(|clues_solution| {
+            if clues_solution.is_empty() {
+                "0".to_string()
+            } else {
+                clues_solution.iter().join(" ")
+            }
+        })
+        .join(", ");
+
+    writeln!(writer, "{} {}", header, clues_solutions)
 }
 
 pub struct LoadError {
@@ -167,13 +217,125 @@ pub struct LoadError {
     pub line_number: Option<usize>,
 }
 
-fn deserialize(str: &str) -> Result<(Size, Vec<Cell>), LoadError> {
-    let mut lines = str.lines();
+/// Parses the clues of a single line separated by spaces, where a single `0` stands for a line without clues.
+pub fn parse_clues(str: &str) -> Result<Clues, &'static str> {
+    let clues_solution = str
+        .split_whitespace()
+        .map(|clue| clue.parse::<Clue>())
+        .collect::<Result<Clues, _>>()
+        .map_err(|_| "expected clues separated by spaces")?;
+
+    match clues_solution.as_slice() {
+        [0] => Ok(Vec::new()),
+        [] => Err("expected at least one clue"),
+        clues_solution if clues_solution.contains(&0) => Err("only a single clue can be 0"),
+        _ => Ok(clues_solution),
+    }
+}
+
+fn parse_clues_header(str: &str, line_number: usize) -> Result<Vec<Clues>, LoadError> {
+    str.split(',')
+        .map(|clues_solution| {
+            parse_clues(clues_solution).map_err(|message| LoadError {
+                message,
+                line_number: Some(line_number),
+            })
+        })
+        .collect()
+}
+
+/// Checks that there are as many lines of clues as the grid has lines and that each line of clues fits into its line.
+fn validate_clues_header(
+    clues_solutions: &[Clues],
+    line_count: u16,
+    line_len: u16,
+    line_number: usize,
+) -> Result<(), LoadError> {
+    if clues_solutions.len() != line_count as usize {
+        return Err(LoadError {
+            message: "clue count does not match the grid size",
+            line_number: Some(line_number),
+        });
+    }
+
+    for clues_solution in clues_solutions {
+        if !grid::clues_fit(clues_solution, line_len) {
+            return Err(LoadError {
+                message: "clues do not fit into the grid",
+                line_number: Some(line_number),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// The horizontal and vertical clue solutions recorded in the header.
+type CluesHeader = (Vec<Clues>, Vec<Clues>);
+
+/// Parses a header line like `title: My Puzzle`, returning the key and the value.
+fn parse_metadata(line: &str) -> Option<(&str, &str)> {
+    let (key, value) = line.split_once(':')?;
+    let valid_key = !key.is_empty()
+        && key
+            .chars()
+            .all(|char| char.is_ascii_lowercase() || char == '_');
+
+    valid_key.then(|| (key, value.trim()))
+}
+
+/// Gets the lines together with their 1-based line numbers in the file.
+///
+/// Files edited on Windows might start with a byte order mark and end their lines with `\r\n`, which are both ignored.
+fn get_numbered_lines(str: &str) -> impl Iterator<Item = (usize, &str)> {
+    let str = str.strip_prefix('\u{feff}').unwrap_or(str);
+
+    str.lines()
+        .map(|line| line.trim_end_matches('\r'))
+        .zip(1..)
+        .map(|(line, line_number)| (line_number, line))
+}
+
+fn deserialize(str: &str) -> Result<(Size, Vec<Cell>, Option<CluesHeader>, Metadata), LoadError> {
+    let mut header_lines = 0;
+    let mut horizontal_clues_solutions = None;
+    let mut vertical_clues_solutions = None;
+    let mut metadata = Metadata::new();
+
+    for (_, line) in get_numbered_lines(str) {
+        if line.starts_with('+') {
+            break;
+        }
+        header_lines += 1;
+
+        if let Some(clues_solutions) = line.strip_prefix(ROWS_HEADER) {
+            horizontal_clues_solutions = Some((
+                parse_clues_header(clues_solutions, header_lines)?,
+                header_lines,
+            ));
+        } else if let Some(clues_solutions) = line.strip_prefix(COLUMNS_HEADER) {
+            vertical_clues_solutions = Some((
+                parse_clues_header(clues_solutions, header_lines)?,
+                header_lines,
+            ));
+        } else if line.starts_with('#') {
+            // A comment
+        } else if let Some((key, value)) = parse_metadata(line) {
+            metadata.insert(key.to_string(), value.to_string());
+        } else {
+            return Err(LoadError {
+                message: "expected a clue header, metadata or '+' at start of line",
+                line_number: Some(header_lines),
+            });
+        }
+    }
+
+    let mut lines = get_numbered_lines(str).skip(header_lines);
 
     // Skip dash line
     lines.next().ok_or(LoadError {
         message: "expected line",
-        line_number: Some(1),
+        line_number: Some(header_lines + 1),
     })?;
 
     let mut cells = Vec::<Cell>::new();
@@ -181,7 +343,7 @@ fn deserialize(str: &str) -> Result<(Size, Vec<Cell>), LoadError> {
     let mut width: Option<u16> = None;
     let mut height: Option<u16> = None;
 
-    for (index, line) in lines.step_by(2).enumerate() {
+    for (line_number, line) in lines.step_by(2) {
         let mut chars = line.chars();
 
         match chars.next() {
@@ -190,7 +352,7 @@ fn deserialize(str: &str) -> Result<(Size, Vec<Cell>), LoadError> {
             _ => {
                 return Err(LoadError {
                     message: "expected '|' or '+' at start of line",
-                    line_number: Some(index),
+                    line_number: Some(line_number),
                 })
             }
         }
@@ -210,7 +372,7 @@ fn deserialize(str: &str) -> Result<(Size, Vec<Cell>), LoadError> {
                 _ => {
                     return Err(LoadError {
                         message: "expected ' ', '1', 'X', '?' or 'R'",
-                        line_number: Some(index),
+                        line_number: Some(line_number),
                     })
                 }
             };
@@ -226,7 +388,7 @@ fn deserialize(str: &str) -> Result<(Size, Vec<Cell>), LoadError> {
         if width.is_none() {
             width = Some(line_width.ok_or(LoadError {
                 message: "no width",
-                line_number: Some(index),
+                line_number: Some(line_number),
             })?);
         }
 
@@ -246,10 +408,263 @@ fn deserialize(str: &str) -> Result<(Size, Vec<Cell>), LoadError> {
         line_number: None,
     })?;
 
-    Ok((Size { width, height }, cells))
+    let clues_header = match (horizontal_clues_solutions, vertical_clues_solutions) {
+        (
+            Some((horizontal_clues_solutions, rows_line_number)),
+            Some((vertical_clues_solutions, columns_line_number)),
+        ) => {
+            validate_clues_header(&horizontal_clues_solutions, height, width, rows_line_number)?;
+            validate_clues_header(
+                &vertical_clues_solutions,
+                width,
+                height,
+                columns_line_number,
+            )?;
+            Some((horizontal_clues_solutions, vertical_clues_solutions))
+        }
+        (None, None) => None,
+        _ => {
+            return Err(LoadError {
+                message: "expected both row and column clues",
+                line_number: Some(1),
+            })
+        }
+    };
+
+    Ok((Size { width, height }, cells, clues_header, metadata))
 }
 
 pub fn load_grid(file_content: &str) -> Result<Grid, LoadError> {
-    let (size, cells) = deserialize(file_content)?;
-    Ok(Grid::new(size, cells))
+    let (size, cells, clues_header, metadata) = deserialize(file_content)?;
+
+    let grid = if let Some((horizontal_clues_solutions, vertical_clues_solutions)) = clues_header {
+        Grid::with_clues_solutions(
+            size,
+            cells,
+            horizontal_clues_solutions,
+            vertical_clues_solutions,
+        )
+    } else {
+        Grid::new(size, cells)
+    };
+    let mut grid = grid.map_err(|err| LoadError {
+        message: err.message(),
+        line_number: None,
+    })?;
+    grid.metadata = metadata;
+
+    Ok(grid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_grid_with_clues_header() {
+        let grid = load_grid(concat!(
+            "rows: 2, 0, 1 1\n",
+            "columns: 1, 1 1, 1\n",
+            "+------------+\n",
+            "|            |\n",
+            "|            |\n",
+            "|            |\n",
+            "|            |\n",
+            "|            |\n",
+            "|            |\n",
+            "+------------+\n",
+        ))
+        .ok()
+        .unwrap();
+
+        assert_eq!(
+            grid.horizontal_clues_solutions,
+            [vec![2], vec![], vec![1, 1]]
+        );
+        assert_eq!(
+            grid.vertical_clues_solutions,
+            [vec![1], vec![1, 1], vec![1]]
+        );
+    }
+
+    #[test]
+    fn test_load_grid_with_invalid_clues_header() {
+        let picture = concat!(
+            "+--------+\n",
+            "|        |\n",
+            "|        |\n",
+            "|        |\n",
+            "|        |\n",
+            "+--------+\n",
+        );
+        let load = |header: &str| load_grid(&format!("{}{}", header, picture)).err().unwrap();
+
+        let err = load("rows: 1\ncolumns: 1, 1\n");
+        assert_eq!(err.message, "clue count does not match the grid size");
+        assert_eq!(err.line_number, Some(1));
+
+        let err = load("rows: 1 1, 1\ncolumns: 1, 1\n");
+        assert_eq!(err.message, "clues do not fit into the grid");
+
+        let err = load("rows: 1\n");
+        assert_eq!(err.message, "expected both row and column clues");
+
+        let err = load("rows: 1, a\ncolumns: 1, 1\n");
+        assert_eq!(err.message, "expected clues separated by spaces");
+
+        let err = load("rows: 1, 1\ncolumns: 1, 0 1\n");
+        assert_eq!(err.message, "only a single clue can be 0");
+        assert_eq!(err.line_number, Some(2));
+    }
+
+    #[test]
+    fn test_metadata() {
+        let content = concat!(
+            "# Drawn by hand\n",
+            "title: A cat\n",
+            "author:   Alice  \n",
+            "+----+\n",
+            "|1111|\n",
+            "|1111|\n",
+            "+----+\n",
+        );
+        let mut grid = load_grid(content).ok().unwrap();
+        assert_eq!(grid.metadata.len(), 2);
+        assert_eq!(grid.metadata["title"], "A cat");
+        assert_eq!(grid.metadata["author"], "Alice");
+
+        Editor::set_metadata(&mut grid, "title", " A dog ");
+        Editor::set_metadata(&mut grid, "author", "");
+        // Otherwise the clues would be saved as well
+        grid.cells = vec![Cell::Filled];
+
+        let mut content = Vec::<u8>::new();
+        Editor::serialize(&grid, &mut content).unwrap();
+        let content = String::from_utf8(content).unwrap();
+        assert!(content.starts_with("title: A dog\n+"));
+
+        let loaded_grid = load_grid(&content).ok().unwrap();
+        assert_eq!(loaded_grid.metadata, grid.metadata);
+        assert_eq!(loaded_grid.solution, Some(vec![Cell::Filled]));
+
+        let err = load_grid("Title: A cat\n+----+\n|1111|\n|1111|\n+----+\n")
+            .err()
+            .unwrap();
+        assert_eq!(
+            err.message,
+            "expected a clue header, metadata or '+' at start of line"
+        );
+    }
+
+    #[test]
+    fn test_load_grid_from_windows() {
+        let content = concat!(
+            "rows: 1, 0\n",
+            "columns: 1, 0\n",
+            "+--------+\n",
+            "|1111    |\n",
+            "|1111    |\n",
+            "|        |\n",
+            "|        |\n",
+            "+--------+\n",
+            "\n",
+            "1: filled",
+        );
+        let crlf_content = content.replace('\n', "\r\n");
+        let bom_content = format!("\u{feff}{}", content);
+        let bom_crlf_content = format!("\u{feff}{}", crlf_content);
+
+        let grid = load_grid(content).ok().unwrap();
+        for content in [crlf_content, bom_content, bom_crlf_content] {
+            let loaded_grid = load_grid(&content).ok().unwrap();
+            assert_eq!(loaded_grid.size, grid.size);
+            assert_eq!(loaded_grid.solution, grid.solution);
+            assert_eq!(
+                loaded_grid.horizontal_clues_solutions,
+                grid.horizontal_clues_solutions
+            );
+            assert_eq!(
+                loaded_grid.vertical_clues_solutions,
+                grid.vertical_clues_solutions
+            );
+        }
+    }
+
+    #[test]
+    fn test_load_grid_error_line_number() {
+        let err = load_grid(concat!(
+            "+--------+\r\n",
+            "|1111    |\r\n",
+            "|1111    |\r\n",
+            "|    AAAA|\r\n",
+            "|    AAAA|\r\n",
+            "+--------+\r\n",
+        ))
+        .err()
+        .unwrap();
+        assert_eq!(err.message, "expected ' ', '1', 'X', '?' or 'R'");
+        assert_eq!(err.line_number, Some(4));
+
+        let err = load_grid("\u{feff}rows: 1\r\ncolumns: 1\r\n+----+\r\n    |\r\n")
+            .err()
+            .unwrap();
+        assert_eq!(err.message, "expected '|' or '+' at start of line");
+        assert_eq!(err.line_number, Some(4));
+    }
+
+    #[test]
+    fn test_serialize_clues_header() {
+        let size = Size {
+            width: 2,
+            height: 1,
+        };
+        let grid = Grid::from_clues(size, vec![vec![2]], vec![vec![1], vec![1]]).unwrap();
+
+        let mut content = Vec::<u8>::new();
+        Editor::serialize(&grid, &mut content).unwrap();
+        let content = String::from_utf8(content).unwrap();
+        assert!(content.starts_with("rows: 2\ncolumns: 1, 1\n+"));
+
+        let loaded_grid = load_grid(&content).ok().unwrap();
+        assert_eq!(
+            loaded_grid.horizontal_clues_solutions,
+            grid.horizontal_clues_solutions
+        );
+        assert_eq!(
+            loaded_grid.vertical_clues_solutions,
+            grid.vertical_clues_solutions
+        );
+
+        // Pictures that match their clues are saved without the header
+        let mut grid = Grid::new(size, vec![Cell::Filled; 2]).unwrap();
+        grid.cells = vec![Cell::Filled; 2];
+        let mut content = Vec::<u8>::new();
+        Editor::serialize(&grid, &mut content).unwrap();
+        assert!(content.starts_with(b"+"));
+    }
+
+    #[test]
+    fn test_has_unsaved_changes() {
+        let size = Size {
+            width: 2,
+            height: 1,
+        };
+        let mut grid = Grid::new(size, vec![Cell::Empty; 2]).unwrap();
+        let mut editor = Editor::default();
+
+        // There is nothing to save yet
+        assert!(!editor.has_unsaved_changes(&grid));
+        Editor::set_metadata(&mut grid, "title", "Dot");
+        assert!(editor.has_unsaved_changes(&grid));
+
+        let mut content = Vec::new();
+        Editor::serialize(&grid, &mut content).unwrap();
+        editor.saved_content = Some(content);
+        assert!(!editor.has_unsaved_changes(&grid));
+
+        grid.cells[0] = Cell::Filled;
+        assert!(editor.has_unsaved_changes(&grid));
+        grid.cells[0] = Cell::Empty;
+        assert!(!editor.has_unsaved_changes(&grid));
+    }
 }
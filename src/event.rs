@@ -1,11 +1,19 @@
 mod alert;
 pub mod input;
+pub mod source;
 
 use crate::{
+    config::Settings,
     editor::{self, Editor},
     event::{self, input::window},
-    grid::{builder::Builder, CellPlacement, Grid},
+    grid::{self, builder::Builder, CellPlacement, Grid},
+    tutorial::TutorialState,
+    undo_redo_buffer,
+    window_title::WindowTitle,
 };
+pub use alert::AlertSeverity;
+use alert::{Alert, AlertHistory};
+use source::EventSource;
 use std::{
     borrow::Cow,
     fs, path,
@@ -20,33 +28,223 @@ pub enum State {
     /// The grid has been solved.
     /// The duration specifies how long it took to solve the grid.
     Solved(Duration),
+    /// Ask whether to clear the grid for editing and then enable the editor.
+    EnableEditor,
+    /// Let the editor enter clues directly.
+    EnterClues,
+    /// Let the editor enter the title of the grid.
+    EnterTitle,
+    /// Let the player enter the step of the undo history to jump to.
+    EnterStep,
+    /// Show the latest alerts.
+    ShowAlertHistory,
+    /// Reveal the solution after the player confirmed it.
+    Reveal,
+    /// The player gave up and the solution was revealed.
+    Revealed,
+    /// The time limit of time attack was exceeded before the grid was solved.
+    TimedOut,
     /// Display an alert. Alerts are cleared after some time.
     ///
     /// NOTE: alert messages do not end in a period.
-    Alert(Cow<'static, str>),
+    Alert(Cow<'static, str>, AlertSeverity),
     /// Clear the alert if present.
     ClearAlert,
+    /// The space needed by the grid changed so everything has to be positioned and drawn again.
+    Relayout,
     /// Halt the game to load a new grid.
     LoadGrid,
+    /// End this game and start a new one with the given grid.
+    NewGrid(Box<Grid>),
+    /// End this game and play the next grid.
+    NextGrid,
     /// Exit the program.
     /// Once the state is evaluated, the instant is immediately converted to a duration which determines whether an exit confirmation prompt needs to be shown.
     Exit(Option<Instant>),
 }
 
-pub fn r#loop(terminal: &mut Terminal, builder: &mut Builder) -> State {
+/// How long to wait for input before doing periodic work like clearing alerts.
+const POLL_TIMEOUT: Duration = Duration::from_millis(250);
+
+/// An input flow that takes over all input until it is done, such as a prompt.
+#[derive(Clone, Copy)]
+enum Modal {
+    /// A grid file is awaited to be dropped onto the window.
+    LoadGrid,
+    /// An action is awaited to be confirmed.
+    Confirmation,
+}
+
+impl Modal {
+    /// Gets the instructions that replace the basic controls help while the modal is active.
+    const fn get_help(self) -> &'static [&'static str] {
+        match self {
+            Self::LoadGrid => &["Backspace: Erase", "Esc: Cancel"],
+            Self::Confirmation => &["Enter: Confirm", "Esc: Cancel"],
+        }
+    }
+
+    fn enter(self, terminal: &mut Terminal, builder: &Builder) {
+        crate::clear_bottom_help(terminal, builder, crate::BASIC_CONTROLS_HELP);
+        crate::draw_bottom_help(terminal, builder, self.get_help());
+    }
+
+    /// Restores the basic controls help.
+    ///
+    /// The cell being placed is forgotten because the mouse button might have been released while the modal was active.
+    fn leave(self, terminal: &mut Terminal, builder: &Builder, cell_placement: &mut CellPlacement) {
+        crate::clear_bottom_help(terminal, builder, self.get_help());
+        crate::draw_bottom_help(terminal, builder, crate::BASIC_CONTROLS_HELP);
+        cell_placement.cell = None;
+    }
+}
+
+/// Asks the player to confirm the given verb and returns whether the player confirmed it.
+fn confirm(
+    terminal: &mut Terminal,
+    builder: &Builder,
+    alert: &mut Option<Alert>,
+    cell_placement: &mut CellPlacement,
+    verb_to_confirm: &str,
+) -> bool {
+    Modal::Confirmation.enter(terminal, builder);
+    let confirmed = window::confirmation_prompt(terminal, builder, alert, verb_to_confirm);
+    Modal::Confirmation.leave(terminal, builder, cell_placement);
+
+    confirmed
+}
+
+/// Draws the alert and records it in the history.
+fn draw_alert(
+    terminal: &mut Terminal,
+    builder: &Builder,
+    alert: &mut Option<Alert>,
+    alert_history: &mut AlertHistory,
+    message: Cow<'static, str>,
+    severity: AlertSeverity,
+) {
+    alert_history.push(&message);
+    alert::draw(terminal, builder, alert, message, severity);
+}
+
+/// Flushes the terminal, with the terminal cursor on the selected cell if it is to be shown.
+fn flush(
+    terminal: &mut Terminal,
+    builder: &Builder,
+    cell_placement: &CellPlacement,
+    event_source: &mut EventSource,
+) {
+    grid::place_terminal_cursor(terminal, builder, cell_placement);
+    event_source.flush(terminal);
+}
+
+/// Runs the game until it ends, starting in the editor if `editor_toggled` is `true` and with the given warning if present.
+///
+/// If `tutorial` is `true`, the steps of the tutorial are shown one after another and once they are done,
+/// the game ends with [`State::NextGrid`].
+///
+/// The game ends with [`State::Exit`] once the event source is exhausted.
+pub fn r#loop(
+    terminal: &mut Terminal,
+    builder: &mut Builder,
+    settings: &Settings,
+    editor_toggled: bool,
+    alert_message: Option<Cow<'static, str>>,
+    tutorial: bool,
+    event_source: &mut EventSource,
+) -> State {
     let mut editor = Editor::default();
+    editor.toggled = editor_toggled;
 
     let mut alert = None;
+    let mut alert_history = AlertHistory::default();
+    if let Some(alert_message) = alert_message {
+        draw_alert(
+            terminal,
+            builder,
+            &mut alert,
+            &mut alert_history,
+            alert_message,
+            AlertSeverity::Warning,
+        );
+        event_source.flush(terminal);
+    }
 
-    let mut cell_placement = CellPlacement::default();
+    let mut tutorial_state = tutorial.then_some(TutorialState::Clues);
+
+    let mut cell_placement = CellPlacement {
+        animate_fill: settings.animate_fill,
+        drag_overwrite: settings.drag_overwrite,
+        show_mistakes: settings.show_mistakes,
+        auto_cross: settings.auto_cross,
+        show_cursor: settings.show_cursor,
+        ..Default::default()
+    };
+
+    let starting_time = Instant::now();
+    let time_limit = builder.mode.get_time_limit(builder.grid.size);
+
+    let mut window_title = WindowTitle::default();
+    let unsaved = editor.toggled && editor.has_unsaved_changes(&builder.grid);
+    window_title.set(terminal, builder, editor.toggled, unsaved);
+    let mut title_editor_toggled = editor.toggled;
 
     loop {
-        if let Some(event) = terminal.read_event() {
-            // The order of statements matters
+        let event = event_source.poll(terminal, POLL_TIMEOUT);
+        if event.is_none() && event_source.is_exhausted() {
+            break State::Exit(None);
+        }
 
-            alert::handle_clear_delay(terminal, builder, &mut alert);
+        // The order of statements matters
 
-            let state = input::handle(
+        if let Some(time_limit) = time_limit {
+            if let Some(remaining_time) = time_limit.checked_sub(starting_time.elapsed()) {
+                // This is redrawn every time because a relayout might have cleared it
+                crate::draw_countdown(terminal, builder, remaining_time);
+            } else {
+                break State::TimedOut;
+            }
+        }
+
+        // The message of the tutorial step stays until the step is done
+        let shows_tutorial_message = tutorial_state.is_some_and(|tutorial_state| {
+            alert
+                .as_ref()
+                .is_some_and(|alert| alert.message == tutorial_state.get_message())
+        });
+        let alert_cleared = !shows_tutorial_message
+            && alert::handle_clear_delay(terminal, builder, &mut alert, settings.alert_clear_delay);
+
+        if let Some(tutorial_state) = &mut tutorial_state {
+            let advanced = tutorial_state.advance(builder, &cell_placement);
+            if *tutorial_state == TutorialState::Completed {
+                break State::NextGrid;
+            }
+            // Another alert might have replaced the message and been cleared
+            if advanced || alert.is_none() {
+                draw_alert(
+                    terminal,
+                    builder,
+                    &mut alert,
+                    &mut alert_history,
+                    tutorial_state.get_message().into(),
+                    AlertSeverity::Info,
+                );
+                flush(terminal, builder, &cell_placement, event_source);
+            }
+        }
+
+        if editor.toggled == title_editor_toggled {
+            window_title.update_progress(terminal, builder, &editor);
+        } else {
+            // The grid might have been edited
+            let unsaved = editor.toggled && editor.has_unsaved_changes(&builder.grid);
+            window_title.set(terminal, builder, editor.toggled, unsaved);
+            title_editor_toggled = editor.toggled;
+        }
+
+        if let Some(event) = event {
+            let mut state = input::handle(
                 terminal,
                 event,
                 builder,
@@ -55,26 +253,46 @@ pub fn r#loop(terminal: &mut Terminal, builder: &mut Builder) -> State {
                 &mut cell_placement,
             );
 
+            if let State::Relayout = state {
+                state = window::relayout(terminal, builder, &alert, &mut cell_placement);
+            }
+
+            if let State::Continue | State::Alert(..) | State::ClearAlert = state {
+                // Redrawing the grid removes the mark
+                grid::draw_keyboard_cursor(terminal, builder, &cell_placement);
+            }
+
             match state {
                 State::Continue => {
-                    terminal.flush();
+                    flush(terminal, builder, &cell_placement, event_source);
                     continue;
                 }
-                State::Alert(alert_message) => {
-                    alert::draw(terminal, builder, &mut alert, alert_message);
-                    terminal.flush();
+                State::Alert(alert_message, severity) => {
+                    draw_alert(
+                        terminal,
+                        builder,
+                        &mut alert,
+                        &mut alert_history,
+                        alert_message,
+                        severity,
+                    );
+                    flush(terminal, builder, &cell_placement, event_source);
                 }
                 State::ClearAlert => {
                     if let Some(mut alert_to_clear) = alert {
                         alert_to_clear.clear(terminal, builder);
                         alert = None;
                     }
-                    terminal.flush();
+                    flush(terminal, builder, &cell_placement, event_source);
                 }
                 State::LoadGrid => {
-                    match event::input::window::await_dropped_grid_file_path(
+                    Modal::LoadGrid.enter(terminal, builder);
+                    let path = event::input::window::await_dropped_grid_file_path(
                         terminal, builder, &mut alert,
-                    ) {
+                    );
+                    Modal::LoadGrid.leave(terminal, builder, &mut cell_placement);
+
+                    match path {
                         Ok(path) => {
                             fn load(path: &str) -> Option<Grid> {
                                 let content = fs::read_to_string(path).ok()?;
@@ -84,13 +302,7 @@ pub fn r#loop(terminal: &mut Terminal, builder: &mut Builder) -> State {
                             }
 
                             if let Some(grid) = load(&path) {
-                                // Currently the new game simply runs inside of this existing game and the new game creates an entirely new state.
-                                // At some point we would probably hit a stack overflow if the user keeps loading new grid files within the same session.
-
-                                terminal.clear();
-                                crate::start_game(terminal, grid);
-
-                                break State::Exit(None);
+                                break State::NewGrid(Box::new(grid));
                             } else {
                                 let err = if !path.contains(path::MAIN_SEPARATOR) {
                                     // The user likely dropped a grid file onto the window without having pressed
@@ -99,40 +311,313 @@ pub fn r#loop(terminal: &mut Terminal, builder: &mut Builder) -> State {
                                 } else {
                                     "Loading failed"
                                 };
-                                alert::draw(terminal, builder, &mut alert, err.into());
-                                terminal.flush();
+                                draw_alert(
+                                    terminal,
+                                    builder,
+                                    &mut alert,
+                                    &mut alert_history,
+                                    err.into(),
+                                    AlertSeverity::Error,
+                                );
+                                flush(terminal, builder, &cell_placement, event_source);
                             }
                         }
                         Err(err) => {
-                            alert::draw(terminal, builder, &mut alert, err.into());
-                            terminal.flush();
+                            draw_alert(
+                                terminal,
+                                builder,
+                                &mut alert,
+                                &mut alert_history,
+                                err.into(),
+                                AlertSeverity::Warning,
+                            );
+                            flush(terminal, builder, &cell_placement, event_source);
+                        }
+                    }
+                }
+                State::EnableEditor => {
+                    let confirmed = confirm(
+                        terminal,
+                        builder,
+                        &mut alert,
+                        &mut cell_placement,
+                        "start from an empty grid",
+                    );
+
+                    if confirmed {
+                        builder.grid.clear_for_editing();
+
+                        // The clues take up less space now
+                        if let State::Exit(_) =
+                            window::relayout(terminal, builder, &alert, &mut cell_placement)
+                        {
+                            return State::Exit(None);
+                        }
+                    }
+
+                    if let State::Alert(alert_message, severity) =
+                        input::key::toggle_editor(builder, &mut editor, &mut cell_placement)
+                    {
+                        draw_alert(
+                            terminal,
+                            builder,
+                            &mut alert,
+                            &mut alert_history,
+                            alert_message,
+                            severity,
+                        );
+                    }
+                    builder.refresh_difficulty_label(terminal, editor.toggled);
+                    flush(terminal, builder, &cell_placement, event_source);
+                }
+                State::ShowAlertHistory => {
+                    if alert_history.is_empty() {
+                        // This is not recorded so that the history stays empty
+                        alert::draw(
+                            terminal,
+                            builder,
+                            &mut alert,
+                            "No alerts yet".into(),
+                            AlertSeverity::Info,
+                        );
+                        flush(terminal, builder, &cell_placement, event_source);
+                    } else if let State::Exit(_) = input::alert_history::show(
+                        terminal,
+                        builder,
+                        &alert,
+                        &mut cell_placement,
+                        &alert_history,
+                    ) {
+                        return State::Exit(None);
+                    }
+                }
+                State::EnterClues => {
+                    match input::clue_entry::enter_clues(
+                        terminal,
+                        builder,
+                        &mut alert,
+                        &mut cell_placement,
+                    ) {
+                        State::Alert(alert_message, severity) => {
+                            draw_alert(
+                                terminal,
+                                builder,
+                                &mut alert,
+                                &mut alert_history,
+                                alert_message,
+                                severity,
+                            );
+                        }
+                        State::Exit(_) => return State::Exit(None),
+                        _ => {}
+                    }
+                    flush(terminal, builder, &cell_placement, event_source);
+                }
+                State::EnterTitle => {
+                    match input::title_entry::enter_title(
+                        terminal,
+                        builder,
+                        &mut alert,
+                        &mut cell_placement,
+                    ) {
+                        State::Alert(alert_message, severity) => {
+                            draw_alert(
+                                terminal,
+                                builder,
+                                &mut alert,
+                                &mut alert_history,
+                                alert_message,
+                                severity,
+                            );
+                        }
+                        State::Exit(_) => return State::Exit(None),
+                        _ => {}
+                    }
+                    flush(terminal, builder, &cell_placement, event_source);
+                }
+                State::EnterStep => {
+                    match input::step_entry::enter_step(
+                        terminal,
+                        builder,
+                        &mut alert,
+                        &mut cell_placement,
+                    ) {
+                        State::Alert(alert_message, severity) => {
+                            draw_alert(
+                                terminal,
+                                builder,
+                                &mut alert,
+                                &mut alert_history,
+                                alert_message,
+                                severity,
+                            );
                         }
+                        State::Exit(_) => return State::Exit(None),
+                        _ => {}
                     }
+                    // The grid might be at another step now
+                    builder.refresh_difficulty_label(terminal, editor.toggled);
+                    flush(terminal, builder, &cell_placement, event_source);
                 }
-                State::Solved(_) => break state,
+                State::Reveal => {
+                    let confirmed = confirm(
+                        terminal,
+                        builder,
+                        &mut alert,
+                        &mut cell_placement,
+                        "reveal the solution",
+                    );
+
+                    if !confirmed {
+                        draw_alert(
+                            terminal,
+                            builder,
+                            &mut alert,
+                            &mut alert_history,
+                            "Canceled".into(),
+                            AlertSeverity::Info,
+                        );
+                        flush(terminal, builder, &cell_placement, event_source);
+                    } else if builder.grid.reveal() {
+                        builder
+                            .grid
+                            .undo_redo_buffer
+                            .push(undo_redo_buffer::Operation::Reveal);
+
+                        #[allow(unused_must_use)]
+                        {
+                            builder.draw_all(terminal);
+                        }
+                        // Nothing is left to decide
+                        builder.clear_difficulty_label(terminal);
+
+                        break State::Revealed;
+                    } else {
+                        draw_alert(
+                            terminal,
+                            builder,
+                            &mut alert,
+                            &mut alert_history,
+                            "No solution found".into(),
+                            AlertSeverity::Error,
+                        );
+                        flush(terminal, builder, &cell_placement, event_source);
+                    }
+                }
+                State::Solved(_)
+                | State::Revealed
+                | State::TimedOut
+                | State::NewGrid(_)
+                | State::NextGrid => break state,
+                State::Relayout => unreachable!(),
                 State::Exit(instant) => {
-                    if let Some(instant) = instant {
-                        if instant.elapsed().as_secs() >= 30 {
+                    let verb_to_confirm =
+                        if editor.toggled && editor.has_unsaved_changes(&builder.grid) {
+                            // Unsaved editor work would be lost, no matter for how long it was worked on
+                            Some("exit without saving")
+                        } else if instant.is_some_and(|instant| instant.elapsed().as_secs() >= 30) {
                             // If the player stayed for half a minute,
                             // the game is considered to have some kind of value to the player,
                             // so we make sure the player really wants to exit.
+                            Some("exit")
+                        } else {
+                            None
+                        };
 
-                            let confirmed =
-                                window::confirmation_prompt(terminal, builder, &mut alert, "exit");
+                    if let Some(verb_to_confirm) = verb_to_confirm {
+                        let confirmed = confirm(
+                            terminal,
+                            builder,
+                            &mut alert,
+                            &mut cell_placement,
+                            verb_to_confirm,
+                        );
 
-                            if confirmed {
-                                return State::Exit(None);
-                            } else {
-                                alert::draw(terminal, builder, &mut alert, "Canceled".into());
-                                terminal.flush();
-                                continue;
-                            }
+                        if !confirmed {
+                            draw_alert(
+                                terminal,
+                                builder,
+                                &mut alert,
+                                &mut alert_history,
+                                "Canceled".into(),
+                                AlertSeverity::Info,
+                            );
+                            flush(terminal, builder, &cell_placement, event_source);
+                            continue;
                         }
                     }
 
                     return State::Exit(None);
                 }
             }
+        } else if alert_cleared || time_limit.is_some() {
+            flush(terminal, builder, &cell_placement, event_source);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::{builder, palette::Palette, Cell};
+    use std::{collections::VecDeque, io};
+    use terminal::{
+        event::{Event, Key, MouseEvent, MouseEventKind},
+        util::{Point, Size},
+    };
+
+    #[test]
+    fn test_editor_relayout() {
+        let mut terminal = Terminal::new(io::stdout().lock()).unwrap();
+
+        let size = Size {
+            width: 5,
+            height: 5,
+        };
+        let grid = Grid::new(size, vec![Cell::Empty; size.product() as usize]).unwrap();
+        let settings = Settings::default();
+        let mut builder = Builder::new(&terminal, grid, Palette::default(), &settings);
+        let _ = builder.draw_all(&mut terminal);
+        let initial_point = builder.point;
+
+        let mut events = VecDeque::new();
+        events.push_back(Event::Mouse(MouseEvent {
+            kind: MouseEventKind::Move,
+            point: builder.get_cell_screen_rect(Point { x: 0, y: 0 }).0,
+        }));
+        // The keyboard cursor starts at the cell under the mouse
+        events.push_back(Event::Key(Key::Right));
+        // Every fill after the first one adds a clue to the row, which makes the left clues wider
+        for x in 0..3 {
+            if x != 0 {
+                events.push_back(Event::Key(Key::Right));
+                events.push_back(Event::Key(Key::Right));
+            }
+            events.push_back(Event::Key(Key::Char('q')));
+        }
+        let mut event_source = EventSource::scripted(events);
+
+        let state = event::r#loop(
+            &mut terminal,
+            &mut builder,
+            &settings,
+            true,
+            None,
+            false,
+            &mut event_source,
+        );
+
+        assert!(matches!(state, State::Exit(None)));
+        assert_eq!(builder.grid.horizontal_clues_solutions[0], vec![1, 1, 1]);
+        // The grid was positioned again to make space for the clues
+        assert_ne!(builder.point, initial_point);
+        assert_eq!(
+            builder.point,
+            builder::centered_point(&terminal, &builder.grid)
+        );
+        // The keyboard cursor moved along with the grid
+        for x in [0, 2, 4] {
+            assert_eq!(builder.grid.get_cell(Point { x, y: 0 }), Cell::Filled);
         }
     }
 }
@@ -1,38 +1,111 @@
-use crate::grid::builder::Builder;
-use std::borrow::Cow;
-use terminal::Terminal;
+use crate::{grid::builder::Builder, util};
+use std::{
+    borrow::Cow,
+    cell::Cell,
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+use terminal::{util::Color, Terminal};
 
-const CLEAR_DELAY: usize = 75;
+/// How important an alert is, which determines its color and badge.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum AlertSeverity {
+    /// Something happened as the player wanted.
+    #[default]
+    Info,
+    /// Something the player should be careful about or that didn't go as the player might have wanted.
+    Warning,
+    /// Something failed.
+    Error,
+}
+
+impl AlertSeverity {
+    const fn get_color(self) -> Color {
+        match self {
+            Self::Info => Color::Gray,
+            Self::Warning => Color::Yellow,
+            Self::Error => Color::Red,
+        }
+    }
+
+    /// Gets the badge that the message is prefixed with.
+    #[cfg(not(windows))]
+    const fn get_badge(self) -> &'static str {
+        match self {
+            Self::Info => "ℹ ",
+            Self::Warning => "⚠ ",
+            Self::Error => "✗ ",
+        }
+    }
+
+    /// Gets the badge that the message is prefixed with.
+    ///
+    /// Like the resize icon, the symbols don't render in many terminals on Windows so these are used instead.
+    #[cfg(windows)]
+    const fn get_badge(self) -> &'static str {
+        match self {
+            Self::Info => "[i] ",
+            Self::Warning => "[!] ",
+            Self::Error => "[x] ",
+        }
+    }
+}
 
 pub struct Alert {
     pub message: Cow<'static, str>,
-    pub clear_delay: usize,
+    pub severity: AlertSeverity,
+    /// The time of when the alert was drawn. The alert is cleared after some time has passed since then.
+    pub drawing_time: Instant,
+    /// How many columns the alert took when it was last drawn, which is what clearing it erases.
+    ///
+    /// This is kept in a `Cell` so that the alert can be drawn wherever it is borrowed.
+    drawn_width: Cell<usize>,
 }
 
 impl Alert {
-    pub fn new(message: Cow<'static, str>) -> Self {
+    pub fn new_with_severity(message: Cow<'static, str>, severity: AlertSeverity) -> Self {
         Self {
             message,
-            clear_delay: CLEAR_DELAY,
+            severity,
+            drawing_time: Instant::now(),
+            drawn_width: Cell::new(0),
         }
     }
 
+    /// Gets the message as it fits above the grid, which is cut off if it doesn't, together with the width of the alert including the badge.
+    fn get_fitting_message(&self, terminal: &Terminal, builder: &Builder) -> (Cow<'_, str>, usize) {
+        let badge_width = util::get_display_width(self.severity.get_badge());
+        let max_message_width =
+            crate::get_top_text_max_width(terminal, builder).saturating_sub(badge_width);
+        let message = util::truncate_to_width(&self.message, max_message_width);
+        let width = badge_width + util::get_display_width(&message);
+
+        (message, width)
+    }
+
     /// Clears the previous alert.
     pub fn clear(&mut self, terminal: &mut Terminal, builder: &Builder) {
-        crate::set_cursor_for_top_text(terminal, builder, self.message.len(), 0, None);
-        for _ in 0..self.message.len() {
-            terminal.write(" ");
-        }
+        let width = self.drawn_width.get();
+        crate::set_cursor_for_top_text(terminal, builder, width, 0, None);
+        terminal.write(&" ".repeat(width));
     }
 
     /// Draws an alert above the grid.
     pub fn draw(&self, terminal: &mut Terminal, builder: &Builder) {
-        crate::set_cursor_for_top_text(terminal, builder, self.message.len(), 0, None);
-        terminal.write(&self.message);
+        let (message, width) = self.get_fitting_message(terminal, builder);
+        self.drawn_width.set(width);
+
+        crate::set_cursor_for_top_text(terminal, builder, width, 0, None);
+        if builder.palette.has_colors() {
+            terminal.set_foreground_color(self.severity.get_color());
+        }
+        terminal.write(self.severity.get_badge());
+        terminal.write(&message);
+        terminal.reset_colors();
     }
 
     pub fn reset_clear_delay(&mut self) {
-        self.clear_delay = CLEAR_DELAY;
+        self.drawing_time = Instant::now();
     }
 }
 
@@ -41,6 +114,7 @@ pub fn draw(
     builder: &Builder,
     alert: &mut Option<Alert>,
     message: Cow<'static, str>,
+    severity: AlertSeverity,
 ) {
     // In some cases we might have colors so we always safely reset them beforehand
     terminal.reset_colors();
@@ -49,23 +123,175 @@ pub fn draw(
         current_alert.clear(terminal, builder);
 
         current_alert.message = message;
+        current_alert.severity = severity;
         current_alert.reset_clear_delay();
 
         current_alert.draw(terminal, builder);
     } else {
-        let new_alert = Alert::new(message);
+        let new_alert = Alert::new_with_severity(message, severity);
         new_alert.draw(terminal, builder);
         *alert = Some(new_alert);
     }
 }
 
-pub fn handle_clear_delay(terminal: &mut Terminal, builder: &Builder, alert: &mut Option<Alert>) {
+/// How many alert messages the history keeps.
+pub const HISTORY_CAPACITY: usize = 20;
+
+/// The latest alert messages, so that alerts that were quickly replaced by others can still be read.
+#[derive(Default)]
+pub struct AlertHistory {
+    /// The oldest message comes first.
+    messages: VecDeque<String>,
+}
+
+impl AlertHistory {
+    /// Records the message, evicting the oldest one if the history is full.
+    ///
+    /// Empty messages and repetitions of the latest message are not recorded.
+    pub fn push(&mut self, message: &str) {
+        if message.is_empty() || self.messages.back().map(String::as_str) == Some(message) {
+            return;
+        }
+
+        if self.messages.len() == HISTORY_CAPACITY {
+            self.messages.pop_front();
+        }
+        self.messages.push_back(message.to_string());
+    }
+
+    /// Gets the messages, the latest first.
+    pub fn latest_first(&self) -> impl Iterator<Item = &str> {
+        self.messages.iter().rev().map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.messages.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+}
+
+/// Clears the alert if it has been shown for at least `clear_delay` and returns whether it was cleared.
+pub fn handle_clear_delay(
+    terminal: &mut Terminal,
+    builder: &Builder,
+    alert: &mut Option<Alert>,
+    clear_delay: Duration,
+) -> bool {
     if let Some(ref mut alert_to_clear) = alert {
-        if alert_to_clear.clear_delay == 0 {
+        if alert_to_clear.drawing_time.elapsed() >= clear_delay {
             alert_to_clear.clear(terminal, builder);
             *alert = None;
-        } else {
-            alert_to_clear.clear_delay -= 1;
+            return true;
         }
     }
+
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::Settings,
+        grid::{self, palette::Palette, Grid},
+    };
+    use std::io;
+    use terminal::util::Size;
+
+    fn get_terminal_and_builder(stdout: io::StdoutLock) -> (Terminal, Builder) {
+        let size = Size {
+            width: 10,
+            height: 5,
+        };
+        let grid = Grid::new(size, vec![grid::Cell::Empty; size.product() as usize]).unwrap();
+        let terminal = Terminal::new(stdout).unwrap();
+        let builder = Builder::new(&terminal, grid, Palette::default(), &Settings::default());
+        (terminal, builder)
+    }
+
+    #[test]
+    fn test_get_fitting_message() {
+        let stdout = io::stdout();
+        let (terminal, builder) = get_terminal_and_builder(stdout.lock());
+        let badge_width = util::get_display_width(AlertSeverity::Info.get_badge());
+
+        for severity in [
+            AlertSeverity::Info,
+            AlertSeverity::Warning,
+            AlertSeverity::Error,
+        ] {
+            let alert = Alert::new_with_severity("Canceled".into(), severity);
+            // The badges are as wide as each other so that alerts of different severities are aligned the same way
+            assert_eq!(
+                alert.get_fitting_message(&terminal, &builder),
+                ("Canceled".into(), "Canceled".len() + badge_width)
+            );
+        }
+
+        // The CJK characters and the emoji take two columns each
+        let alert =
+            Alert::new_with_severity("Grid saved as パズル🧩.yaya".into(), AlertSeverity::Info);
+        assert_eq!(
+            alert.get_fitting_message(&terminal, &builder).1,
+            badge_width + "Grid saved as ".len() + 8 + ".yaya".len()
+        );
+
+        let long_message = format!("Grid saved as {}.yaya", "パズル".repeat(20));
+        let alert = Alert::new_with_severity(long_message.into(), AlertSeverity::Info);
+        let (message, width) = alert.get_fitting_message(&terminal, &builder);
+        assert!(message.ends_with('…'));
+        assert!(width <= crate::get_top_text_max_width(&terminal, &builder));
+    }
+
+    #[test]
+    fn test_clear_erases_drawn_width() {
+        let stdout = io::stdout();
+        let (mut terminal, builder) = get_terminal_and_builder(stdout.lock());
+
+        let mut alert =
+            Alert::new_with_severity("Loaded 🧩 パズル.yaya".into(), AlertSeverity::Info);
+        assert_eq!(alert.drawn_width.get(), 0);
+        alert.draw(&mut terminal, &builder);
+        let (_, width) = alert.get_fitting_message(&terminal, &builder);
+        assert_eq!(alert.drawn_width.get(), width);
+
+        // Even if the message changes before it is cleared, what was drawn is cleared
+        alert.message = "Canceled".into();
+        alert.clear(&mut terminal, &builder);
+        assert_eq!(alert.drawn_width.get(), width);
+    }
+
+    #[test]
+    fn test_alert_history() {
+        let mut history = AlertHistory::default();
+        history.push("");
+        assert!(history.is_empty());
+
+        for index in 0..HISTORY_CAPACITY + 5 {
+            history.push(&format!("Alert {}", index));
+        }
+        assert_eq!(history.len(), HISTORY_CAPACITY);
+        // The oldest alerts were evicted
+        assert_eq!(history.latest_first().next(), Some("Alert 24"));
+        assert_eq!(history.latest_first().last(), Some("Alert 5"));
+
+        // Adjacent duplicates are only recorded once
+        history.push("Eraser enabled");
+        history.push("Eraser enabled");
+        history.push("Eraser disabled");
+        history.push("Eraser enabled");
+        assert_eq!(
+            history.latest_first().take(4).collect::<Vec<&str>>(),
+            [
+                "Eraser enabled",
+                "Eraser disabled",
+                "Eraser enabled",
+                "Alert 24"
+            ]
+        );
+        assert_eq!(history.len(), HISTORY_CAPACITY);
+    }
 }
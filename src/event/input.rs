@@ -1,5 +1,9 @@
+pub mod alert_history;
+pub mod clue_entry;
 pub mod key;
 mod mouse;
+pub mod step_entry;
+pub mod title_entry;
 pub mod window;
 
 use super::{alert::Alert, State};
@@ -19,6 +23,8 @@ pub fn handle(
     cell_placement: &mut CellPlacement,
 ) -> State {
     match event {
+        // Mouse events can still arrive if the terminal reports them without being asked to
+        Event::Mouse(_) if !builder.mouse => State::Continue,
         Event::Mouse(mouse_event) => mouse::handle_event(
             terminal,
             mouse_event,
@@ -30,8 +36,6 @@ pub fn handle(
         Event::Key(key_event) => {
             key::handle_event(terminal, key_event, builder, editor, cell_placement)
         }
-        Event::Resize => {
-            window::handle_resize(terminal, builder, alert, cell_placement.starting_time)
-        }
+        Event::Resize => window::relayout(terminal, builder, alert, cell_placement),
     }
 }
@@ -0,0 +1,170 @@
+//! Shows the latest alerts in a panel over the grid, for example to read an error that was quickly replaced by another alert.
+
+use super::{window, Alert, State};
+use crate::{
+    event::alert::AlertHistory,
+    grid::{
+        builder::{Builder, GlyphSet},
+        CellPlacement,
+    },
+    util,
+};
+use terminal::{
+    event::{Event, Key},
+    util::{Point, Size},
+    Terminal,
+};
+
+const TITLE: &str = " Alerts, latest first. Esc to close ";
+
+/// Gets the size of the panel inside its border, which fits all messages if the terminal is large enough.
+fn get_panel_size(terminal_size: Size, history: &AlertHistory) -> Size {
+    let message_width = history
+        .latest_first()
+        .map(util::get_display_width)
+        .max()
+        .unwrap_or(0);
+    // The messages are padded by a space on each side
+    let width = (message_width + 2).max(TITLE.len()) as u16;
+
+    Size {
+        width: width.min(terminal_size.width.saturating_sub(2)),
+        height: (history.len() as u16).min(terminal_size.height.saturating_sub(2)),
+    }
+}
+
+/// Draws the panel centered in the terminal, starting with the message at the scroll offset.
+fn draw_panel(
+    terminal: &mut Terminal,
+    history: &AlertHistory,
+    glyphs: GlyphSet,
+    size: Size,
+    scroll_y: usize,
+) {
+    let [top_left, top_right, bottom_left, bottom_right, horizontal, vertical] = glyphs.get_box();
+
+    let point = Point {
+        x: terminal.size.width.saturating_sub(size.width + 2) / 2,
+        y: terminal.size.height.saturating_sub(size.height + 2) / 2,
+    };
+
+    terminal.set_cursor(point);
+    let title: String = TITLE.chars().take(size.width as usize).collect();
+    terminal.write(&format!(
+        "{}{}{}{}",
+        top_left,
+        title,
+        horizontal.repeat(size.width as usize - title.chars().count()),
+        top_right
+    ));
+
+    for (y, message) in history
+        .latest_first()
+        .skip(scroll_y)
+        .take(size.height as usize)
+        .enumerate()
+    {
+        terminal.set_cursor(Point {
+            y: point.y + 1 + y as u16,
+            ..point
+        });
+        let message = format!(" {}", message);
+        let message = util::truncate_to_width(&message, size.width as usize);
+        terminal.write(&format!(
+            "{}{}{}{}",
+            vertical,
+            message,
+            " ".repeat(size.width as usize - util::get_display_width(&message)),
+            vertical
+        ));
+    }
+
+    terminal.set_cursor(Point {
+        y: point.y + 1 + size.height,
+        ..point
+    });
+    terminal.write(&format!(
+        "{}{}{}",
+        bottom_left,
+        horizontal.repeat(size.width as usize),
+        bottom_right
+    ));
+}
+
+/// Shows the alert history until Esc is pressed. The arrow keys scroll through it if it doesn't fit.
+pub fn show(
+    terminal: &mut Terminal,
+    builder: &mut Builder,
+    alert: &Option<Alert>,
+    cell_placement: &mut CellPlacement,
+    history: &AlertHistory,
+) -> State {
+    let mut scroll_y = 0;
+
+    loop {
+        let size = get_panel_size(terminal.size, history);
+        let max_scroll_y = history.len() - size.height as usize;
+        scroll_y = scroll_y.min(max_scroll_y);
+
+        draw_panel(terminal, history, builder.glyphs, size, scroll_y);
+        terminal.flush();
+
+        match terminal.read_event() {
+            Some(Event::Key(Key::Esc)) => break,
+            Some(Event::Key(Key::Up | Key::Char('k' | 'K'))) => {
+                scroll_y = scroll_y.saturating_sub(1);
+            }
+            Some(Event::Key(Key::Down | Key::Char('j' | 'J'))) => {
+                scroll_y = (scroll_y + 1).min(max_scroll_y);
+            }
+            Some(Event::Resize) => {
+                if let State::Exit(_) = window::relayout(terminal, builder, alert, cell_placement) {
+                    return State::Exit(None);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // Draw everything that the panel covered again
+    window::relayout(terminal, builder, alert, cell_placement)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_panel_size() {
+        let mut history = AlertHistory::default();
+        history.push("Eraser enabled");
+        let long_message =
+            "Grid saved as a file with a long name that does not fit into the terminal";
+        history.push(long_message);
+
+        let size = get_panel_size(
+            Size {
+                width: 100,
+                height: 50,
+            },
+            &history,
+        );
+        assert_eq!(size.width as usize, long_message.len() + 2);
+        assert_eq!(size.height, 2);
+
+        let size = get_panel_size(
+            Size {
+                width: 40,
+                height: 3,
+            },
+            &history,
+        );
+        assert_eq!(
+            size,
+            Size {
+                width: 38,
+                height: 1
+            }
+        );
+    }
+}
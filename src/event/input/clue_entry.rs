@@ -0,0 +1,285 @@
+//! Lets the editor enter the clues of rows and columns directly instead of drawing the picture,
+//! for example to transcribe a puzzle whose picture is not known.
+
+use super::{window, Alert, State};
+use crate::{
+    editor,
+    event::alert::{self, AlertSeverity},
+    grid::{self, builder::Builder, CellPlacement, Grid, Line},
+};
+use itertools::Itertools;
+use std::borrow::Cow;
+use terminal::{
+    event::{Event, Key},
+    util::Point,
+    Terminal,
+};
+
+/// The row or column whose clues are being entered together with the input so far.
+struct ClueEntry {
+    line: Line,
+    index: u16,
+    input: String,
+}
+
+impl ClueEntry {
+    fn new(grid: &Grid) -> Self {
+        let mut clue_entry = Self {
+            line: Line::Row,
+            index: 0,
+            input: String::new(),
+        };
+        clue_entry.reset_input(grid);
+        clue_entry
+    }
+
+    const fn get_line_len(&self, grid: &Grid) -> u16 {
+        match self.line {
+            Line::Row => grid.size.width,
+            Line::Column => grid.size.height,
+        }
+    }
+
+    const fn get_line_count(&self, grid: &Grid) -> u16 {
+        match self.line {
+            Line::Row => grid.size.height,
+            Line::Column => grid.size.width,
+        }
+    }
+
+    /// Fills the input with the current clues of the line so that they can be edited.
+    fn reset_input(&mut self, grid: &Grid) {
+        let clues_solution = match self.line {
+            Line::Row => &grid.horizontal_clues_solutions[self.index as usize],
+            Line::Column => &grid.vertical_clues_solutions[self.index as usize],
+        };
+
+        self.input = if clues_solution.is_empty() {
+            "0".into()
+        } else {
+            clues_solution.iter().join(" ")
+        };
+    }
+
+    /// Selects the line of the given kind that is `offset` lines away, wrapping around.
+    ///
+    /// Switching between rows and columns selects the first line.
+    fn select(&mut self, grid: &Grid, line: Line, offset: i32) {
+        if self.line == line {
+            let line_count = self.get_line_count(grid) as i32;
+            self.index = (self.index as i32 + offset).rem_euclid(line_count) as u16;
+        } else {
+            self.line = line;
+            self.index = 0;
+        }
+
+        self.reset_input(grid);
+    }
+
+    /// Sets the entered clues for the line and selects the next line.
+    ///
+    /// Returns whether `max_clues_size` grew or why the clues are invalid.
+    fn apply(&mut self, grid: &mut Grid) -> Result<bool, Cow<'static, str>> {
+        let clues_solution =
+            editor::parse_clues(&self.input).map_err(|err| format!("Invalid clues: {}", err))?;
+
+        if !grid::clues_fit(&clues_solution, self.get_line_len(grid)) {
+            return Err(format!(
+                "Clues do not fit into the {}",
+                self.get_name().to_lowercase()
+            )
+            .into());
+        }
+
+        let clues_grew = grid.set_clues_solution(&self.line, self.index, clues_solution);
+
+        self.select(grid, self.line, 1);
+
+        Ok(clues_grew)
+    }
+
+    const fn get_name(&self) -> &'static str {
+        match self.line {
+            Line::Row => "Row",
+            Line::Column => "Column",
+        }
+    }
+
+    fn get_prompt(&self) -> String {
+        format!(
+            "{} {}: {}_ Enter to set, arrows to select, Esc to finish",
+            self.get_name(),
+            self.index + 1,
+            self.input
+        )
+    }
+
+    /// Draws the grid with the selected line highlighted.
+    fn draw_line(&self, terminal: &mut Terminal, builder: &mut Builder) {
+        builder.draw_grid(terminal);
+
+        let first_cell_point = match self.line {
+            Line::Row => Point {
+                x: 0,
+                y: self.index,
+            },
+            Line::Column => Point {
+                x: self.index,
+                y: 0,
+            },
+        };
+        for cell_point in self.line.get_points(first_cell_point, builder.grid.size) {
+            terminal.set_cursor(builder.get_cell_screen_rect(cell_point).0);
+            builder
+                .grid
+                .get_cell(cell_point)
+                .draw(terminal, cell_point, true, builder.palette);
+        }
+        terminal.reset_colors();
+    }
+}
+
+/// Checks whether the row and column clues add up to the same amount of filled cells, which every solvable grid fulfills.
+fn clues_add_up(grid: &Grid) -> bool {
+    let sum = |clues_solutions: &[grid::Clues]| -> usize {
+        clues_solutions
+            .iter()
+            .flatten()
+            .map(|clue| *clue as usize)
+            .sum()
+    };
+
+    sum(&grid.horizontal_clues_solutions) == sum(&grid.vertical_clues_solutions)
+}
+
+/// Lets the user enter clues line by line until Esc is pressed.
+pub fn enter_clues(
+    terminal: &mut Terminal,
+    builder: &mut Builder,
+    alert: &mut Option<Alert>,
+    cell_placement: &mut CellPlacement,
+) -> State {
+    let mut clue_entry = ClueEntry::new(&builder.grid);
+    let mut feedback: Option<Cow<'static, str>> = None;
+
+    loop {
+        clue_entry.draw_line(terminal, builder);
+        let (prompt, severity) = if let Some(feedback) = feedback.take() {
+            (
+                format!("{}. {}", feedback, clue_entry.get_prompt()),
+                AlertSeverity::Error,
+            )
+        } else {
+            (clue_entry.get_prompt(), AlertSeverity::Info)
+        };
+        alert::draw(terminal, builder, alert, prompt.into(), severity);
+        terminal.flush();
+
+        match terminal.read_event() {
+            Some(Event::Key(Key::Esc)) => break,
+            Some(Event::Key(Key::Enter)) => {
+                builder.clear_clues(terminal);
+
+                match clue_entry.apply(&mut builder.grid) {
+                    Ok(true) => {
+                        if let State::Exit(_) =
+                            window::relayout(terminal, builder, alert, cell_placement)
+                        {
+                            return State::Exit(None);
+                        }
+                    }
+                    Ok(false) => {}
+                    Err(err) => feedback = Some(err),
+                }
+
+                // The grid shouldn't be solved while editing it
+                #[allow(unused_must_use)]
+                {
+                    builder.draw_all(terminal);
+                }
+            }
+            Some(Event::Key(Key::Backspace)) => {
+                clue_entry.input.pop();
+            }
+            Some(Event::Key(Key::Char(char @ ('0'..='9' | ' ')))) => clue_entry.input.push(char),
+            Some(Event::Key(Key::Up)) => clue_entry.select(&builder.grid, Line::Row, -1),
+            Some(Event::Key(Key::Down)) => clue_entry.select(&builder.grid, Line::Row, 1),
+            Some(Event::Key(Key::Left)) => clue_entry.select(&builder.grid, Line::Column, -1),
+            Some(Event::Key(Key::Right)) => clue_entry.select(&builder.grid, Line::Column, 1),
+            Some(Event::Resize) => {
+                if let State::Exit(_) = window::relayout(terminal, builder, alert, cell_placement) {
+                    return State::Exit(None);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    builder.draw_grid(terminal);
+
+    if clues_add_up(&builder.grid) {
+        State::Alert("Clues entered".into(), AlertSeverity::Info)
+    } else {
+        State::Alert(
+            "Row and column clues do not add up to the same amount of cells".into(),
+            AlertSeverity::Warning,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clue_entry() {
+        #[rustfmt::skip]
+        let mut grid = Grid::from_lines(&[
+            "1 1",
+            "   ",
+        ]).unwrap();
+        let mut clue_entry = ClueEntry::new(&grid);
+        assert_eq!(clue_entry.input, "1 1");
+
+        clue_entry.input = "3".into();
+        assert_eq!(clue_entry.apply(&mut grid), Ok(false));
+        assert_eq!(grid.horizontal_clues_solutions[0], [3]);
+        // The next row is selected
+        assert_eq!((clue_entry.line, clue_entry.index), (Line::Row, 1));
+        assert_eq!(clue_entry.input, "0");
+
+        clue_entry.input = "2 2".into();
+        assert!(clue_entry.apply(&mut grid).is_err());
+        clue_entry.input = "1 0".into();
+        assert!(clue_entry.apply(&mut grid).is_err());
+        clue_entry.input = " ".into();
+        assert!(clue_entry.apply(&mut grid).is_err());
+        assert_eq!(clue_entry.index, 1);
+
+        // Wrapping around
+        clue_entry.select(&grid, Line::Row, 1);
+        assert_eq!(clue_entry.index, 0);
+        clue_entry.select(&grid, Line::Column, -1);
+        assert_eq!((clue_entry.line, clue_entry.index), (Line::Column, 0));
+        clue_entry.select(&grid, Line::Column, -1);
+        assert_eq!(clue_entry.index, 2);
+
+        clue_entry.input = "1".into();
+        assert_eq!(clue_entry.apply(&mut grid), Ok(false));
+        assert_eq!(grid.vertical_clues_solutions, [vec![1], vec![], vec![1]]);
+        assert!(!clues_add_up(&grid));
+    }
+
+    #[test]
+    fn test_clues_add_up() {
+        #[rustfmt::skip]
+        let mut grid = Grid::from_lines(&[
+            "11",
+            " 1",
+        ]).unwrap();
+        assert!(clues_add_up(&grid));
+
+        let _ = grid.set_clues_solution(&Line::Row, 1, Vec::new());
+        assert!(!clues_add_up(&grid));
+    }
+}
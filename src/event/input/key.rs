@@ -1,15 +1,196 @@
-use super::State;
+use super::{super::AlertSeverity, State};
 use crate::{
     editor::Editor,
     grid::CellPlacement,
-    grid::{self, builder::Builder, Cell},
-    undo_redo_buffer,
+    grid::{self, builder::Builder, tools::transform::Transform, Cell, Grid},
+    undo_redo_buffer, util,
 };
+use std::{borrow::Cow, fs, io::Write};
 use terminal::{
     event::{Event, Key},
+    util::Point,
     Terminal,
 };
 
+/// The file that the grid is exported to as an SVG image.
+const SVG_EXPORT_FILENAME: &str = "export.svg";
+/// The board is saved to a file named like `board-1.txt`.
+const BOARD_FILE_NAME: &str = "board";
+const BOARD_FILE_EXTENSION: &str = "txt";
+
+/// Saves the current state of the grid as text to a new file and returns the filename.
+fn save_board(grid: &Grid) -> Result<String, &'static str> {
+    let (filename, mut file) = util::create_numbered_file(
+        BOARD_FILE_NAME,
+        BOARD_FILE_EXTENSION,
+        "Too many board files",
+    )?;
+
+    if file.write_all(grid.to_board_text().as_bytes()).is_err() {
+        return Err("Save failed");
+    }
+
+    Ok(filename)
+}
+
+/// A jump of the selected cell to the edge of the grid.
+enum Jump {
+    /// To the first cell of the current row.
+    RowStart,
+    /// To the last cell of the current row.
+    RowEnd,
+    /// To the top left cell of the grid.
+    TopLeft,
+    /// To the bottom right cell of the grid.
+    BottomRight,
+    /// By the given amount of cells, stopping at the edges of the grid.
+    By { x: i32, y: i32 },
+}
+
+/// How many cells the selected cell moves horizontally when skipping, like skipping words in text editors.
+const HORIZONTAL_SKIP: i32 = 5;
+
+/// Gets the point of the cell that the selected cell jumps to.
+fn get_jump_point(builder: &Builder, selected_cell_point: Point, jump: Jump) -> Point {
+    let last_cell_x = builder.point.x + builder.grid.size.width * 2 - 2;
+    let last_cell_y = builder.point.y + builder.grid.size.height - 1;
+
+    match jump {
+        Jump::RowStart => Point {
+            x: builder.point.x,
+            ..selected_cell_point
+        },
+        Jump::RowEnd => Point {
+            x: last_cell_x,
+            ..selected_cell_point
+        },
+        Jump::TopLeft => builder.point,
+        Jump::BottomRight => Point {
+            x: last_cell_x,
+            y: last_cell_y,
+        },
+        Jump::By { x, y } => {
+            let cell_point =
+                grid::get_clamped_cell_point_from_cursor_point(selected_cell_point, builder);
+            let cell_x = cell_point.x as i32 + x;
+            let cell_y = cell_point.y as i32 + y;
+
+            builder
+                .get_cell_screen_rect(Point {
+                    x: cell_x.clamp(0, builder.grid.size.width as i32 - 1) as u16,
+                    y: cell_y.clamp(0, builder.grid.size.height as i32 - 1) as u16,
+                })
+                .0
+        }
+    }
+}
+
+/// Moves the selected cell by the given amount of cells, wrapping around at the edges of the grid.
+///
+/// The movement is done on the cells of the grid and only the result is converted back to a point on the screen.
+fn move_selected_cell_point(
+    builder: &Builder,
+    selected_cell_point: Point,
+    x: i32,
+    y: i32,
+) -> Point {
+    let cell_point = grid::get_clamped_cell_point_from_cursor_point(selected_cell_point, builder);
+    let cell_x = (cell_point.x as i32 + x).rem_euclid(builder.grid.size.width as i32) as u16;
+    let cell_y = (cell_point.y as i32 + y).rem_euclid(builder.grid.size.height as i32) as u16;
+
+    builder
+        .get_cell_screen_rect(Point {
+            x: cell_x,
+            y: cell_y,
+        })
+        .0
+}
+
+/// Places the pending cell at the point that the selected cell moved to.
+fn place_pending_cell(
+    terminal: &mut Terminal,
+    builder: &mut Builder,
+    editor: &Editor,
+    cell_placement: &mut CellPlacement,
+    selected_cell_point: Point,
+) -> State {
+    let state = cell_placement.place(
+        terminal,
+        builder,
+        selected_cell_point,
+        Cell::default(), // This is replaced by the pending cell
+        editor.toggled,
+    );
+
+    cell_placement.cell = None;
+
+    state
+}
+
+/// Formats the count of lines, such as `1 row` or `3 columns`.
+fn format_line_count(count: u16, line_name: &str) -> String {
+    if count == 1 {
+        format!("1 {}", line_name)
+    } else {
+        format!("{} {}s", count, line_name)
+    }
+}
+
+/// Checks whether the filled cells contradict the clues so far, telling how many rows and columns do but not which cells are wrong.
+fn check(grid: &mut Grid) -> Cow<'static, str> {
+    let lines = match grid.get_contradicted_line_counts() {
+        (0, 0) => return "All lines consistent so far".into(),
+        (rows, 0) => format_line_count(rows, "row"),
+        (0, columns) => format_line_count(columns, "column"),
+        (rows, columns) => format!(
+            "{} and {}",
+            format_line_count(rows, "row"),
+            format_line_count(columns, "column")
+        ),
+    };
+    let verb = if lines.starts_with("1 ") && !lines.contains(" and ") {
+        "contradicts"
+    } else {
+        "contradict"
+    };
+
+    format!("{} {} the clues", lines, verb).into()
+}
+
+/// Toggles the editor and returns an alert saying so.
+pub fn toggle_editor(
+    builder: &mut Builder,
+    editor: &mut Editor,
+    cell_placement: &mut CellPlacement,
+) -> State {
+    editor.toggle();
+    if editor.toggled {
+        builder.edited_at_least_once = true;
+    }
+
+    // The time spent on the grid before it was edited doesn't count and editing doesn't start the timer,
+    // so it starts again with the first placement after the editor is disabled
+    cell_placement.starting_time = None;
+
+    let canceled = grid::on_mode_change(cell_placement, builder);
+
+    // The window title is updated by the event loop
+    let message = if editor.toggled {
+        "Editor enabled"
+    } else {
+        "Editor disabled"
+    };
+
+    if let Some(canceled) = canceled {
+        State::Alert(
+            format!("{message}; {canceled}").into(),
+            AlertSeverity::Warning,
+        )
+    } else {
+        State::Alert(message.into(), AlertSeverity::Info)
+    }
+}
+
 /// This handles all key input.
 pub fn handle_event(
     terminal: &mut Terminal,
@@ -20,29 +201,80 @@ pub fn handle_event(
 ) -> State {
     match key_event {
         Key::Char('a' | 'A') => {
+            let transforms = matches!(
+                builder.grid.undo_redo_buffer.get_undo_operation(),
+                Some(undo_redo_buffer::Operation::Transform(_))
+            );
             if builder.grid.undo_last_cell() {
+                if transforms {
+                    // The clues and possibly the size changed
+                    return State::Relayout;
+                }
+
                 // An undo won't cause the grid to be solved at this point because otherwise it would've already been solved before when that operation was done.
                 #[allow(unused_must_use)]
                 {
                     builder.draw_all(terminal);
                 }
+                builder.refresh_difficulty_label(terminal, editor.toggled);
             }
 
             State::Continue
         }
         Key::Char('d' | 'D') => {
+            let transforms = matches!(
+                builder.grid.undo_redo_buffer.get_redo_operation(),
+                Some(undo_redo_buffer::Operation::Transform(_))
+            );
             if builder.grid.redo_last_cell() {
+                if transforms {
+                    return State::Relayout;
+                }
+
                 // A redo won't cause the grid to be solved at this point because otherwise it would've already been solved before when that operation was done.
                 #[allow(unused_must_use)]
                 {
                     builder.draw_all(terminal);
                 }
+                builder.refresh_difficulty_label(terminal, editor.toggled);
             }
 
             State::Continue
         }
-        Key::Char('c' | 'C') => {
+        // Unlike most letters, C, F, G, Y and N do something different but related with Shift, so with Caps Lock on the two are swapped.
+        // The lowercase letter does the more common thing.
+        Key::Char('C') => {
+            let points = builder.grid.clear_mistakes();
+            if points.is_empty() {
+                return State::Alert("No cells to clear".into(), AlertSeverity::Info);
+            }
+
+            let cleared_cells = points.len();
+            builder.grid.undo_redo_buffer.push_cells(
+                points
+                    .into_iter()
+                    .map(|point| (point, Cell::Empty))
+                    .collect(),
+                false,
+            );
+
+            // Once the wrong cells are gone, the right ones might be all that's left
+            let all_clues_solved = builder.draw_all(terminal);
+            if all_clues_solved && !editor.toggled {
+                if let Some(starting_time) = cell_placement.starting_time {
+                    return State::Solved(starting_time.elapsed());
+                }
+            }
+            builder.refresh_difficulty_label(terminal, editor.toggled);
+
+            State::Alert(
+                format!("Cleared {} cells", cleared_cells).into(),
+                AlertSeverity::Info,
+            )
+        }
+        Key::Char('c') => {
             builder.grid.clear();
+            builder.cleared_at_least_once = true;
             builder
                 .grid
                 .undo_redo_buffer
@@ -53,90 +285,325 @@ pub fn handle_event(
             {
                 builder.draw_all(terminal);
             }
+            builder.refresh_difficulty_label(terminal, editor.toggled);
 
             State::Continue
         }
-        Key::Char('f' | 'F') => {
+        Key::Char('f') => {
             cell_placement.fill = true;
-            State::Alert("Set place to fill".into())
+            cell_placement.fill_target = None;
+            State::Alert("Set place to fill".into(), AlertSeverity::Info)
+        }
+        Key::Char('F') => {
+            cell_placement.fill = true;
+            cell_placement.fill_target = match cell_placement.fill_target {
+                None => Some(Cell::Maybed),
+                Some(Cell::Maybed) => Some(Cell::Crossed),
+                Some(Cell::Crossed) => Some(Cell::Empty),
+                Some(Cell::Empty) => Some(Cell::Filled),
+                Some(_) => None,
+            };
+            let target = match cell_placement.fill_target {
+                Some(Cell::Maybed) => "maybed",
+                Some(Cell::Crossed) => "crossed out",
+                Some(Cell::Empty) => "empty",
+                Some(Cell::Filled) => "filled",
+                _ => return State::Alert("Set place to fill".into(), AlertSeverity::Info),
+            };
+            State::Alert(
+                format!("Set place to fill {} cells", target).into(),
+                AlertSeverity::Info,
+            )
         }
         Key::Char('x' | 'X') => cell_placement.place_measured_cells(terminal, builder),
+        Key::Char(char @ ('-' | '|')) => {
+            if let Some(selected_cell_point) = cell_placement.get_key_point() {
+                let line = if char == '-' {
+                    grid::Line::Row
+                } else {
+                    grid::Line::Column
+                };
+
+                cell_placement.fill_line(
+                    terminal,
+                    builder,
+                    selected_cell_point,
+                    line,
+                    Cell::Filled,
+                    editor.toggled,
+                )
+            } else {
+                State::Continue
+            }
+        }
+        Key::Char('o' | 'O') => {
+            cell_placement.thick_measurement = !cell_placement.thick_measurement;
+
+            if cell_placement.thick_measurement {
+                State::Alert("Thick measurement enabled".into(), AlertSeverity::Info)
+            } else {
+                State::Alert("Thick measurement disabled".into(), AlertSeverity::Info)
+            }
+        }
+        Key::Char('b' | 'B') if !builder.glyphs.has_braille() => State::Alert(
+            "The braille picture can't be drawn with ASCII".into(),
+            AlertSeverity::Warning,
+        ),
+        Key::Char('b' | 'B') => {
+            builder.clear_picture(terminal);
+            builder.braille_picture = !builder.braille_picture;
+            builder.draw_picture(terminal);
+
+            State::Continue
+        }
+        Key::Char('z' | 'Z') => {
+            let max_zoom = builder.get_max_picture_zoom();
+            if max_zoom == 1 {
+                return State::Alert(
+                    "The picture can't be zoomed in on this window size".into(),
+                    AlertSeverity::Warning,
+                );
+            }
+
+            builder.clear_picture(terminal);
+            builder.picture_zoom = if builder.picture_zoom >= max_zoom {
+                1
+            } else {
+                builder.picture_zoom + 1
+            };
+            // The zoom applies to the normal picture
+            builder.braille_picture = false;
+            builder.draw_picture(terminal);
+
+            State::Alert(
+                format!("Picture zoom: {}x", builder.picture_zoom).into(),
+                AlertSeverity::Info,
+            )
+        }
+        Key::Char('r' | 'R') if !editor.toggled => State::Reveal,
+        Key::Char('v' | 'V') if !editor.toggled => {
+            cell_placement.rect_fill_mode = !cell_placement.rect_fill_mode;
+
+            if cell_placement.rect_fill_mode {
+                State::Alert("Rectangle fill enabled".into(), AlertSeverity::Info)
+            } else {
+                State::Alert("Rectangle fill disabled".into(), AlertSeverity::Info)
+            }
+        }
+        Key::Char('?') if !editor.toggled => {
+            builder.check_count += 1;
+            State::Alert(check(&mut builder.grid), AlertSeverity::Info)
+        }
+        Key::Char('i' | 'I') => {
+            builder.remaining_counts = !builder.remaining_counts;
+
+            if !builder.remaining_counts {
+                builder.clear_row_remaining_counts(terminal);
+                builder.clear_progress_bar_and_resize_icon(terminal);
+            }
+
+            // Showing or hiding the counts doesn't change whether the grid is solved
+            #[allow(unused_must_use)]
+            {
+                builder.draw_all(terminal);
+            }
+
+            if builder.remaining_counts {
+                State::Alert("Remaining counts shown".into(), AlertSeverity::Info)
+            } else {
+                State::Alert("Remaining counts hidden".into(), AlertSeverity::Info)
+            }
+        }
+        Key::Char('u' | 'U') => {
+            util::clipboard_write(terminal, &builder.grid.to_unicode_art(true));
+            State::Alert("Grid copied as text".into(), AlertSeverity::Info)
+        }
+        Key::Char('p' | 'P') => {
+            if fs::write(SVG_EXPORT_FILENAME, builder.grid.to_svg()).is_err() {
+                State::Alert("Export failed".into(), AlertSeverity::Error)
+            } else {
+                State::Alert(
+                    format!("Grid exported as {}", SVG_EXPORT_FILENAME).into(),
+                    AlertSeverity::Info,
+                )
+            }
+        }
         Key::Tab => {
-            editor.toggle();
+            let selected_points = cell_placement
+                .get_key_point()
+                .and_then(|selected_cell_point| {
+                    grid::get_cell_point_from_cursor_point(selected_cell_point, builder)
+                        .map(|cell_point| (selected_cell_point, cell_point))
+                });
+            if let Some((selected_cell_point, cell_point)) = selected_points {
+                let cell_to_place = builder.grid.get_cell(cell_point).cycle();
+
+                // Every press is a placement of its own rather than part of a stroke
+                cell_placement.cell = None;
 
-            if editor.toggled {
-                terminal.set_title("yayagram Editor");
-                State::Alert("Editor enabled".into())
+                let state = cell_placement.place(
+                    terminal,
+                    builder,
+                    selected_cell_point,
+                    cell_to_place,
+                    editor.toggled,
+                );
+
+                cell_placement.cell = None;
+
+                state
+            } else {
+                State::Continue
+            }
+        }
+        // Ctrl+Tab and F4 are not reported by the terminal library yet
+        Key::Char('t' | 'T') => {
+            // Designing a grid from scratch is easier without the clues of the previous grid
+            if !editor.toggled && !builder.grid.is_blank() {
+                State::EnableEditor
             } else {
-                terminal.set_title("yayagram");
-                State::Alert("Editor disabled".into())
+                let state = toggle_editor(builder, editor, cell_placement);
+                builder.refresh_difficulty_label(terminal, editor.toggled);
+                state
             }
         }
+        Key::Char(char @ ('y' | 'Y' | '<' | '>')) if editor.toggled => {
+            let transform = match char {
+                'y' => Transform::MirrorHorizontally,
+                'Y' => Transform::MirrorVertically,
+                '<' => Transform::RotateCounterclockwise,
+                _ => Transform::RotateClockwise,
+            };
+            builder.grid.transform(transform);
+            builder
+                .grid
+                .undo_redo_buffer
+                .push(undo_redo_buffer::Operation::Transform(transform));
+
+            // The clues and possibly the size changed and the rotated grid might not fit anymore
+            State::Relayout
+        }
+        // Ctrl+G is not reported by the terminal library either
+        Key::Char('#') => State::EnterStep,
+        Key::Char('m' | 'M') => State::ShowAlertHistory,
+        Key::Char('n') if editor.toggled => State::EnterClues,
+        // Ctrl+M is not reported by the terminal library either and usually arrives as Enter, which loads grids
+        Key::Char('N') if editor.toggled => State::EnterTitle,
         Key::Char('s' | 'S') if editor.toggled => {
             if let Err(err) = editor.save_grid(builder) {
-                State::Alert(err.into())
+                State::Alert(err.into(), AlertSeverity::Error)
             } else {
-                State::Alert(format!("Grid saved as {}", editor.filename).into())
+                State::Alert(
+                    format!("Grid saved as {}", editor.filename).into(),
+                    AlertSeverity::Info,
+                )
             }
         }
+        // Ctrl+P is not reported by the terminal library either
+        Key::Char('s' | 'S') => match save_board(&builder.grid) {
+            Ok(filename) => State::Alert(
+                format!("Board saved as {}", filename).into(),
+                AlertSeverity::Info,
+            ),
+            Err(err) => State::Alert(err.into(), AlertSeverity::Error),
+        },
         Key::Enter => State::LoadGrid,
+        Key::Backspace => {
+            cell_placement.erase = !cell_placement.erase;
+
+            if cell_placement.erase {
+                State::Alert("Eraser enabled".into(), AlertSeverity::Info)
+            } else {
+                State::Alert("Eraser disabled".into(), AlertSeverity::Info)
+            }
+        }
         Key::Up
         | Key::Down
         | Key::Left
         | Key::Right
         | Key::Char('h' | 'H' | 'j' | 'J' | 'k' | 'K' | 'l' | 'L') => {
-            let selected_cell_point = if let Some(selected_cell_point) =
-                &mut cell_placement.selected_cell_point
-            {
-                match key_event {
-                    Key::Up | Key::Char('k' | 'K') => {
-                        selected_cell_point.y -= 1;
-
-                        if !(builder.point.y..builder.point.y + builder.grid.size.height)
-                            .contains(&selected_cell_point.y)
-                        {
-                            selected_cell_point.y = builder.point.y + builder.grid.size.height - 1;
-                        }
-                    }
-                    Key::Down | Key::Char('j' | 'J') => {
-                        selected_cell_point.y += 1;
-
-                        if !(builder.point.y..builder.point.y + builder.grid.size.height)
-                            .contains(&selected_cell_point.y)
-                        {
-                            selected_cell_point.y = builder.point.y;
-                        }
-                    }
-                    Key::Left | Key::Char('h' | 'H') => {
-                        selected_cell_point.x -= 2;
-
-                        if !(builder.point.x..builder.point.x + builder.grid.size.width * 2)
-                            .contains(&selected_cell_point.x)
-                        {
-                            selected_cell_point.x =
-                                builder.point.x + builder.grid.size.width * 2 - 2;
-                        }
-                    }
-                    Key::Right | Key::Char('l' | 'L') => {
-                        selected_cell_point.x += 2;
-
-                        if !(builder.point.x..builder.point.x + builder.grid.size.width * 2)
-                            .contains(&selected_cell_point.x)
-                        {
-                            selected_cell_point.x = builder.point.x
-                        }
-                    }
-                    _ => unreachable!(),
-                }
+            // If the keyboard cursor wasn't used yet, it starts at the cell under the mouse
+            let selected_cell_point =
+                if let Some(selected_cell_point) = cell_placement.selected_cell_point {
+                    let (x, y) = match key_event {
+                        Key::Up | Key::Char('k' | 'K') => (0, -1),
+                        Key::Down | Key::Char('j' | 'J') => (0, 1),
+                        Key::Left | Key::Char('h' | 'H') => (-1, 0),
+                        Key::Right | Key::Char('l' | 'L') => (1, 0),
+                        _ => unreachable!(),
+                    };
 
-                *selected_cell_point
-            } else {
-                let grid_center = builder.get_center();
-                cell_placement.selected_cell_point = Some(grid_center);
+                    move_selected_cell_point(builder, selected_cell_point, x, y)
+                } else {
+                    cell_placement
+                        .hovered_cell_point
+                        .unwrap_or_else(|| builder.get_center())
+                };
+            cell_placement.selected_cell_point = Some(selected_cell_point);
 
-                grid_center
+            if cell_placement.pending_cell.is_some() {
+                return place_pending_cell(
+                    terminal,
+                    builder,
+                    editor,
+                    cell_placement,
+                    selected_cell_point,
+                );
+            }
+
+            builder.draw_grid(terminal);
+
+            // We know that this point is hovered
+            grid::draw_highlighted_cells(terminal, builder, selected_cell_point);
+
+            State::Continue
+        }
+        // These are mostly the same as in vi.
+        // Home, End, Page Up, Page Down and modifiers are not reported by the terminal library yet
+        Key::Char('^' | '$' | 'g' | 'G' | '{' | '}' | '[' | ']') => {
+            // A quarter of the grid
+            let vertical_skip = (builder.grid.size.height as i32 / 4).max(1);
+
+            let jump = match key_event {
+                Key::Char('^') => Jump::RowStart,
+                Key::Char('$') => Jump::RowEnd,
+                Key::Char('g') => Jump::TopLeft,
+                Key::Char('G') => Jump::BottomRight,
+                Key::Char('{') => Jump::By {
+                    x: 0,
+                    y: -vertical_skip,
+                },
+                Key::Char('}') => Jump::By {
+                    x: 0,
+                    y: vertical_skip,
+                },
+                Key::Char('[') => Jump::By {
+                    x: -HORIZONTAL_SKIP,
+                    y: 0,
+                },
+                Key::Char(']') => Jump::By {
+                    x: HORIZONTAL_SKIP,
+                    y: 0,
+                },
+                _ => unreachable!(),
             };
 
+            let selected_cell_point = cell_placement
+                .get_key_point()
+                .unwrap_or_else(|| builder.get_center());
+            let selected_cell_point = get_jump_point(builder, selected_cell_point, jump);
+            cell_placement.selected_cell_point = Some(selected_cell_point);
+
+            if cell_placement.pending_cell.is_some() {
+                return place_pending_cell(
+                    terminal,
+                    builder,
+                    editor,
+                    cell_placement,
+                    selected_cell_point,
+                );
+            }
+
             builder.draw_grid(terminal);
 
             // We know that this point is hovered
@@ -144,8 +611,20 @@ pub fn handle_event(
 
             State::Continue
         }
+        Key::Char(char @ '0'..='3') => {
+            let pending_cell = match char {
+                '1' => Cell::Filled,
+                '2' => Cell::Crossed,
+                '3' => Cell::Maybed,
+                _ => Cell::Empty,
+            };
+            cell_placement.pending_cell = Some(pending_cell);
+            crate::draw_pending_cell(terminal, pending_cell);
+
+            State::Continue
+        }
         Key::Char(char) => {
-            if let Some(selected_cell_point) = cell_placement.selected_cell_point {
+            if let Some(selected_cell_point) = cell_placement.get_key_point() {
                 let cell_to_place = match char {
                     'q' | 'Q' => Cell::Filled,
                     'w' | 'W' => Cell::Maybed,
@@ -173,11 +652,250 @@ pub fn handle_event(
     }
 }
 
-pub fn r#await(terminal: &mut Terminal) {
+pub fn r#await(terminal: &mut Terminal) -> Key {
     loop {
         let event = terminal.read_event();
-        if let Some(Event::Key(_)) = event {
-            break;
+        if let Some(Event::Key(key)) = event {
+            break key;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io;
+    use terminal::util::Size;
+
+    #[test]
+    fn test_check() {
+        #[rustfmt::skip]
+        let mut grid = Grid::from_lines(&[
+            "1  ",
+            "   ",
+        ]).unwrap();
+        assert_eq!(check(&mut grid), "All lines consistent so far");
+
+        *grid.get_mut_cell(Point { x: 1, y: 1 }) = Cell::Filled;
+        assert_eq!(check(&mut grid), "1 row and 1 column contradict the clues");
+
+        *grid.get_mut_cell(Point { x: 2, y: 1 }) = Cell::Filled;
+        assert_eq!(check(&mut grid), "1 row and 2 columns contradict the clues");
+
+        *grid.get_mut_cell(Point { x: 1, y: 1 }) = Cell::Crossed;
+        *grid.get_mut_cell(Point { x: 2, y: 1 }) = Cell::Empty;
+        *grid.get_mut_cell(Point { x: 1, y: 0 }) = Cell::Filled;
+        assert_eq!(check(&mut grid), "1 column contradicts the clues");
+    }
+
+    #[test]
+    fn test_get_jump_point() {
+        let size = Size {
+            width: 10,
+            height: 10,
+        };
+        let builder = Builder::for_test(size, Point { x: 13, y: 7 });
+        let selected_cell_point = Point { x: 21, y: 11 };
+
+        assert_eq!(
+            get_jump_point(&builder, selected_cell_point, Jump::RowStart),
+            Point { x: 13, y: 11 }
+        );
+        assert_eq!(
+            get_jump_point(&builder, selected_cell_point, Jump::RowEnd),
+            Point { x: 31, y: 11 }
+        );
+        assert_eq!(
+            get_jump_point(&builder, selected_cell_point, Jump::TopLeft),
+            Point { x: 13, y: 7 }
+        );
+        assert_eq!(
+            get_jump_point(&builder, selected_cell_point, Jump::BottomRight),
+            Point { x: 31, y: 16 }
+        );
+
+        assert_eq!(
+            get_jump_point(&builder, selected_cell_point, Jump::By { x: 2, y: -1 }),
+            Point { x: 25, y: 10 }
+        );
+        // It stops at the edges
+        assert_eq!(
+            get_jump_point(&builder, selected_cell_point, Jump::By { x: -5, y: 5 }),
+            Point { x: 13, y: 16 }
+        );
+        assert_eq!(
+            get_jump_point(&builder, selected_cell_point, Jump::By { x: 5, y: -5 }),
+            Point { x: 31, y: 7 }
+        );
+
+        // All of them are within the grid
+        for jump in [
+            Jump::RowStart,
+            Jump::RowEnd,
+            Jump::TopLeft,
+            Jump::BottomRight,
+        ] {
+            assert!(builder
+                .screen_to_cell(get_jump_point(&builder, selected_cell_point, jump))
+                .is_some());
         }
     }
+
+    #[test]
+    fn test_move_selected_cell_point() {
+        let builder = Builder::for_test(
+            Size {
+                width: 10,
+                height: 10,
+            },
+            Point { x: 13, y: 7 },
+        );
+        let selected_cell_point = Point { x: 21, y: 11 };
+
+        assert_eq!(
+            move_selected_cell_point(&builder, selected_cell_point, 1, 0),
+            Point { x: 23, y: 11 }
+        );
+        assert_eq!(
+            move_selected_cell_point(&builder, selected_cell_point, 0, -1),
+            Point { x: 21, y: 10 }
+        );
+        // It wraps around at the edges
+        assert_eq!(
+            move_selected_cell_point(&builder, Point { x: 13, y: 7 }, -1, -1),
+            Point { x: 31, y: 16 }
+        );
+        assert_eq!(
+            move_selected_cell_point(&builder, Point { x: 31, y: 16 }, 1, 1),
+            Point { x: 13, y: 7 }
+        );
+    }
+
+    #[test]
+    fn test_move_selected_cell_point_in_thin_grids() {
+        let directions = [(0, -1), (0, 1), (-1, 0), (1, 0)];
+
+        for (width, height) in [(1, 1), (1, 99), (99, 1)] {
+            // At the very left of the screen, where moving to the left of the grid would underflow
+            let builder = Builder::for_test(Size { width, height }, Point { x: 0, y: 0 });
+
+            for (x, y) in directions {
+                let mut selected_cell_point = builder.get_center();
+                for _ in 0..200 {
+                    selected_cell_point =
+                        move_selected_cell_point(&builder, selected_cell_point, x, y);
+                    assert!(builder.screen_to_cell(selected_cell_point).is_some());
+                    // The point is always on the first character of a cell
+                    assert_eq!(selected_cell_point.x % 2, 0);
+                }
+                // Full turns around the grid cancel out, leaving only the remainder
+                let len = if x == 0 { height } else { width } as i32;
+                let expected_point = move_selected_cell_point(
+                    &builder,
+                    builder.get_center(),
+                    x * (200 % len),
+                    y * (200 % len),
+                );
+                assert_eq!(selected_cell_point, expected_point);
+            }
+        }
+    }
+
+    #[test]
+    fn test_grid_changing_keys_update_difficulty_label() {
+        let stdout = io::stdout();
+        let mut terminal = Terminal::new(stdout.lock()).unwrap();
+        let mut builder = Builder::for_test(
+            Size {
+                width: 2,
+                height: 2,
+            },
+            Point { x: 20, y: 10 },
+        );
+        #[rustfmt::skip]
+        let grid = Grid::from_lines(&[
+            "1 ",
+            "  ",
+        ]).unwrap();
+        builder.grid = grid;
+        let mut editor = Editor::default();
+        let mut cell_placement = CellPlacement::default();
+        let mut handle = |builder: &mut Builder, key: Key| {
+            let _ = handle_event(
+                &mut terminal,
+                key,
+                builder,
+                &mut editor,
+                &mut cell_placement,
+            );
+        };
+
+        // A wrongly filled cell contradicts the clues so there is no label
+        let point = Point { x: 1, y: 0 };
+        *builder.grid.get_mut_cell(point) = Cell::Filled;
+        builder
+            .grid
+            .undo_redo_buffer
+            .push_cells(vec![(point, Cell::Filled)], false);
+        assert!(builder.grid.get_remaining_difficulty_score().is_none());
+
+        handle(&mut builder, Key::Char('a'));
+        assert!(builder.difficulty_label.is_some());
+
+        handle(&mut builder, Key::Char('d'));
+        assert!(builder.difficulty_label.is_none());
+
+        handle(&mut builder, Key::Char('c'));
+        assert!(builder.difficulty_label.is_some());
+    }
+
+    #[test]
+    fn test_editor_toggle_marks_grid_as_edited() {
+        let mut terminal = Terminal::new(io::stdout().lock()).unwrap();
+        let size = Size {
+            width: 5,
+            height: 5,
+        };
+        let mut builder = Builder::for_test(size, Point { x: 20, y: 10 });
+        // The editor is enabled right away on a blank grid
+        builder.grid = Grid::new(size, vec![Cell::Empty; size.product() as usize]).unwrap();
+        let mut editor = Editor::default();
+        let mut cell_placement = CellPlacement::default();
+
+        for _ in 0..2 {
+            let _ = handle_event(
+                &mut terminal,
+                Key::Char('t'),
+                &mut builder,
+                &mut editor,
+                &mut cell_placement,
+            );
+        }
+        assert!(!editor.toggled);
+        // Disabling the editor again doesn't make the grid count towards the records
+        assert!(builder.edited_at_least_once);
+    }
+
+    #[test]
+    fn test_clear_marks_grid_as_cleared() {
+        let stdout = io::stdout();
+        let mut terminal = Terminal::new(stdout.lock()).unwrap();
+        let mut builder = Builder::for_test(
+            Size {
+                width: 5,
+                height: 5,
+            },
+            Point { x: 20, y: 10 },
+        );
+        assert!(!builder.cleared_at_least_once);
+
+        let _ = handle_event(
+            &mut terminal,
+            Key::Char('c'),
+            &mut builder,
+            &mut Editor::default(),
+            &mut CellPlacement::default(),
+        );
+        assert!(builder.cleared_at_least_once);
+    }
 }
@@ -1,11 +1,27 @@
-use super::{window, Alert, State};
+use super::{super::AlertSeverity, window, Alert, State};
 use crate::grid::{self, builder::Builder, Cell, CellPlacement, Grid};
+use std::time::Instant;
 use terminal::{
     event::{Event, MouseButton, MouseEvent, MouseEventKind},
-    util::Point,
+    util::{Point, Size},
     Terminal,
 };
 
+/// Checks whether the point is on the screen.
+///
+/// Some terminals report points beyond the screen when they were resized but haven't reported the resize yet.
+fn is_on_screen(screen_size: Size, point: Point) -> bool {
+    point.x < screen_size.width && point.y < screen_size.height
+}
+
+/// Moves the point onto the screen if it is beyond it.
+fn clamp_to_screen(screen_size: Size, point: Point) -> Point {
+    Point {
+        x: point.x.min(screen_size.width.saturating_sub(1)),
+        y: point.y.min(screen_size.height.saturating_sub(1)),
+    }
+}
+
 /// This handles all mouse input.
 pub fn handle_event(
     terminal: &mut Terminal,
@@ -15,19 +31,84 @@ pub fn handle_event(
     cell_placement: &mut CellPlacement,
     alert: &mut Option<Alert>,
 ) -> State {
+    if !is_on_screen(terminal.size, event.point) {
+        return if let MouseEventKind::Release(_) = event.kind {
+            // Strokes and rectangles still have to end
+            let event = MouseEvent {
+                point: clamp_to_screen(terminal.size, event.point),
+                ..event
+            };
+            handle_event(
+                terminal,
+                event,
+                builder,
+                editor_toggled,
+                cell_placement,
+                alert,
+            )
+        } else {
+            State::Continue
+        };
+    }
+
     match event {
+        MouseEvent {
+            kind: MouseEventKind::Press(mouse_button),
+            point: selected_cell_point,
+        } if cell_placement.rect_fill_mode
+            && !editor_toggled
+            && builder.screen_to_cell(selected_cell_point).is_some() =>
+        {
+            cell_placement.hovered_cell_point = Some(selected_cell_point);
+            cell_placement.start_rect(
+                terminal,
+                builder,
+                selected_cell_point,
+                get_cell_to_place(mouse_button, cell_placement),
+            );
+            State::Continue
+        }
+        MouseEvent {
+            kind: MouseEventKind::Drag(_),
+            point: selected_cell_point,
+        } if cell_placement.rect_start.is_some() => {
+            cell_placement.preview_rect(terminal, builder, selected_cell_point);
+            State::Continue
+        }
+        MouseEvent {
+            kind: MouseEventKind::Release(_),
+            point: selected_cell_point,
+        } if cell_placement.rect_start.is_some() => {
+            cell_placement.place_rect(terminal, builder, selected_cell_point)
+        }
         MouseEvent {
             kind: MouseEventKind::Drag(mouse_button) | MouseEventKind::Press(mouse_button),
             point: selected_cell_point,
         } => {
-            if builder.contains(selected_cell_point) {
-                let cell_to_place = match mouse_button {
-                    MouseButton::Left => Cell::Filled,
-                    MouseButton::Middle => Cell::Maybed,
-                    MouseButton::Right => Cell::Crossed,
-                };
+            if builder.screen_to_cell(selected_cell_point).is_some() {
+                let cell_to_place = get_cell_to_place(mouse_button, cell_placement);
+
+                cell_placement.hovered_cell_point = Some(selected_cell_point);
+
+                if let MouseEventKind::Press(_) = event.kind {
+                    if !cell_placement.measurement_points.is_empty() {
+                        return cell_placement.add_measurement_waypoint(
+                            terminal,
+                            builder,
+                            selected_cell_point,
+                        );
+                    }
 
-                cell_placement.selected_cell_point = Some(selected_cell_point);
+                    if cell_placement.register_click(selected_cell_point, Instant::now()) {
+                        return cell_placement.place_line(
+                            terminal,
+                            builder,
+                            selected_cell_point,
+                            cell_to_place,
+                            editor_toggled,
+                        );
+                    }
+                }
 
                 cell_placement.place(
                     terminal,
@@ -53,18 +134,30 @@ pub fn handle_event(
                 }
             }
         }
+        MouseEvent {
+            kind: MouseEventKind::ScrollUp,
+            point,
+        } => scroll(terminal, builder, cell_placement, point, true),
+        MouseEvent {
+            kind: MouseEventKind::ScrollDown,
+            point,
+        } => scroll(terminal, builder, cell_placement, point, false),
         MouseEvent {
             kind: MouseEventKind::Move,
             point,
         } => {
             builder.draw_grid(terminal);
 
-            if builder.contains(point) {
-                cell_placement.selected_cell_point = Some(point);
-                let some_selected_cell_point = point;
+            if builder.screen_to_cell(point).is_some() {
+                // The keyboard cursor stays where it is
+                cell_placement.hovered_cell_point = Some(point);
 
                 // We know that this point is hovered
-                grid::draw_highlighted_cells(terminal, builder, some_selected_cell_point);
+                grid::draw_highlighted_cells(terminal, builder, point);
+            } else {
+                cell_placement.hovered_cell_point = None;
+                crate::clear_hover_status(terminal, builder);
+                crate::clear_compact_clues(terminal, builder);
             }
             State::Continue
         }
@@ -75,6 +168,62 @@ pub fn handle_event(
     }
 }
 
+/// Gets the cell that the mouse button places.
+const fn get_cell_to_place(mouse_button: MouseButton, cell_placement: &CellPlacement) -> Cell {
+    if cell_placement.erase {
+        return Cell::Empty;
+    }
+
+    match mouse_button {
+        MouseButton::Left => Cell::Filled,
+        MouseButton::Middle => Cell::Maybed,
+        MouseButton::Right => Cell::Crossed,
+    }
+}
+
+/// Moves the selected cell one cell up or down, stopping at the edges of the grid.
+fn scroll_selected_cell_point(builder: &Builder, selected_cell_point: Point, up: bool) -> Point {
+    let y = if up {
+        selected_cell_point.y.saturating_sub(1).max(builder.point.y)
+    } else {
+        (selected_cell_point.y + 1).min(builder.point.y + builder.grid.size.height - 1)
+    };
+
+    Point {
+        y,
+        ..selected_cell_point
+    }
+}
+
+/// Scrolling over the grid moves the selected cell and scrolling over the left clues scrolls the top clues.
+fn scroll(
+    terminal: &mut Terminal,
+    builder: &mut Builder,
+    cell_placement: &mut CellPlacement,
+    point: Point,
+    up: bool,
+) -> State {
+    if builder.screen_to_cell(point).is_some() {
+        let selected_cell_point = match cell_placement.selected_cell_point {
+            Some(selected_cell_point) if builder.screen_to_cell(selected_cell_point).is_some() => {
+                selected_cell_point
+            }
+            _ => point,
+        };
+        let selected_cell_point = scroll_selected_cell_point(builder, selected_cell_point, up);
+        cell_placement.selected_cell_point = Some(selected_cell_point);
+
+        builder.draw_grid(terminal);
+
+        // We know that this point is hovered
+        grid::draw_highlighted_cells(terminal, builder, selected_cell_point);
+    } else if builder.left_clues_contain(point) {
+        builder.scroll_clues(terminal, up);
+    }
+
+    State::Continue
+}
+
 fn resize_grid(
     terminal: &mut Terminal,
     builder: &mut Builder,
@@ -182,13 +331,10 @@ fn resize_grid(
         builder.grid.size = new_grid_size;
 
         if confirmed {
-            // Currently the new game simply runs inside of this existing game and the new game creates an entirely new state.
-            // At some point we would probably hit a stack overflow if the user keeps resizing the grid within the same session.
-
-            terminal.clear();
-            crate::start_game(terminal, Grid::random(builder.grid.size));
-
-            State::Exit(None)
+            // The width and height were clamped to at least 1 while dragging
+            let grid = Grid::random(builder.grid.size, builder.empty_lines)
+                .expect("the grid size is never 0");
+            State::NewGrid(Box::new(grid))
         } else {
             builder.grid.size = original_grid_size;
 
@@ -201,8 +347,231 @@ fn resize_grid(
             }
 
             crate::draw_basic_controls_help(terminal, builder);
+            builder.draw_difficulty_label(terminal);
+
+            State::Alert("Canceled".into(), AlertSeverity::Info)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_builder() -> Builder {
+        Builder::for_test(
+            Size {
+                width: 5,
+                height: 5,
+            },
+            Point { x: 20, y: 10 },
+        )
+    }
+
+    #[test]
+    fn test_get_cell_to_place() {
+        let mut cell_placement = CellPlacement::default();
+        assert_eq!(
+            get_cell_to_place(MouseButton::Right, &cell_placement),
+            Cell::Crossed
+        );
+
+        cell_placement.erase = true;
+        for mouse_button in [MouseButton::Left, MouseButton::Middle, MouseButton::Right] {
+            assert_eq!(
+                get_cell_to_place(mouse_button, &cell_placement),
+                Cell::Empty
+            );
+        }
+    }
+
+    #[test]
+    fn test_double_click() {
+        let stdout = std::io::stdout();
+        let mut terminal = Terminal::new(stdout.lock()).unwrap();
+        let mut builder = get_builder();
+        let mut cell_placement = CellPlacement::default();
+        let mut alert = None;
+
+        // The center cell of the grid
+        let event = MouseEvent {
+            kind: MouseEventKind::Press(MouseButton::Left),
+            point: Point { x: 24, y: 12 },
+        };
+        for _ in 0..2 {
+            let _ = handle_event(
+                &mut terminal,
+                event,
+                &mut builder,
+                false,
+                &mut cell_placement,
+                &mut alert,
+            );
+        }
+
+        for x in 0..builder.grid.size.width {
+            assert_eq!(builder.grid.get_cell(Point { x, y: 2 }), Cell::Filled);
+        }
+        assert_eq!(builder.grid.get_cell(Point { x: 2, y: 1 }), Cell::Empty);
 
-            State::Alert("Canceled".into())
+        // The whole row is undone at once, leaving the cell of the first click
+        assert!(builder.grid.undo_last_cell());
+        assert_eq!(builder.grid.get_cell(Point { x: 2, y: 2 }), Cell::Filled);
+        assert_eq!(builder.grid.get_cell(Point { x: 0, y: 2 }), Cell::Empty);
+    }
+
+    #[test]
+    fn test_mouse_keeps_keyboard_cursor() {
+        let stdout = std::io::stdout();
+        let mut terminal = Terminal::new(stdout.lock()).unwrap();
+        let mut builder = get_builder();
+        let keyboard_cursor = Point { x: 20, y: 10 };
+        let mut cell_placement = CellPlacement {
+            selected_cell_point: Some(keyboard_cursor),
+            ..Default::default()
+        };
+        let mut alert = None;
+
+        for kind in [
+            MouseEventKind::Move,
+            MouseEventKind::Press(MouseButton::Left),
+        ] {
+            let point = Point { x: 24, y: 12 };
+            let _ = handle_event(
+                &mut terminal,
+                MouseEvent { kind, point },
+                &mut builder,
+                false,
+                &mut cell_placement,
+                &mut alert,
+            );
+            assert_eq!(cell_placement.selected_cell_point, Some(keyboard_cursor));
+            assert_eq!(cell_placement.hovered_cell_point, Some(point));
+            assert_eq!(cell_placement.get_key_point(), Some(keyboard_cursor));
+        }
+
+        // Clicks act on the mouse point
+        assert_eq!(builder.grid.get_cell(Point { x: 2, y: 2 }), Cell::Filled);
+        assert_eq!(builder.grid.get_cell(Point { x: 0, y: 0 }), Cell::Empty);
+    }
+
+    #[test]
+    fn test_press_outside_grid() {
+        let stdout = std::io::stdout();
+        let mut terminal = Terminal::new(stdout.lock()).unwrap();
+        let mut builder = get_builder();
+        let mut cell_placement = CellPlacement::default();
+        let mut alert = None;
+
+        for point in [
+            // The top clues
+            Point { x: 20, y: 9 },
+            // The left clues
+            Point { x: 19, y: 10 },
+            // Right of the last cell
+            Point { x: 30, y: 14 },
+            // The progress bar
+            Point { x: 20, y: 15 },
+        ] {
+            for kind in [
+                MouseEventKind::Press(MouseButton::Left),
+                MouseEventKind::Drag(MouseButton::Left),
+                MouseEventKind::Move,
+            ] {
+                let _ = handle_event(
+                    &mut terminal,
+                    MouseEvent { kind, point },
+                    &mut builder,
+                    false,
+                    &mut cell_placement,
+                    &mut alert,
+                );
+            }
+        }
+
+        assert_eq!(cell_placement.selected_cell_point, None);
+        assert_eq!(cell_placement.hovered_cell_point, None);
+        assert!(builder.grid.cells.iter().all(|cell| *cell == Cell::Empty));
+    }
+
+    #[test]
+    fn test_point_beyond_screen() {
+        let stdout = std::io::stdout();
+        let mut terminal = Terminal::new(stdout.lock()).unwrap();
+        // The grid is beyond this screen
+        terminal.size = Size {
+            width: 20,
+            height: 10,
+        };
+        let mut builder = get_builder();
+        let mut cell_placement = CellPlacement::default();
+        let mut alert = None;
+
+        for kind in [
+            MouseEventKind::Press(MouseButton::Left),
+            MouseEventKind::Drag(MouseButton::Left),
+            MouseEventKind::Move,
+            MouseEventKind::ScrollDown,
+            MouseEventKind::Release(MouseButton::Left),
+        ] {
+            let state = handle_event(
+                &mut terminal,
+                MouseEvent {
+                    kind,
+                    point: Point { x: 24, y: 12 },
+                },
+                &mut builder,
+                false,
+                &mut cell_placement,
+                &mut alert,
+            );
+            assert!(matches!(state, State::Continue));
         }
+
+        assert_eq!(cell_placement.selected_cell_point, None);
+        assert_eq!(cell_placement.hovered_cell_point, None);
+        assert_eq!(cell_placement.starting_time, None);
+        assert!(builder.grid.cells.iter().all(|cell| *cell == Cell::Empty));
+        assert!(builder.grid.undo_redo_buffer.buffer.is_empty());
+    }
+
+    #[test]
+    fn test_clamp_to_screen() {
+        let screen_size = Size {
+            width: 20,
+            height: 10,
+        };
+        assert!(is_on_screen(screen_size, Point { x: 19, y: 9 }));
+        assert!(!is_on_screen(screen_size, Point { x: 20, y: 9 }));
+        assert_eq!(
+            clamp_to_screen(screen_size, Point { x: 25, y: 3 }),
+            Point { x: 19, y: 3 }
+        );
+    }
+
+    #[test]
+    fn test_scroll_selected_cell_point() {
+        let builder = get_builder();
+
+        let point = Point { x: 22, y: 12 };
+        assert_eq!(
+            scroll_selected_cell_point(&builder, point, true),
+            Point { x: 22, y: 11 }
+        );
+        assert_eq!(
+            scroll_selected_cell_point(&builder, point, false),
+            Point { x: 22, y: 13 }
+        );
+
+        let top_point = Point { x: 22, y: 10 };
+        assert_eq!(
+            scroll_selected_cell_point(&builder, top_point, true),
+            top_point
+        );
+        let bottom_point = Point { x: 22, y: 14 };
+        assert_eq!(
+            scroll_selected_cell_point(&builder, bottom_point, false),
+            bottom_point
+        );
     }
 }
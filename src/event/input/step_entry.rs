@@ -0,0 +1,72 @@
+//! Lets the player jump to any step of the undo history at once instead of undoing or redoing one step at a time.
+
+use super::{window, Alert, State};
+use crate::{
+    event::alert::{self, AlertSeverity},
+    grid::{builder::Builder, CellPlacement},
+};
+use terminal::{
+    event::{Event, Key},
+    Terminal,
+};
+
+/// Lets the player enter the number of the step to jump to until Enter or Esc is pressed.
+///
+/// Step 0 is the grid before any operation.
+pub fn enter_step(
+    terminal: &mut Terminal,
+    builder: &mut Builder,
+    alert: &mut Option<Alert>,
+    cell_placement: &mut CellPlacement,
+) -> State {
+    let operation_count = builder.grid.operation_count();
+    let mut input = String::new();
+
+    loop {
+        let prompt = format!(
+            "Step {}/{}, jump to: {}_ Enter to jump, Esc to cancel",
+            builder.grid.current_operation_index(),
+            operation_count,
+            input
+        );
+        alert::draw(terminal, builder, alert, prompt.into(), AlertSeverity::Info);
+        terminal.flush();
+
+        match terminal.read_event() {
+            Some(Event::Key(Key::Esc)) => {
+                return State::Alert("Canceled".into(), AlertSeverity::Info)
+            }
+            Some(Event::Key(Key::Enter)) => break,
+            Some(Event::Key(Key::Backspace)) => {
+                input.pop();
+            }
+            Some(Event::Key(Key::Char(char))) if char.is_ascii_digit() => input.push(char),
+            Some(Event::Resize) => {
+                if let State::Exit(_) = window::relayout(terminal, builder, alert, cell_placement) {
+                    return State::Exit(None);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let step = match input.parse::<usize>() {
+        Ok(step) if builder.grid.jump_to(step) => step,
+        _ => {
+            return State::Alert(
+                format!("Step must be between 0 and {}", operation_count).into(),
+                AlertSeverity::Warning,
+            )
+        }
+    };
+
+    // Transforms might have changed the clues and the size
+    if let State::Exit(_) = window::relayout(terminal, builder, alert, cell_placement) {
+        return State::Exit(None);
+    }
+
+    State::Alert(
+        format!("Jumped to step {}/{}", step, operation_count).into(),
+        AlertSeverity::Info,
+    )
+}
@@ -0,0 +1,64 @@
+//! Lets the editor give the grid a title that is saved along with it.
+
+use super::{window, Alert, State};
+use crate::{
+    editor::Editor,
+    event::alert::{self, AlertSeverity},
+    grid::{builder::Builder, CellPlacement},
+};
+use terminal::{
+    event::{Event, Key},
+    Terminal,
+};
+
+/// The metadata key that the title is saved as.
+const TITLE_KEY: &str = "title";
+
+/// Lets the user edit the title of the grid until Enter or Esc is pressed. An empty title removes it.
+pub fn enter_title(
+    terminal: &mut Terminal,
+    builder: &mut Builder,
+    alert: &mut Option<Alert>,
+    cell_placement: &mut CellPlacement,
+) -> State {
+    let mut input = builder
+        .grid
+        .metadata
+        .get(TITLE_KEY)
+        .cloned()
+        .unwrap_or_default();
+
+    loop {
+        let prompt = format!("Title: {}_ Enter to set, Esc to cancel", input);
+        alert::draw(terminal, builder, alert, prompt.into(), AlertSeverity::Info);
+        terminal.flush();
+
+        match terminal.read_event() {
+            Some(Event::Key(Key::Esc)) => {
+                return State::Alert("Canceled".into(), AlertSeverity::Info)
+            }
+            Some(Event::Key(Key::Enter)) => break,
+            Some(Event::Key(Key::Backspace)) => {
+                input.pop();
+            }
+            Some(Event::Key(Key::Char(char))) => input.push(char),
+            Some(Event::Resize) => {
+                if let State::Exit(_) = window::relayout(terminal, builder, alert, cell_placement) {
+                    return State::Exit(None);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Editor::set_metadata(&mut builder.grid, TITLE_KEY, &input);
+
+    if let Some(title) = builder.grid.metadata.get(TITLE_KEY) {
+        State::Alert(
+            format!("Title set to \"{}\"", title).into(),
+            AlertSeverity::Info,
+        )
+    } else {
+        State::Alert("Title removed".into(), AlertSeverity::Info)
+    }
+}
@@ -1,8 +1,8 @@
-use super::super::alert;
+use super::super::alert::{self, AlertSeverity};
 use super::{Alert, State};
 use crate::{
     args::{valid_extension, FILE_EXTENSION},
-    grid::{self, builder::Builder, Grid},
+    grid::{self, builder::Builder, CellPlacement, Grid},
 };
 use std::time::Instant;
 use terminal::{
@@ -11,17 +11,48 @@ use terminal::{
     Terminal,
 };
 
-pub fn handle_resize(
+/// Positions and draws everything again.
+///
+/// This is done when the window is resized or when the space needed by the grid changed.
+pub fn relayout(
     terminal: &mut Terminal,
     builder: &mut Builder,
     alert: &Option<Alert>,
-    starting_time: Option<Instant>,
+    cell_placement: &mut CellPlacement,
 ) -> State {
     terminal.clear();
 
-    let state = await_fitting_size(terminal, &builder.grid, starting_time);
+    let state = await_fitting_size(
+        terminal,
+        &builder.grid,
+        builder.legend,
+        cell_placement.starting_time,
+    );
+    // The terminal might still be too small to draw anything, including an exit confirmation
+    if let State::Exit(_) = state {
+        return State::Exit(None);
+    }
 
+    let previous_point = builder.point;
     builder.point = grid::builder::centered_point(terminal, &builder.grid);
+    builder.compact_clues = !grid::builder::fits_left_clues(terminal, &builder.grid);
+    // The zoomed picture might not fit anymore
+    builder.picture_zoom = builder.picture_zoom.min(builder.get_max_picture_zoom());
+    builder.clue_scroll_y = builder.clue_scroll_y.min(builder.get_max_clue_scroll_y());
+
+    // Move the points that are on the grid along with it
+    let move_along = |point: &mut Point| {
+        point.x = point.x - previous_point.x + builder.point.x;
+        point.y = point.y - previous_point.y + builder.point.y;
+    };
+    if let Some(selected_cell_point) = &mut cell_placement.selected_cell_point {
+        move_along(selected_cell_point);
+    }
+    // The mouse stays where it is, so it isn't known what it hovers until it moves
+    cell_placement.hovered_cell_point = None;
+    for measurement_point in &mut cell_placement.measurement_points {
+        move_along(measurement_point);
+    }
 
     // The grid wasn't mutated
     #[allow(unused_must_use)]
@@ -30,6 +61,7 @@ pub fn handle_resize(
     }
 
     crate::draw_basic_controls_help(terminal, builder);
+    builder.draw_difficulty_label(terminal);
     if let Some(alert) = alert {
         alert.draw(terminal, builder);
     }
@@ -40,21 +72,27 @@ pub fn handle_resize(
 pub fn await_fitting_size(
     terminal: &mut Terminal,
     grid: &Grid,
+    legend: bool,
     starting_time: Option<Instant>,
 ) -> State {
+    /// If the left clues don't fit next to the grid, they are shown compactly instead.
     const fn terminal_width_is_within_grid_width(grid: &Grid, terminal: &Terminal) -> bool {
-        terminal.size.width >= grid.size.width * 2 + grid.max_clues_size.width
+        terminal.size.width >= grid.size.width * 2
     }
 
-    fn terminal_height_is_within_grid_height(grid: &Grid, terminal: &Terminal) -> bool {
-        terminal.size.height > crate::total_height(grid)
+    fn terminal_height_is_within_grid_height(
+        grid: &Grid,
+        legend: bool,
+        terminal: &Terminal,
+    ) -> bool {
+        terminal.size.height > crate::total_height(grid, legend)
     }
 
     let mut state = State::Continue;
 
     match (
         terminal_width_is_within_grid_width(grid, terminal),
-        terminal_height_is_within_grid_height(grid, terminal),
+        terminal_height_is_within_grid_height(grid, legend, terminal),
     ) {
         (true, true) => state,
         (within_width, within_height) => {
@@ -74,7 +112,7 @@ pub fn await_fitting_size(
             let state = loop {
                 match (
                     terminal_width_is_within_grid_width(grid, terminal),
-                    terminal_height_is_within_grid_height(grid, terminal),
+                    terminal_height_is_within_grid_height(grid, legend, terminal),
                 ) {
                     (true, true) => break state,
                     _ => {
@@ -122,7 +160,7 @@ pub fn await_dropped_grid_file_path(
         FILE_EXTENSION
     )
     .into();
-    alert::draw(terminal, builder, alert, message);
+    alert::draw(terminal, builder, alert, message, AlertSeverity::Info);
 
     terminal.flush();
 
@@ -142,6 +180,9 @@ pub fn await_dropped_grid_file_path(
                     path.push(char);
                 }
             }
+            Some(Event::Key(Key::Backspace)) => {
+                path.pop();
+            }
             Some(Event::Key(Key::Esc)) => {
                 return Err("Canceled");
             }
@@ -167,11 +208,13 @@ pub fn confirmation_prompt(
     verb_to_confirm: &str,
 ) -> bool {
     let message = format!("Press Enter to {}; Esc to cancel", verb_to_confirm).into();
-    alert::draw(terminal, builder, alert, message);
+    alert::draw(terminal, builder, alert, message, AlertSeverity::Warning);
 
     // We could also just ignore `Event::Mouse(_)` in the loop below but disabling mouse capture changes the pointer icon
     // in some terminals, which helps inform the user that the game is halted and that they can't use their mouse and need to respond to the confirmation prompt.
-    terminal.disable_mouse_capture();
+    if builder.mouse {
+        terminal.disable_mouse_capture();
+    }
 
     terminal.flush();
 
@@ -185,7 +228,9 @@ pub fn confirmation_prompt(
         }
     };
 
-    terminal.enable_mouse_capture();
+    if builder.mouse {
+        terminal.enable_mouse_capture();
+    }
 
     confirmed
 }
@@ -0,0 +1,69 @@
+//! Where the events that the game handles come from: the terminal or, for automated testing, a script.
+
+use std::{collections::VecDeque, mem, time::Duration};
+use terminal::{event::Event, Terminal};
+
+/// How many events were handled and how often the terminal was flushed while doing so.
+#[derive(Debug, Default)]
+pub struct Stats {
+    pub event_count: usize,
+    pub flush_count: usize,
+}
+
+pub enum EventSource {
+    /// The events are read from the terminal as the player causes them.
+    Terminal,
+    /// The events are handed out one after another without waiting, as if they happened as fast as possible.
+    Scripted {
+        events: VecDeque<Event>,
+        stats: Stats,
+    },
+}
+
+impl EventSource {
+    pub fn scripted(events: VecDeque<Event>) -> Self {
+        Self::Scripted {
+            events,
+            stats: Stats::default(),
+        }
+    }
+
+    /// Waits for an event for at most the timeout.
+    pub fn poll(&mut self, terminal: &mut Terminal, timeout: Duration) -> Option<Event> {
+        match self {
+            Self::Terminal => terminal.poll_event(timeout),
+            Self::Scripted { events, stats } => {
+                let event = events.pop_front();
+                if event.is_some() {
+                    stats.event_count += 1;
+                }
+                event
+            }
+        }
+    }
+
+    /// Checks whether there will never be another event, which is the case once a script has run out.
+    pub fn is_exhausted(&self) -> bool {
+        match self {
+            Self::Terminal => false,
+            Self::Scripted { events, .. } => events.is_empty(),
+        }
+    }
+
+    /// Flushes the terminal, counting the flush if the events are scripted.
+    pub fn flush(&mut self, terminal: &mut Terminal) {
+        terminal.flush();
+
+        if let Self::Scripted { stats, .. } = self {
+            stats.flush_count += 1;
+        }
+    }
+
+    /// Takes the stats of the scripted events so far, if the events are scripted.
+    pub fn take_stats(&mut self) -> Option<Stats> {
+        match self {
+            Self::Terminal => None,
+            Self::Scripted { stats, .. } => Some(mem::take(stats)),
+        }
+    }
+}
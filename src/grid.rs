@@ -1,18 +1,30 @@
-pub mod builder;
+mod board;
+pub(crate) mod builder;
 mod cell;
+mod export;
+pub mod palette;
 mod random;
+mod solver;
+mod stats;
 pub mod tools;
 
 use crate::undo_redo_buffer::UndoRedoBuffer;
-pub use cell::*;
+pub(crate) use cell::*;
+pub use cell::{Cell, Line};
 use itertools::Itertools;
+pub use solver::{get_difficulty_label, Difficulty};
+pub use stats::Stats;
+use std::{borrow::Cow, collections::BTreeMap, convert::TryFrom};
 use terminal::util::{Point, Size};
 
 /// A single clue specifying how many cells there are in a row at some point.
-type Clue = u16;
+pub type Clue = u16;
 /// A complete set of clues.
-type Clues = Vec<Clue>;
+pub type Clues = Vec<Clue>;
+/// Information about a grid by key, for example its `title` and `author`.
+pub type Metadata = BTreeMap<String, String>;
 
+#[derive(Clone)]
 pub struct Grid {
     pub size: Size,
     /// This is where the player's input is stored. It is initially empty.
@@ -22,8 +34,36 @@ pub struct Grid {
     /// The vertical clue solutions generated out of the initial input.
     pub vertical_clues_solutions: Vec<Clues>,
     pub max_clues_size: Size,
+    /// The picture that the clue solutions were generated out of, if it is known to fulfill them.
+    pub solution: Option<Vec<Cell>>,
     pub undo_redo_buffer: UndoRedoBuffer,
     pub measurement_counter: usize,
+    /// Information about the grid such as its `title` and `author`, as recorded in the grid file.
+    pub metadata: Metadata,
+    /// What is known about the rows as of the last time they were looked at.
+    ///
+    /// NOTE: after changing the cells through `cells` instead of [`Grid::get_mut_cell`], [`Grid::invalidate_line_caches`] needs to be called.
+    row_caches: Vec<LineCache>,
+    /// Like `row_caches` but for the columns.
+    column_caches: Vec<LineCache>,
+}
+
+/// What is known about a row or column, if its cells and clue solution didn't change since it was determined.
+#[derive(Clone, Default)]
+struct LineCache {
+    /// The runs of filled cells, which are the clues that the line currently has.
+    runs: Option<Clues>,
+    status: Option<LineStatus>,
+}
+
+impl LineCache {
+    /// Gets the runs of the row or column at the index of the cells, only determining them if they aren't known.
+    fn get_runs(&mut self, cells: &[Cell], size: Size, line: &Line, index: u16) -> &[Clue] {
+        self.runs.get_or_insert_with(|| match line {
+            Line::Row => get_horizontal_clues(cells, size.width, index).collect(),
+            Line::Column => get_vertical_clues(cells, size, index).collect(),
+        })
+    }
 }
 
 fn get_index(grid_width: u16, point: Point) -> usize {
@@ -50,10 +90,194 @@ fn get_vertical_clues(cells: &[Cell], grid_size: Size, x: u16) -> impl Iterator<
         .map(|(count, _)| count as Clue)
 }
 
+/// Whether a single row or column is solved or can't be solved anymore.
+/// Why a grid couldn't be created out of the given size and cells.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GridError {
+    /// The amount of cells is not the product of the width and height.
+    SizeMismatch { expected: usize, actual: usize },
+    /// The width or height is 0.
+    ZeroDimension,
+    /// The amount of rows or columns with clues is not the height or width.
+    ClueCountMismatch { expected: usize, actual: usize },
+    /// The clues of the rows add up to a different amount of filled cells than the clues of the columns.
+    ClueSumMismatch { row_sum: usize, column_sum: usize },
+}
+
+impl GridError {
+    pub const fn message(self) -> &'static str {
+        match self {
+            Self::SizeMismatch { .. } => "cell count does not match the grid size",
+            Self::ZeroDimension => "grid must be at least one cell wide and high",
+            Self::ClueCountMismatch { .. } => "clue count does not match the grid size",
+            Self::ClueSumMismatch { .. } => "row and column clues must add up to the same amount",
+        }
+    }
+}
+
+/// Checks that a grid of the size can be made out of the given amount of cells.
+fn check_size(size: Size, cell_count: usize) -> Result<(), GridError> {
+    if size.width == 0 || size.height == 0 {
+        return Err(GridError::ZeroDimension);
+    }
+
+    let expected = size.product() as usize;
+    if cell_count != expected {
+        return Err(GridError::SizeMismatch {
+            expected,
+            actual: cell_count,
+        });
+    }
+
+    Ok(())
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LineStatus {
+    /// The filled cells don't match the clues yet but they can still be completed to match them.
+    Incomplete,
+    Solved,
+    /// Some of the filled cells have to be emptied again for the line to match its clues,
+    /// for example because a run of filled cells is longer than any clue or there are too many filled cells.
+    Contradicted,
+}
+
+/// Checks whether the filled cells of the line, whose runs are given, match the clue solution or can still be completed to match it.
+///
+/// Only filled cells are considered because crossed out and maybed cells are only notes of the player.
+fn get_line_status(
+    line: impl Iterator<Item = Cell>,
+    runs: &[Clue],
+    clues_solution: &[Clue],
+) -> LineStatus {
+    if runs == clues_solution {
+        return LineStatus::Solved;
+    }
+
+    let mut line: Vec<Option<bool>> = line
+        .map(|cell| (cell == Cell::Filled).then_some(true))
+        .collect();
+
+    if solver::solve_line(clues_solution, &mut line).is_none() {
+        LineStatus::Contradicted
+    } else {
+        LineStatus::Incomplete
+    }
+}
+
+/// A cell that contradicts the solution.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mistake {
+    /// The cell is filled but it isn't filled in the solution.
+    WrongFill,
+    /// The cell is crossed out but it is filled in the solution.
+    WrongCross,
+}
+
+impl Mistake {
+    /// Classifies the cell as a mistake, if it is one, by comparing it to the cell at the same point in the solution.
+    const fn classify(cell: Cell, solution_cell: Cell) -> Option<Self> {
+        match (cell, solution_cell) {
+            (Cell::Filled, Cell::Empty | Cell::Crossed) => Some(Self::WrongFill),
+            (Cell::Crossed, Cell::Filled) => Some(Self::WrongCross),
+            _ => None,
+        }
+    }
+}
+
+/// How far a single row or column is from being solved.
+#[derive(Debug, PartialEq)]
+pub struct LineProgress {
+    /// The amount of clues that match the clue solution at the same position.
+    pub matching_clues: usize,
+    /// The amount of clues in the clue solution.
+    pub clues: usize,
+    pub solved: bool,
+}
+
+fn get_line_progress(clues: impl Iterator<Item = Clue>, clues_solution: &[Clue]) -> LineProgress {
+    let clues: Clues = clues.collect();
+
+    let matching_clues = clues
+        .iter()
+        .zip(clues_solution)
+        .filter(|(clue, clue_solution)| clue == clue_solution)
+        .count();
+
+    LineProgress {
+        matching_clues,
+        clues: clues_solution.len(),
+        solved: clues == clues_solution,
+    }
+}
+
+/// Gets how many more cells have to be filled for the line to have as many filled cells as its clues require.
+///
+/// This says nothing about whether the filled cells are in the right place.
+fn get_remaining_cells(clues: impl Iterator<Item = Clue>, clues_solution: &[Clue]) -> u16 {
+    let filled_cells: u16 = clues.sum();
+    let required_cells: u16 = clues_solution.iter().sum();
+
+    required_cells.saturating_sub(filled_cells)
+}
+
+/// Checks whether the clues fit into a line of the given length, with at least one empty cell between them.
+pub fn clues_fit(clues_solution: &[Clue], line_len: u16) -> bool {
+    let needed_len = clues_solution
+        .iter()
+        .map(|clue| *clue as usize)
+        .sum::<usize>()
+        + clues_solution.len().saturating_sub(1);
+
+    needed_len <= line_len as usize
+}
+
+/// The least amount of characters that a clue takes up, which is also the width of a cell.
+const MIN_CLUE_WIDTH: u16 = 2;
+
+/// Gets how many characters each of the left clues takes up, which depends on the largest clue so that all of them fit.
+///
+/// The top clues are always as wide as the cells because the grid is at most 99 cells high.
+pub fn get_left_clue_width(horizontal_clues_solutions: &[Clues]) -> u16 {
+    let max_clue = horizontal_clues_solutions
+        .iter()
+        .flatten()
+        .max()
+        .copied()
+        .unwrap_or_default();
+
+    (max_clue.to_string().len() as u16).max(MIN_CLUE_WIDTH)
+}
+
+/// Computes the space needed to draw all the given clues.
+///
+/// Space for at least one clue is always reserved so that empty lines can be drawn as `0`.
+fn get_max_clues_size(
+    horizontal_clues_solutions: &[Clues],
+    vertical_clues_solutions: &[Clues],
+) -> Size {
+    let clue_width = get_left_clue_width(horizontal_clues_solutions) as usize;
+    let max_clues_width = horizontal_clues_solutions
+        .iter()
+        .map(|horizontal_clues_solution| horizontal_clues_solution.len().max(1) * clue_width)
+        .max()
+        .unwrap() as u16; // The iterator won't be empty
+    let max_clues_height = vertical_clues_solutions
+        .iter()
+        .map(|vertical_clues_solution| vertical_clues_solution.len().max(1))
+        .max()
+        .unwrap() as u16; // The iterator won't be empty
+
+    Size {
+        width: max_clues_width,
+        height: max_clues_height,
+    }
+}
+
 impl Grid {
-    /// Creates a new grid. `cells`' `len` must be equal to the product of the width and height of `size`.
-    pub fn new(size: Size, mut cells: Vec<Cell>) -> Self {
-        debug_assert_eq!(cells.len(), size.product() as usize);
+    /// Creates a new grid. `cells`' `len` must be equal to the product of the width and height of `size`, neither of which may be 0.
+    pub fn new(size: Size, cells: Vec<Cell>) -> Result<Self, GridError> {
+        check_size(size, cells.len())?;
 
         let mut horizontal_clues_solutions = Vec::<Clues>::new();
         for y in 0..size.height {
@@ -61,22 +285,170 @@ impl Grid {
                 get_horizontal_clues(&cells, size.width, y).collect();
             horizontal_clues_solutions.push(horizontal_clues_solution);
         }
-        let max_clues_width = horizontal_clues_solutions
-            .iter()
-            .map(|horizontal_clues_solution| horizontal_clues_solution.len() * 2)
-            .max()
-            .unwrap() as u16; // The iterator won't be empty
 
         let mut vertical_clues_solutions = Vec::<Clues>::new();
         for x in 0..size.width {
             let vertical_clues_solution: Clues = get_vertical_clues(&cells, size, x).collect();
             vertical_clues_solutions.push(vertical_clues_solution);
         }
-        let max_clues_height = vertical_clues_solutions
+
+        Self::with_clues_solutions(
+            size,
+            cells,
+            horizontal_clues_solutions,
+            vertical_clues_solutions,
+        )
+    }
+
+    /// Creates a new grid from the given lines of strings.
+    /// A `1` represents a filled cell, a ` ` represents an empty cell.
+    ///
+    /// Lines that are shorter than the longest one are padded with empty cells.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yayagram::grid::Grid;
+    ///
+    /// let lines = [
+    ///    "111 1",
+    ///    "11111",
+    ///    "1 111"
+    /// ];
+    /// let grid = Grid::from_lines(&lines).unwrap();
+    /// assert_eq!(grid.horizontal_clues_solutions, [vec![3, 1], vec![5], vec![1, 3]]);
+    /// ```
+    pub fn from_lines(lines: &[&str]) -> Result<Self, Cow<'static, str>> {
+        let width = lines
             .iter()
-            .map(|vertical_clues_solution| vertical_clues_solution.len())
+            .map(|line| line.chars().count())
             .max()
-            .unwrap() as u16; // The iterator won't be empty
+            .unwrap_or(0);
+        if width == 0 {
+            return Err("Grid must have at least one cell".into());
+        }
+        let size = Size {
+            width: u16::try_from(width).map_err(|_| "Grid is too wide")?,
+            height: u16::try_from(lines.len()).map_err(|_| "Grid is too high")?,
+        };
+
+        let mut cells = Vec::<Cell>::with_capacity(size.product() as usize);
+        for (index, line) in lines.iter().enumerate() {
+            for char in line.chars() {
+                cells.push(match char {
+                    '1' => Cell::Filled,
+                    ' ' => Cell::Empty,
+                    _ => {
+                        return Err(format!(
+                            "Line {} must only contain '1' or ' ', not '{}'",
+                            index + 1,
+                            char
+                        )
+                        .into())
+                    }
+                });
+            }
+            for _ in line.chars().count()..width {
+                cells.push(Cell::Empty);
+            }
+        }
+
+        Self::new(size, cells).map_err(|err| err.message().into())
+    }
+
+    /// Creates a new grid out of rows of the given width where a `1` represents a filled cell and a `0` an empty cell.
+    /// `matrix`' `len` must be a multiple of the width.
+    pub fn from_bit_matrix(matrix: &[u8], width: usize) -> Result<Self, GridError> {
+        if width == 0 {
+            return Err(GridError::ZeroDimension);
+        }
+
+        let size = Size {
+            width: width as u16,
+            height: (matrix.len() / width) as u16,
+        };
+        let cells = matrix.iter().map(|bit| Cell::from(*bit == 1)).collect();
+
+        Self::new(size, cells)
+    }
+
+    /// Creates a new grid with only the given clue solutions and no picture, so all cells are empty.
+    ///
+    /// There has to be a clue solution for every row and column and the rows and columns have to add up to the same amount of filled cells.
+    pub fn from_clues(
+        size: Size,
+        horizontal_clues_solutions: Vec<Clues>,
+        vertical_clues_solutions: Vec<Clues>,
+    ) -> Result<Self, GridError> {
+        check_size(size, size.product() as usize)?;
+
+        for (clues_solutions, expected) in [
+            (&horizontal_clues_solutions, size.height),
+            (&vertical_clues_solutions, size.width),
+        ] {
+            if clues_solutions.len() != expected as usize {
+                return Err(GridError::ClueCountMismatch {
+                    expected: expected as usize,
+                    actual: clues_solutions.len(),
+                });
+            }
+        }
+
+        let get_sum = |clues_solutions: &[Clues]| -> usize {
+            clues_solutions
+                .iter()
+                .flatten()
+                .map(|clue| *clue as usize)
+                .sum()
+        };
+        let row_sum = get_sum(&horizontal_clues_solutions);
+        let column_sum = get_sum(&vertical_clues_solutions);
+        if row_sum != column_sum {
+            return Err(GridError::ClueSumMismatch {
+                row_sum,
+                column_sum,
+            });
+        }
+
+        Self::with_clues_solutions(
+            size,
+            vec![Cell::Empty; size.product() as usize],
+            horizontal_clues_solutions,
+            vertical_clues_solutions,
+        )
+    }
+
+    /// Creates a new grid with the given clue solutions instead of deriving them from the filled cells.
+    ///
+    /// Filled cells are removed because they are part of the solution, not of the player's input.
+    pub fn with_clues_solutions(
+        size: Size,
+        mut cells: Vec<Cell>,
+        horizontal_clues_solutions: Vec<Clues>,
+        vertical_clues_solutions: Vec<Clues>,
+    ) -> Result<Self, GridError> {
+        check_size(size, cells.len())?;
+        debug_assert_eq!(horizontal_clues_solutions.len(), size.height as usize);
+        debug_assert_eq!(vertical_clues_solutions.len(), size.width as usize);
+
+        let picture: Vec<Cell> = cells
+            .iter()
+            .map(|cell| Cell::from(*cell == Cell::Filled))
+            .collect();
+        let picture_fulfills_clues = (0..size.height).all(|y| {
+            get_line_progress(
+                get_horizontal_clues(&picture, size.width, y),
+                &horizontal_clues_solutions[y as usize],
+            )
+            .solved
+        }) && (0..size.width).all(|x| {
+            get_line_progress(
+                get_vertical_clues(&picture, size, x),
+                &vertical_clues_solutions[x as usize],
+            )
+            .solved
+        });
+        let solution = picture_fulfills_clues.then_some(picture);
 
         for cell in &mut cells {
             if *cell == Cell::Filled {
@@ -84,24 +456,26 @@ impl Grid {
             }
         }
 
-        let max_clues_size = Size {
-            width: max_clues_width,
-            height: max_clues_height,
-        };
+        let max_clues_size =
+            get_max_clues_size(&horizontal_clues_solutions, &vertical_clues_solutions);
 
         let undo_redo_buffer = UndoRedoBuffer::default();
 
         let measurement_counter = 0;
 
-        Self {
+        Ok(Self {
             size,
             cells,
             horizontal_clues_solutions,
             vertical_clues_solutions,
             max_clues_size,
+            solution,
             undo_redo_buffer,
             measurement_counter,
-        }
+            metadata: Metadata::new(),
+            row_caches: vec![LineCache::default(); size.height as usize],
+            column_caches: vec![LineCache::default(); size.width as usize],
+        })
     }
 
     fn cell_panic(point: Point, index: usize) -> ! {
@@ -111,7 +485,7 @@ impl Grid {
         );
     }
 
-    fn get_cell(&self, point: Point) -> Cell {
+    pub fn get_cell(&self, point: Point) -> Cell {
         let index = get_index(self.size.width, point);
         *self
             .cells
@@ -120,6 +494,9 @@ impl Grid {
     }
 
     pub fn get_mut_cell(&mut self, point: Point) -> &mut Cell {
+        self.invalidate_line_cache(&Line::Row, point.y);
+        self.invalidate_line_cache(&Line::Column, point.x);
+
         let index = get_index(self.size.width, point);
         self.cells
             .get_mut(index)
@@ -134,8 +511,343 @@ impl Grid {
         get_vertical_clues(&self.cells, self.size, x)
     }
 
+    /// Compares the row's clues to its clue solution.
+    pub fn get_row_progress(&self, y: u16) -> LineProgress {
+        get_line_progress(
+            self.get_horizontal_clues(y),
+            &self.horizontal_clues_solutions[y as usize],
+        )
+    }
+
+    /// Compares the column's clues to its clue solution.
+    pub fn get_column_progress(&self, x: u16) -> LineProgress {
+        get_line_progress(
+            self.get_vertical_clues(x),
+            &self.vertical_clues_solutions[x as usize],
+        )
+    }
+
+    /// Gets the cache of the row or column at the index together with its clue solution and the cells.
+    fn get_line_cache(&mut self, line: &Line, index: u16) -> (&mut LineCache, &[Clue], &[Cell]) {
+        match line {
+            Line::Row => (
+                &mut self.row_caches[index as usize],
+                &self.horizontal_clues_solutions[index as usize],
+                &self.cells,
+            ),
+            Line::Column => (
+                &mut self.column_caches[index as usize],
+                &self.vertical_clues_solutions[index as usize],
+                &self.cells,
+            ),
+        }
+    }
+
+    /// Checks whether the row or column at the index is solved or contradicts its clue solution.
+    ///
+    /// The status is only checked again if the line changed since the last time.
+    fn get_line_status(&mut self, line: &Line, index: u16) -> LineStatus {
+        let size = self.size;
+        let (cache, clues_solution, cells) = self.get_line_cache(line, index);
+        if let Some(status) = cache.status {
+            return status;
+        }
+
+        let line_len = match line {
+            Line::Row => size.width,
+            Line::Column => size.height,
+        };
+        let line_cells = (0..line_len).map(|position| {
+            let point = match line {
+                Line::Row => Point {
+                    x: position,
+                    y: index,
+                },
+                Line::Column => Point {
+                    x: index,
+                    y: position,
+                },
+            };
+            cells[get_index(size.width, point)]
+        });
+        let status = get_line_status(
+            line_cells,
+            cache.get_runs(cells, size, line, index),
+            clues_solution,
+        );
+
+        cache.status = Some(status);
+        status
+    }
+
+    /// Gets how many more cells have to be filled in the row or column at the index.
+    fn get_remaining_cells(&mut self, line: &Line, index: u16) -> u16 {
+        let size = self.size;
+        let (cache, clues_solution, cells) = self.get_line_cache(line, index);
+
+        get_remaining_cells(
+            cache.get_runs(cells, size, line, index).iter().copied(),
+            clues_solution,
+        )
+    }
+
+    /// Checks whether the row is solved or contradicts its clue solution.
+    pub fn get_row_status(&mut self, y: u16) -> LineStatus {
+        self.get_line_status(&Line::Row, y)
+    }
+
+    /// Checks whether the column is solved or contradicts its clue solution.
+    pub fn get_column_status(&mut self, x: u16) -> LineStatus {
+        self.get_line_status(&Line::Column, x)
+    }
+
+    /// Makes everything about the row or column at the index be determined again the next time.
+    fn invalidate_line_cache(&mut self, line: &Line, index: u16) {
+        let caches = match line {
+            Line::Row => &mut self.row_caches,
+            Line::Column => &mut self.column_caches,
+        };
+        if let Some(cache) = caches.get_mut(index as usize) {
+            *cache = LineCache::default();
+        }
+    }
+
+    /// Makes everything about all rows and columns be determined again the next time.
+    pub fn invalidate_line_caches(&mut self) {
+        self.row_caches.fill(LineCache::default());
+        self.column_caches.fill(LineCache::default());
+    }
+
+    /// Gets how many more cells have to be filled in the row.
+    pub fn remaining_in_row(&mut self, y: u16) -> u16 {
+        self.get_remaining_cells(&Line::Row, y)
+    }
+
+    /// Gets how many more cells have to be filled in the column.
+    pub fn remaining_in_column(&mut self, x: u16) -> u16 {
+        self.get_remaining_cells(&Line::Column, x)
+    }
+
+    /// Counts the rows and the columns whose filled cells contradict their clues, returning them in that order.
+    ///
+    /// This says nothing about which cells are wrong, only that some of the filled cells in those lines have to be emptied again.
+    pub fn get_contradicted_line_counts(&mut self) -> (u16, u16) {
+        let contradicted_rows = (0..self.size.height)
+            .filter(|y| self.get_row_status(*y) == LineStatus::Contradicted)
+            .count();
+        let contradicted_columns = (0..self.size.width)
+            .filter(|x| self.get_column_status(*x) == LineStatus::Contradicted)
+            .count();
+
+        (contradicted_rows as u16, contradicted_columns as u16)
+    }
+
+    /// Counts the rows and columns that are solved.
+    pub fn get_solved_line_count(&self) -> u16 {
+        let solved_rows = (0..self.size.height)
+            .filter(|y| self.get_row_progress(*y).solved)
+            .count();
+        let solved_columns = (0..self.size.width)
+            .filter(|x| self.get_column_progress(*x).solved)
+            .count();
+
+        (solved_rows + solved_columns) as u16
+    }
+
+    /// Reconstructs the clue solutions associated with the given `cell_point` and returns whether `max_clues_size` grew.
+    ///
+    /// `max_clues_size` never shrinks so that the layout stays stable within a session.
+    pub fn rebuild_clues(&mut self, cell_point: Point) -> bool {
+        // The picture changed so the old solution no longer applies
+        self.solution = None;
+
+        self.horizontal_clues_solutions[cell_point.y as usize] =
+            self.get_horizontal_clues(cell_point.y).collect();
+        self.vertical_clues_solutions[cell_point.x as usize] =
+            self.get_vertical_clues(cell_point.x).collect();
+        self.invalidate_line_cache(&Line::Row, cell_point.y);
+        self.invalidate_line_cache(&Line::Column, cell_point.x);
+
+        self.grow_max_clues_size()
+    }
+
+    /// Gets how many characters each of the left clues takes up.
+    pub fn get_left_clue_width(&self) -> u16 {
+        get_left_clue_width(&self.horizontal_clues_solutions)
+    }
+
+    /// Sets the clue solution of the row or column at the index directly instead of deriving it from the picture
+    /// and returns whether `max_clues_size` grew.
+    pub fn set_clues_solution(&mut self, line: &Line, index: u16, clues_solution: Clues) -> bool {
+        // The picture might not fulfill the new clues
+        self.solution = None;
+
+        match line {
+            Line::Row => self.horizontal_clues_solutions[index as usize] = clues_solution,
+            Line::Column => self.vertical_clues_solutions[index as usize] = clues_solution,
+        }
+        self.invalidate_line_cache(line, index);
+
+        self.grow_max_clues_size()
+    }
+
+    /// Grows `max_clues_size` to fit the current clue solutions and returns whether it grew.
+    fn grow_max_clues_size(&mut self) -> bool {
+        let max_clues_size = get_max_clues_size(
+            &self.horizontal_clues_solutions,
+            &self.vertical_clues_solutions,
+        );
+
+        if max_clues_size.width > self.max_clues_size.width
+            || max_clues_size.height > self.max_clues_size.height
+        {
+            self.max_clues_size = Size {
+                width: max_clues_size.width.max(self.max_clues_size.width),
+                height: max_clues_size.height.max(self.max_clues_size.height),
+            };
+            true
+        } else {
+            false
+        }
+    }
+
     pub fn clear(&mut self) {
         self.cells.fill_with(Default::default);
+        self.invalidate_line_caches();
+    }
+
+    /// Checks whether all cells are empty and there are no clues, as after [`Grid::clear_for_editing`].
+    pub fn is_blank(&self) -> bool {
+        self.cells.iter().all(|cell| *cell == Cell::Empty)
+            && self
+                .horizontal_clues_solutions
+                .iter()
+                .chain(&self.vertical_clues_solutions)
+                .all(Vec::is_empty)
+    }
+
+    /// Empties the cells and removes all clues so that a new grid can be designed from scratch.
+    ///
+    /// The undo-redo buffer is emptied as well because its operations were done with the previous clues.
+    pub fn clear_for_editing(&mut self) {
+        self.clear();
+        for clues_solution in self
+            .horizontal_clues_solutions
+            .iter_mut()
+            .chain(&mut self.vertical_clues_solutions)
+        {
+            clues_solution.clear();
+        }
+        self.max_clues_size = get_max_clues_size(
+            &self.horizontal_clues_solutions,
+            &self.vertical_clues_solutions,
+        );
+        // The empty picture fulfills the empty clues
+        self.solution = Some(self.cells.clone());
+        self.undo_redo_buffer = UndoRedoBuffer::default();
+    }
+
+    /// Gets the mistake that the cell at the point is, if the solution is known.
+    pub fn get_mistake(&self, point: Point) -> Option<Mistake> {
+        let solution = self.solution.as_ref()?;
+
+        Mistake::classify(
+            self.get_cell(point),
+            solution[get_index(self.size.width, point)],
+        )
+    }
+
+    /// Gets the points of all cells that contradict the solution along with what kind of mistake they are.
+    ///
+    /// If the solution is not known, there are none.
+    pub fn get_mistakes(&self) -> Vec<(Point, Mistake)> {
+        let width = self.size.width as usize;
+        let solution = if let Some(solution) = &self.solution {
+            solution
+        } else {
+            return Vec::new();
+        };
+
+        self.cells
+            .iter()
+            .zip(solution)
+            .enumerate()
+            .filter_map(|(index, (cell, solution_cell))| {
+                let mistake = Mistake::classify(*cell, *solution_cell)?;
+                let point = Point {
+                    x: (index % width) as u16,
+                    y: (index / width) as u16,
+                };
+                Some((point, mistake))
+            })
+            .collect()
+    }
+
+    /// Checks whether exactly the cells that are filled in `expected` are filled, for example to check the result of a test.
+    ///
+    /// How the other cells are marked doesn't matter, just like for solving the grid.
+    pub fn verify_against(&self, expected: &[Cell]) -> bool {
+        self.cells.len() == expected.len()
+            && self
+                .cells
+                .iter()
+                .zip(expected)
+                .all(|(cell, expected_cell)| {
+                    (*cell == Cell::Filled) == (*expected_cell == Cell::Filled)
+                })
+    }
+
+    /// Empties the cells that contradict the solution and returns their points.
+    ///
+    /// Those are the cells of [`Grid::get_mistakes`].
+    /// If the solution is not known, only maybed and measured cells are emptied because nothing is known about the others.
+    pub fn clear_mistakes(&mut self) -> Vec<Point> {
+        let width = self.size.width as usize;
+        let mut points = Vec::<Point>::new();
+
+        self.invalidate_line_caches();
+
+        for (index, cell) in self.cells.iter_mut().enumerate() {
+            let mistake = match &self.solution {
+                Some(solution) => Mistake::classify(*cell, solution[index]).is_some(),
+                None => matches!(cell, Cell::Maybed | Cell::Measured(_, _)),
+            };
+
+            if mistake {
+                *cell = Cell::Empty;
+                points.push(Point {
+                    x: (index % width) as u16,
+                    y: (index / width) as u16,
+                });
+            }
+        }
+
+        points
+    }
+
+    /// Replaces the cells with the solution and returns whether there is one.
+    ///
+    /// If the solution is not known, one is found using only the clues.
+    pub fn reveal(&mut self) -> bool {
+        if self.solution.is_none() {
+            let mut grid = self.clone();
+            if !grid.solve() {
+                return false;
+            }
+            self.solution = Some(grid.cells);
+        }
+
+        self.set_solution_cells();
+
+        true
+    }
+
+    /// Replaces the cells with the solution if it is known.
+    pub fn set_solution_cells(&mut self) {
+        if let Some(solution) = &self.solution {
+            self.cells.clone_from(solution);
+            self.invalidate_line_caches();
+        }
     }
 }
 
@@ -143,39 +855,144 @@ impl Grid {
 mod tests {
     use super::*;
 
-    impl Grid {
-        /// Creates a new grid from the given line of strings.
-        /// A `1` represents a filled cell, a ` ` represents an empty cell.
-        ///
-        /// # Examples
-        ///
-        /// ```
-        /// let lines = [
-        ///    "111 1",
-        ///    "11111",
-        ///    "1 111"
-        /// ]
-        /// let grid = Grid::from_lines(lines);
-        /// ```
-        fn from_lines(lines: &[&str]) -> Grid {
-            let width = lines.iter().map(|line| line.len()).max().unwrap();
-            let height = lines.len();
-            let size = Size {
-                width: width as u16,
-                height: height as u16,
-            };
-            let mut cells = Vec::<Cell>::with_capacity(size.product() as usize);
-            for line in lines {
-                for char in line.chars() {
-                    cells.push(match char {
-                        '1' => Cell::Filled,
-                        ' ' => Cell::Empty,
-                        _ => panic!("the strings must only contain '1' or ' '"),
-                    });
-                }
+    #[test]
+    fn test_from_lines() {
+        // Shorter lines are padded
+        let grid = Grid::from_lines(&["11", "1", ""]).unwrap();
+        assert_eq!(
+            grid.size,
+            Size {
+                width: 2,
+                height: 3
             }
-            Grid::new(size, cells)
-        }
+        );
+        assert_eq!(
+            grid.solution,
+            Some(vec![
+                Cell::Filled,
+                Cell::Filled,
+                Cell::Filled,
+                Cell::Empty,
+                Cell::Empty,
+                Cell::Empty,
+            ])
+        );
+
+        assert_eq!(
+            Grid::from_lines(&["11", "1x"]).err().unwrap(),
+            "Line 2 must only contain '1' or ' ', not 'x'"
+        );
+        assert!(Grid::from_lines(&[]).is_err());
+        assert!(Grid::from_lines(&["", ""]).is_err());
+    }
+
+    #[test]
+    fn test_new_errors() {
+        let size = Size {
+            width: 2,
+            height: 2,
+        };
+        assert_eq!(
+            Grid::new(size, vec![Cell::Empty; 3]).err(),
+            Some(GridError::SizeMismatch {
+                expected: 4,
+                actual: 3
+            })
+        );
+
+        let size = Size {
+            width: 0,
+            height: 2,
+        };
+        assert_eq!(
+            Grid::new(size, Vec::new()).err(),
+            Some(GridError::ZeroDimension)
+        );
+        assert_eq!(
+            Grid::from_bit_matrix(&[], 0).err(),
+            Some(GridError::ZeroDimension)
+        );
+        assert_eq!(
+            Grid::from_bit_matrix(&[1, 0, 1], 2).err(),
+            Some(GridError::SizeMismatch {
+                expected: 2,
+                actual: 3
+            })
+        );
+    }
+
+    #[test]
+    fn test_from_clues() {
+        let size = Size {
+            width: 3,
+            height: 2,
+        };
+        let grid = Grid::from_clues(
+            size,
+            vec![vec![1, 1], vec![3]],
+            vec![vec![2], vec![1], vec![2]],
+        )
+        .unwrap();
+        assert!(grid.cells.iter().all(|cell| *cell == Cell::Empty));
+        assert_eq!(grid.horizontal_clues_solutions, [vec![1, 1], vec![3]]);
+        assert_eq!(
+            grid.max_clues_size,
+            Size {
+                width: 4,
+                height: 1
+            }
+        );
+        assert!(grid.solution.is_none());
+
+        assert_eq!(
+            Grid::from_clues(size, vec![vec![3]], vec![vec![1]; 3]).err(),
+            Some(GridError::ClueCountMismatch {
+                expected: 2,
+                actual: 1
+            })
+        );
+        assert_eq!(
+            Grid::from_clues(size, vec![vec![3], vec![3]], vec![vec![2]; 2]).err(),
+            Some(GridError::ClueCountMismatch {
+                expected: 3,
+                actual: 2
+            })
+        );
+        assert_eq!(
+            Grid::from_clues(size, vec![vec![3], vec![1]], vec![vec![1]; 3]).err(),
+            Some(GridError::ClueSumMismatch {
+                row_sum: 4,
+                column_sum: 3
+            })
+        );
+        assert_eq!(
+            Grid::from_clues(
+                Size {
+                    width: 0,
+                    height: 2
+                },
+                vec![vec![]; 2],
+                Vec::new()
+            )
+            .err(),
+            Some(GridError::ZeroDimension)
+        );
+    }
+
+    #[test]
+    fn test_from_bit_matrix() {
+        let grid = Grid::from_bit_matrix(&[1, 0, 1, 1, 1, 0], 3).unwrap();
+        #[rustfmt::skip]
+        let expected_grid = Grid::from_lines(&[
+            "1 1",
+            "11 ",
+        ]).unwrap();
+        assert_eq!(grid.size, expected_grid.size);
+        assert_eq!(grid.solution, expected_grid.solution);
+        assert_eq!(
+            grid.horizontal_clues_solutions,
+            expected_grid.horizontal_clues_solutions
+        );
     }
 
     #[test]
@@ -187,7 +1004,7 @@ mod tests {
             "1111 11  1",
             "1 11 1  11",
             "1  111  11",
-        ]);
+        ]).unwrap();
 
         assert_eq!(
             grid.horizontal_clues_solutions,
@@ -227,7 +1044,7 @@ mod tests {
             "1 1 ",
             "1  1",
             "  1 ",
-        ]);
+        ]).unwrap();
 
         assert_eq!(
             grid.horizontal_clues_solutions,
@@ -254,10 +1071,481 @@ mod tests {
             "1111",
             "1111",
             "1111",
-        ]);
+        ]).unwrap();
 
         grid.clear();
 
         assert!(grid.cells.iter().all(|cell| *cell == Cell::Empty));
     }
+
+    #[test]
+    fn test_set_clues_solution() {
+        #[rustfmt::skip]
+        let mut grid = Grid::from_lines(&[
+            "11 ",
+            "  1",
+        ]).unwrap();
+        assert!(grid.solution.is_some());
+
+        assert!(!grid.set_clues_solution(&Line::Column, 2, vec![2]));
+        assert_eq!(grid.vertical_clues_solutions, [vec![1], vec![1], vec![2]]);
+        assert!(grid.solution.is_none());
+
+        assert!(grid.set_clues_solution(&Line::Row, 0, vec![1, 1]));
+        assert_eq!(grid.horizontal_clues_solutions, [vec![1, 1], vec![1]]);
+        assert_eq!(grid.max_clues_size.width, 4);
+    }
+
+    #[test]
+    fn test_get_left_clue_width() {
+        assert_eq!(get_left_clue_width(&[vec![], vec![1, 2]]), 2);
+        assert_eq!(get_left_clue_width(&[vec![99]]), 2);
+        assert_eq!(get_left_clue_width(&[vec![5], vec![1, 100]]), 3);
+
+        let size = Size {
+            width: 150,
+            height: 2,
+        };
+        let mut cells = vec![Cell::Filled; size.product() as usize];
+        cells[size.width as usize + 1] = Cell::Empty;
+        let grid = Grid::new(size, cells).unwrap();
+        assert_eq!(grid.horizontal_clues_solutions, [vec![150], vec![1, 148]]);
+        assert_eq!(grid.get_left_clue_width(), 3);
+        assert_eq!(grid.max_clues_size.width, 2 * 3);
+    }
+
+    #[test]
+    fn test_get_horizontal_clues() {
+        #[rustfmt::skip]
+        let grid = Grid::from_lines(&[
+            "11111",
+            "     ",
+            "1 1 1",
+            " 1 1 ",
+            "11 11",
+            "1    ",
+            "    1",
+        ]).unwrap();
+        let clues: Vec<Clues> = (0..grid.size.height)
+            .map(|y| {
+                get_horizontal_clues(&grid.solution.clone().unwrap(), grid.size.width, y).collect()
+            })
+            .collect();
+        assert_eq!(
+            clues,
+            [
+                vec![5],
+                vec![],
+                vec![1, 1, 1],
+                vec![1, 1],
+                vec![2, 2],
+                vec![1],
+                vec![1],
+            ]
+        );
+        assert_eq!(clues, grid.horizontal_clues_solutions);
+    }
+
+    #[test]
+    fn test_get_vertical_clues() {
+        #[rustfmt::skip]
+        let grid = Grid::from_lines(&[
+            "1 1 11",
+            "1  1 1",
+            "1 1 1 ",
+            "1  1 1",
+        ]).unwrap();
+        let clues: Vec<Clues> = (0..grid.size.width)
+            .map(|x| get_vertical_clues(&grid.solution.clone().unwrap(), grid.size, x).collect())
+            .collect();
+        assert_eq!(
+            clues,
+            [
+                vec![4],
+                vec![],
+                vec![1, 1],
+                vec![1, 1],
+                vec![1, 1],
+                vec![2, 1],
+            ]
+        );
+        assert_eq!(clues, grid.vertical_clues_solutions);
+    }
+
+    #[test]
+    fn test_get_line_status() {
+        let status = |str: &str, clues_solution: &[Clue]| {
+            let line: Vec<Cell> = str
+                .chars()
+                .map(|char| match char {
+                    '1' => Cell::Filled,
+                    'x' => Cell::Crossed,
+                    _ => Cell::Empty,
+                })
+                .collect();
+            let runs: Clues = get_horizontal_clues(&line, line.len() as u16, 0).collect();
+            get_line_status(line.into_iter(), &runs, clues_solution)
+        };
+
+        assert_eq!(status("11 1 ", &[2, 1]), LineStatus::Solved);
+        assert_eq!(status("     ", &[]), LineStatus::Solved);
+        assert_eq!(status("xx x ", &[]), LineStatus::Solved);
+
+        // Unfinished runs that can still grow into the clues
+        assert_eq!(status("     ", &[2, 1]), LineStatus::Incomplete);
+        assert_eq!(status(" 1   ", &[2, 1]), LineStatus::Incomplete);
+        assert_eq!(status("1   1", &[2, 1]), LineStatus::Incomplete);
+        // Two runs that can still be joined into one
+        assert_eq!(status("1 1  ", &[3]), LineStatus::Incomplete);
+        // Crossed out cells are ignored
+        assert_eq!(status("xx1xx", &[3]), LineStatus::Incomplete);
+
+        // A run longer than any clue
+        assert_eq!(status("111  ", &[2, 1]), LineStatus::Contradicted);
+        // Too many runs that can't be joined
+        assert_eq!(status("1 1 1", &[2, 1]), LineStatus::Contradicted);
+        // Too many filled cells
+        assert_eq!(status("11 11", &[2, 1]), LineStatus::Contradicted);
+        // A run in a place that no clue can reach
+        assert_eq!(status(" 1   ", &[1, 3]), LineStatus::Contradicted);
+        assert_eq!(status("  1  ", &[]), LineStatus::Contradicted);
+    }
+
+    #[test]
+    fn test_get_row_and_column_status() {
+        #[rustfmt::skip]
+        let mut grid = Grid::from_lines(&[
+            "11 ",
+            "  1",
+        ]).unwrap();
+        *grid.get_mut_cell(Point { x: 0, y: 0 }) = Cell::Filled;
+        assert_eq!(grid.get_row_status(0), LineStatus::Incomplete);
+        assert_eq!(grid.get_column_status(0), LineStatus::Solved);
+
+        *grid.get_mut_cell(Point { x: 0, y: 1 }) = Cell::Filled;
+        *grid.get_mut_cell(Point { x: 2, y: 1 }) = Cell::Filled;
+        assert_eq!(grid.get_row_status(1), LineStatus::Contradicted);
+        assert_eq!(grid.get_column_status(0), LineStatus::Contradicted);
+        // Maybed cells count as empty
+        *grid.get_mut_cell(Point { x: 1, y: 1 }) = Cell::Maybed;
+        assert_eq!(grid.get_contradicted_line_counts(), (1, 1));
+
+        // The cached statuses are checked again after changes that don't go through `get_mut_cell`
+        grid.clear();
+        assert_eq!(grid.get_row_status(1), LineStatus::Incomplete);
+        assert_eq!(grid.get_column_status(0), LineStatus::Incomplete);
+    }
+
+    #[test]
+    fn test_remaining_cells() {
+        #[rustfmt::skip]
+        let mut grid = Grid::from_lines(&[
+            "11 1",
+            "  11",
+        ]).unwrap();
+        assert_eq!(grid.remaining_in_row(0), 3);
+        assert_eq!(grid.remaining_in_column(3), 2);
+
+        *grid.get_mut_cell(Point { x: 0, y: 0 }) = Cell::Filled;
+        *grid.get_mut_cell(Point { x: 2, y: 0 }) = Cell::Filled;
+        assert_eq!(grid.remaining_in_row(0), 1);
+        assert_eq!(grid.remaining_in_column(2), 0);
+
+        // Too many filled cells don't make the count go below zero
+        *grid.get_mut_cell(Point { x: 0, y: 1 }) = Cell::Filled;
+        assert_eq!(grid.remaining_in_column(0), 0);
+        assert_eq!(grid.remaining_in_row(1), 1);
+    }
+
+    #[test]
+    fn test_clear_for_editing() {
+        #[rustfmt::skip]
+        let mut grid = Grid::from_lines(&[
+            "1 11",
+            " 111",
+        ]).unwrap();
+        *grid.get_mut_cell(Point { x: 0, y: 0 }) = Cell::Filled;
+        *grid.get_mut_cell(Point { x: 1, y: 0 }) = Cell::Crossed;
+        assert!(!grid.is_blank());
+
+        grid.clear_for_editing();
+
+        assert!(grid.is_blank());
+        let empty_grid =
+            Grid::new(grid.size, vec![Cell::Empty; grid.size.product() as usize]).unwrap();
+        assert_eq!(
+            grid.horizontal_clues_solutions,
+            empty_grid.horizontal_clues_solutions
+        );
+        assert_eq!(
+            grid.vertical_clues_solutions,
+            empty_grid.vertical_clues_solutions
+        );
+        assert_eq!(grid.max_clues_size, empty_grid.max_clues_size);
+        assert_eq!(grid.get_row_status(0), LineStatus::Solved);
+        assert!(grid.undo_redo_buffer.buffer.is_empty());
+    }
+
+    #[test]
+    fn test_clear_mistakes() {
+        #[rustfmt::skip]
+        let mut grid = Grid::from_lines(&[
+            "1 ",
+            " 1",
+        ]).unwrap();
+        *grid.get_mut_cell(Point { x: 0, y: 0 }) = Cell::Filled;
+        *grid.get_mut_cell(Point { x: 1, y: 0 }) = Cell::Filled;
+        *grid.get_mut_cell(Point { x: 0, y: 1 }) = Cell::Crossed;
+        *grid.get_mut_cell(Point { x: 1, y: 1 }) = Cell::Crossed;
+
+        assert_eq!(
+            grid.get_mistakes(),
+            [
+                (Point { x: 1, y: 0 }, Mistake::WrongFill),
+                (Point { x: 1, y: 1 }, Mistake::WrongCross)
+            ]
+        );
+        assert_eq!(grid.get_mistake(Point { x: 0, y: 0 }), None);
+        assert_eq!(
+            grid.get_mistake(Point { x: 1, y: 1 }),
+            Some(Mistake::WrongCross)
+        );
+
+        assert_eq!(
+            grid.clear_mistakes(),
+            [Point { x: 1, y: 0 }, Point { x: 1, y: 1 }]
+        );
+        assert_eq!(
+            grid.cells,
+            [Cell::Filled, Cell::Empty, Cell::Crossed, Cell::Empty]
+        );
+        assert!(grid.clear_mistakes().is_empty());
+
+        // Without the solution, only the player's guesses are cleared
+        grid.solution = None;
+        assert!(grid.get_mistakes().is_empty());
+        *grid.get_mut_cell(Point { x: 1, y: 0 }) = Cell::Maybed;
+        *grid.get_mut_cell(Point { x: 1, y: 1 }) = Cell::Measured(Some(1), None);
+        assert_eq!(grid.clear_mistakes().len(), 2);
+        assert_eq!(
+            grid.cells,
+            [Cell::Filled, Cell::Empty, Cell::Crossed, Cell::Empty]
+        );
+    }
+
+    #[test]
+    fn test_empty_grid_clues() {
+        let size = Size {
+            width: 3,
+            height: 2,
+        };
+        let grid = Grid::new(size, vec![Cell::Empty; size.product() as usize]).unwrap();
+
+        assert_eq!(grid.horizontal_clues_solutions, [vec![], vec![]]);
+        assert_eq!(grid.vertical_clues_solutions, [vec![], vec![], vec![]]);
+        // There is still space for drawing the empty lines as 0
+        assert_eq!(
+            grid.max_clues_size,
+            Size {
+                width: 2,
+                height: 1
+            }
+        );
+    }
+
+    #[test]
+    fn test_clues_fit() {
+        assert!(clues_fit(&[], 0));
+        assert!(clues_fit(&[2, 1], 4));
+        assert!(!clues_fit(&[2, 2], 4));
+        assert!(clues_fit(&[5], 5));
+        assert!(!clues_fit(&[6], 5));
+    }
+
+    #[test]
+    fn test_rebuild_clues() {
+        #[rustfmt::skip]
+        let mut grid = Grid::from_lines(&[
+            "1          ",
+            "1          ",
+        ]).unwrap();
+
+        assert_eq!(
+            grid.max_clues_size,
+            Size {
+                width: 2,
+                height: 1
+            }
+        );
+
+        // Grow the first row from 1 to 6 clues
+        for x in (0..11).step_by(2) {
+            let point = Point { x, y: 0 };
+            *grid.get_mut_cell(point) = Cell::Filled;
+            assert_eq!(grid.rebuild_clues(point), x != 0);
+        }
+
+        assert_eq!(grid.horizontal_clues_solutions[0], vec![1, 1, 1, 1, 1, 1]);
+        assert_eq!(
+            grid.max_clues_size,
+            Size {
+                width: 12,
+                height: 1
+            }
+        );
+
+        // Vertically adjacent filled cells do not grow the height
+        let point = Point { x: 0, y: 1 };
+        *grid.get_mut_cell(point) = Cell::Filled;
+        assert!(!grid.rebuild_clues(point));
+        assert_eq!(grid.vertical_clues_solutions[0], vec![2]);
+    }
+
+    #[test]
+    fn test_rebuild_clues_keeps_larger_reservation() {
+        #[rustfmt::skip]
+        let mut grid = Grid::from_lines(&[
+            "1 1 1",
+        ]).unwrap();
+
+        assert_eq!(
+            grid.max_clues_size,
+            Size {
+                width: 6,
+                height: 1
+            }
+        );
+
+        let point = Point { x: 2, y: 0 };
+        *grid.get_mut_cell(point) = Cell::Filled;
+        assert!(!grid.rebuild_clues(point));
+
+        assert_eq!(grid.horizontal_clues_solutions[0], vec![1]);
+        assert_eq!(
+            grid.max_clues_size,
+            Size {
+                width: 6,
+                height: 1
+            }
+        );
+    }
+
+    #[test]
+    fn test_line_progress() {
+        #[rustfmt::skip]
+        let mut grid = Grid::from_lines(&[
+            "11 1 1",
+            "1     ",
+        ]).unwrap();
+
+        assert_eq!(
+            grid.get_row_progress(0),
+            LineProgress {
+                matching_clues: 0,
+                clues: 3,
+                solved: false
+            }
+        );
+        // The empty columns
+        assert_eq!(grid.get_solved_line_count(), 2);
+
+        *grid.get_mut_cell(Point { x: 0, y: 0 }) = Cell::Filled;
+        *grid.get_mut_cell(Point { x: 1, y: 0 }) = Cell::Filled;
+        *grid.get_mut_cell(Point { x: 5, y: 0 }) = Cell::Filled;
+        assert_eq!(
+            grid.get_row_progress(0),
+            LineProgress {
+                matching_clues: 2,
+                clues: 3,
+                solved: false
+            }
+        );
+
+        *grid.get_mut_cell(Point { x: 3, y: 0 }) = Cell::Filled;
+        assert!(grid.get_row_progress(0).solved);
+
+        assert!(!grid.get_column_progress(0).solved);
+        *grid.get_mut_cell(Point { x: 0, y: 1 }) = Cell::Filled;
+        assert!(grid.get_column_progress(0).solved);
+        assert!(grid.get_column_progress(2).solved);
+        assert_eq!(grid.get_solved_line_count(), 8);
+    }
+
+    #[test]
+    fn test_reveal() {
+        // This grid has two solutions and the one of the picture is revealed
+        #[rustfmt::skip]
+        let lines = [
+            "11 ",
+            "111",
+            " 11",
+        ];
+        let mut grid = Grid::from_lines(&lines).unwrap();
+        *grid.get_mut_cell(Point { x: 0, y: 2 }) = Cell::Crossed;
+
+        assert!(grid.reveal());
+        assert_eq!(
+            grid.cells,
+            Grid::from_lines(&lines).unwrap().solution.unwrap()
+        );
+
+        // Without a picture the solution is found using the clues
+        let size = Size {
+            width: 3,
+            height: 3,
+        };
+        let mut grid = Grid::from_clues(
+            size,
+            vec![vec![2], vec![3], vec![2]],
+            vec![vec![2], vec![3], vec![2]],
+        )
+        .unwrap();
+        assert!(grid.solution.is_none());
+
+        assert!(grid.reveal());
+        for y in 0..size.height {
+            assert!(grid.get_row_progress(y).solved);
+        }
+        for x in 0..size.width {
+            assert!(grid.get_column_progress(x).solved);
+        }
+    }
+
+    #[test]
+    fn test_reveal_unsolvable() {
+        let size = Size {
+            width: 2,
+            height: 1,
+        };
+        let mut grid = Grid::with_clues_solutions(
+            size,
+            vec![Cell::Empty; size.product() as usize],
+            vec![vec![2]],
+            vec![vec![], vec![]],
+        )
+        .unwrap();
+
+        assert!(!grid.reveal());
+        assert!(grid.cells.iter().all(|cell| *cell == Cell::Empty));
+    }
+
+    #[test]
+    fn test_verify_against() {
+        #[rustfmt::skip]
+        let mut grid = Grid::from_lines(&[
+            "1 ",
+            " 1",
+        ]).unwrap();
+        let solution = grid.solution.clone().unwrap();
+        assert!(!grid.verify_against(&solution));
+
+        *grid.get_mut_cell(Point { x: 0, y: 0 }) = Cell::Filled;
+        *grid.get_mut_cell(Point { x: 1, y: 1 }) = Cell::Filled;
+        *grid.get_mut_cell(Point { x: 1, y: 0 }) = Cell::Crossed;
+        *grid.get_mut_cell(Point { x: 0, y: 1 }) = Cell::Maybed;
+        assert!(grid.verify_against(&solution));
+
+        // A grid of another size never matches
+        assert!(!grid.verify_against(&solution[..3]));
+    }
 }
@@ -0,0 +1,179 @@
+use super::{Cell, Clue, Clues, Grid};
+
+/// How [`Grid::to_text`] renders a grid.
+pub(super) struct TextStyle {
+    /// Gets the character a cell is rendered as, which is repeated over the width of its column.
+    pub get_cell_char: fn(Cell) -> char,
+    /// How many spaces separate the columns from each other and from the left clues.
+    ///
+    /// Without any, the columns are a character wider so that the top clues don't run into each other.
+    pub column_spacing: usize,
+    /// Whether a line without clues is shown as `0`.
+    pub zero_clue: bool,
+    /// Whether how many percent of the rows and columns are solved is shown below the grid.
+    pub percentage: bool,
+}
+
+/// Gets the character a cell is rendered as on the board.
+const fn get_cell_char(cell: Cell) -> char {
+    match cell {
+        // Measurements are drawn over empty cells
+        Cell::Empty | Cell::Measured(_, _) => '.',
+        Cell::Filled => '#',
+        Cell::Crossed => 'x',
+        Cell::Maybed => '?',
+    }
+}
+
+/// Gets the clues of a line as text, where a line without clues is shown as `0` if `zero_clue` is `true`.
+fn get_clues_texts(clues_solution: &[Clue], zero_clue: bool) -> Vec<String> {
+    if clues_solution.is_empty() && zero_clue {
+        vec![0.to_string()]
+    } else {
+        clues_solution.iter().map(Clue::to_string).collect()
+    }
+}
+
+/// Gets how many characters the longest clue of the lines takes.
+fn get_clue_width(clues_solutions: &[Clues]) -> usize {
+    clues_solutions
+        .iter()
+        .flatten()
+        .max()
+        .map_or(1, |clue| clue.to_string().len())
+}
+
+impl Grid {
+    /// Renders the grid with its clues as text in the style.
+    ///
+    /// Each column is as wide as the widest top clue so that the clues above the grid line up with the cells below them.
+    pub(super) fn to_text(&self, style: &TextStyle) -> String {
+        let column_width = get_clue_width(&self.vertical_clues_solutions)
+            + if style.column_spacing == 0 { 1 } else { 0 };
+        let column_spacing = " ".repeat(style.column_spacing);
+
+        let left_clues_texts: Vec<String> = self
+            .horizontal_clues_solutions
+            .iter()
+            .map(|clues_solution| get_clues_texts(clues_solution, style.zero_clue).join(" "))
+            .collect();
+        let left_clues_width = left_clues_texts
+            .iter()
+            .map(String::len)
+            .max()
+            .unwrap_or_default();
+
+        let top_clues_texts: Vec<Vec<String>> = self
+            .vertical_clues_solutions
+            .iter()
+            .map(|clues_solution| get_clues_texts(clues_solution, style.zero_clue))
+            .collect();
+        let top_clues_height = top_clues_texts
+            .iter()
+            .map(Vec::len)
+            .max()
+            .unwrap_or_default();
+
+        let mut text = String::new();
+
+        // The top clues, aligned to the bottom
+        for y in 0..top_clues_height {
+            text.push_str(&" ".repeat(left_clues_width));
+            for clues_texts in &top_clues_texts {
+                let padding = top_clues_height - clues_texts.len();
+                let clue_text = y
+                    .checked_sub(padding)
+                    .map_or("", |index| clues_texts[index].as_str());
+                text.push_str(&column_spacing);
+                text.push_str(&format!("{:>width$}", clue_text, width = column_width));
+            }
+            text.push('\n');
+        }
+
+        for (row, left_clues_text) in self
+            .cells
+            .chunks(self.size.width as usize)
+            .zip(&left_clues_texts)
+        {
+            text.push_str(&format!(
+                "{:>width$}",
+                left_clues_text,
+                width = left_clues_width
+            ));
+            for cell in row {
+                text.push_str(&column_spacing);
+                for _ in 0..column_width {
+                    text.push((style.get_cell_char)(*cell));
+                }
+            }
+            text.push('\n');
+        }
+
+        if style.percentage {
+            let line_count = self.size.width + self.size.height;
+            let percentage = self.get_solved_line_count() as u32 * 100 / line_count as u32;
+            text.push_str(&format!("\n{}% completed\n", percentage));
+        }
+
+        text
+    }
+
+    /// Renders the current state of the grid with its clues and progress as plain text, which is useful for sharing it.
+    pub fn to_board_text(&self) -> String {
+        self.to_text(&TextStyle {
+            get_cell_char,
+            column_spacing: 1,
+            zero_clue: true,
+            percentage: true,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use terminal::util::Point;
+
+    #[test]
+    fn test_to_board_text() {
+        #[rustfmt::skip]
+        let mut grid = Grid::from_lines(&[
+            "1 1",
+            "111",
+            "   ",
+        ]).unwrap();
+        *grid.get_mut_cell(Point { x: 0, y: 0 }) = Cell::Filled;
+        *grid.get_mut_cell(Point { x: 1, y: 0 }) = Cell::Crossed;
+        *grid.get_mut_cell(Point { x: 2, y: 0 }) = Cell::Filled;
+        *grid.get_mut_cell(Point { x: 0, y: 2 }) = Cell::Maybed;
+        *grid.get_mut_cell(Point { x: 1, y: 2 }) = Cell::Measured(Some(1), None);
+
+        assert_eq!(
+            grid.to_board_text(),
+            concat!(
+                "    2 1 2\n",
+                "1 1 # x #\n",
+                "  3 . . .\n",
+                "  0 ? . .\n",
+                "\n",
+                // The first and last row
+                "33% completed\n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_to_board_text_with_multi_digit_clues() {
+        let mut lines = vec!["1 "; 10];
+        lines.extend([" 1", " 1"]);
+        let grid = Grid::from_lines(&lines).unwrap();
+
+        let board = grid.to_board_text();
+        let mut lines = board.lines();
+        // The second column is as wide as the first one even though its clue has a single digit
+        assert_eq!(lines.next(), Some("  10  2"));
+        assert_eq!(lines.next(), Some("1 .. .."));
+        assert_eq!(lines.nth(10), Some("1 .. .."));
+        assert_eq!(lines.next(), Some(""));
+    }
+}
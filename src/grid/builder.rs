@@ -1,43 +1,362 @@
-use super::{Cell, Grid};
+use super::{
+    get_difficulty_label,
+    palette::{Palette, CONTRADICTED_CLUE_COLOR, HIGH_CONTRAST_SOLVED_CLUE_COLORS},
+    Cell, Clue, Grid, LineStatus,
+};
+use crate::{bell::Bell, config::Settings, mode::Mode, util};
 use itertools::Itertools;
+use std::{borrow::Cow, cmp};
 use terminal::{
-    util::{Color, Point},
+    util::{Color, Point, Size},
     Terminal,
 };
 
+/// The character with no dots raised. Raising a dot is done by setting one of the lower 8 bits.
+const BRAILLE_BASE: u32 = 0x2800;
+
+/// Maps the position of a cell within a 2x4 group to the bit of its braille dot: `BRAILLE_DOT_BITS[y][x]`.
+const BRAILLE_DOT_BITS: [[u8; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+
+/// Renders the filled cells of the grid as rows of braille characters, one character for each 2x4 group of cells.
+///
+/// Groups at the right or bottom edge that are only partially inside the grid are padded with empty cells.
+fn get_braille_rows(grid: &Grid) -> Vec<String> {
+    (0..grid.size.height.div_ceil(4))
+        .map(|row| {
+            (0..grid.size.width.div_ceil(2))
+                .map(|column| {
+                    let mut dots = 0;
+                    for (y_offset, bits) in BRAILLE_DOT_BITS.iter().enumerate() {
+                        for (x_offset, bit) in bits.iter().enumerate() {
+                            let point = Point {
+                                x: column * 2 + x_offset as u16,
+                                y: row * 4 + y_offset as u16,
+                            };
+                            if point.x < grid.size.width
+                                && point.y < grid.size.height
+                                && grid.get_cell(point) == Cell::Filled
+                            {
+                                dots |= bit;
+                            }
+                        }
+                    }
+                    char::from_u32(BRAILLE_BASE + dots as u32).unwrap() // The braille block has all 256 patterns
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// The characters that everything other than text is drawn with.
+///
+/// For good Windows terminal compatibility, everything in [`GlyphSet::Ascii`] is also on [code page 437](https://en.wikipedia.org/wiki/Code_page_437).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum GlyphSet {
+    #[default]
+    Unicode,
+    /// Only ASCII characters, for terminals or fonts that render other characters poorly.
+    Ascii,
+}
+
+impl GlyphSet {
+    pub const fn new(ascii: bool) -> Self {
+        if ascii {
+            Self::Ascii
+        } else {
+            Self::Unicode
+        }
+    }
+
+    /// Gets the character for two vertically adjacent cells of the picture when it can't be drawn with colors.
+    /// Only filled cells are shown.
+    pub const fn get_monochrome_half_block(
+        self,
+        upper_cell: Cell,
+        lower_cell: Cell,
+    ) -> &'static str {
+        let filled = (
+            matches!(upper_cell, Cell::Filled),
+            matches!(lower_cell, Cell::Filled),
+        );
+        match self {
+            Self::Unicode => match filled {
+                (true, true) => "█",
+                (true, false) => "▀",
+                (false, true) => "▄",
+                (false, false) => " ",
+            },
+            Self::Ascii => match filled {
+                (true, true) => "#",
+                (true, false) => "\"",
+                (false, true) => ",",
+                (false, false) => " ",
+            },
+        }
+    }
+
+    /// Gets the character that the progress bar is made of, for the solved part and for the rest.
+    pub const fn get_progress_bar(self, solved: bool) -> &'static str {
+        match self {
+            Self::Unicode => "▄",
+            Self::Ascii if solved => "=",
+            Self::Ascii => "-",
+        }
+    }
+
+    /// Gets the resize icon, which is drawn next to the progress bar.
+    pub const fn get_resize_icon(self) -> &'static str {
+        match self {
+            // The arrow doesn't render in many cases on Windows so at least for now,
+            // until the situation improves (and https://en.wikipedia.org/wiki/Windows_Terminal becomes the new default on Windows?),
+            // the plus is used as an alternative there.
+            // Someday the arrow could be used on Windows too.
+            // Then the resize icon could also be renamed to a resize arrow.
+            //
+            // In the future it might be helpful to take a look at the market share of Windows 10 or 11 and decide by that.
+            Self::Unicode if cfg!(not(windows)) => " ↘",
+            Self::Unicode | Self::Ascii => " +",
+        }
+    }
+
+    /// Gets the character between the parts of a status line.
+    pub const fn get_separator(self) -> &'static str {
+        match self {
+            Self::Unicode => "·",
+            Self::Ascii => "|",
+        }
+    }
+
+    /// Gets the characters of a box: the top left, top right, bottom left and bottom right corners and the horizontal and vertical sides.
+    pub const fn get_box(self) -> [&'static str; 6] {
+        match self {
+            Self::Unicode => ["┌", "┐", "└", "┘", "─", "│"],
+            Self::Ascii => ["+", "+", "+", "+", "-", "|"],
+        }
+    }
+
+    /// Checks whether the picture can be drawn with braille characters.
+    pub const fn has_braille(self) -> bool {
+        matches!(self, Self::Unicode)
+    }
+}
+
+/// Gets the clues to draw for a line, which for an empty line is a single `0` if `zero_clues` is enabled.
+fn get_displayed_clues(clues_solution: &[Clue], zero_clues: bool) -> &[Clue] {
+    if clues_solution.is_empty() && zero_clues {
+        &[0]
+    } else {
+        clues_solution
+    }
+}
+
+/// Checks whether the terminal is wide enough for the grid along with the clues on its left.
+///
+/// If it isn't, the clues of the hovered row and column are shown in a single line instead.
+pub const fn fits_left_clues(terminal: &Terminal, grid: &Grid) -> bool {
+    terminal.size.width >= grid.size.width * 2 + grid.max_clues_size.width
+}
+
 /// Gets a point to the first cell of the grid which is together with its clues centered on the screen.
+///
+/// If the grid doesn't fit, the point is moved to the top left instead of wrapping around.
+/// Nothing should be drawn in that case.
 pub const fn centered_point(terminal: &Terminal, grid: &Grid) -> Point {
     let grid_width_half = grid.size.width; // No division because blocks are 2 characters
     let grid_height_half = grid.size.height / 2;
 
-    let max_clues_width_half = grid.max_clues_size.width / 2;
+    // Without the left clues, only the grid is centered horizontally
+    let max_clues_width_half = if fits_left_clues(terminal, grid) {
+        grid.max_clues_size.width / 2
+    } else {
+        0
+    };
     let max_clues_height_half = grid.max_clues_size.height / 2;
 
     Point {
-        x: terminal.size.width / 2 - grid_width_half + max_clues_width_half,
-        y: terminal.size.height / 2 - grid_height_half + max_clues_height_half,
+        x: (terminal.size.width / 2 + max_clues_width_half).saturating_sub(grid_width_half),
+        y: (terminal.size.height / 2 + max_clues_height_half).saturating_sub(grid_height_half),
     }
 }
 
-const HIGHLIGHTED_CLUE_BACKGROUND_COLOR: Color = Color::Byte(238);
+/// How many times bigger the picture can be drawn at most.
+const MAX_PICTURE_ZOOM: u8 = 3;
+
+/// How many characters the remaining count of a row takes, which is enough for the widest grids.
+const ROW_REMAINING_COUNT_WIDTH: u16 = 3;
+
+const DIFFICULTY_LABEL_PREFIX: &str = "Difficulty: ";
+
+/// The difficulty label is above the alerts and the countdown of time attack.
+const DIFFICULTY_LABEL_Y_ALIGNMENT: u16 = 2;
 
 /// Builds and draws the grid to the screen.
 pub struct Builder {
     pub grid: Grid,
     pub point: Point,
+    /// Whether the picture is drawn with braille characters instead of half blocks, making it much smaller.
+    pub braille_picture: bool,
+    /// How many times bigger the picture is drawn, unless it is drawn with braille characters.
+    pub picture_zoom: u8,
+    /// How many rows the top clues are scrolled by, revealing the upper clues that do not fit above the grid.
+    pub clue_scroll_y: u16,
+    pub palette: Palette,
+    pub mode: Mode,
+    /// Whether empty rows and columns have a single `0` clue instead of none.
+    pub zero_clues: bool,
+    /// Whether the mouse can be used. If not, nothing that can only be used with the mouse is drawn.
+    pub mouse: bool,
+    /// Whether new random grids may have rows and columns without any filled cells.
+    pub empty_lines: bool,
+    /// Whether the amount of cells that still have to be filled is shown at the end of each row and column.
+    pub remaining_counts: bool,
+    /// Rings when the grid is solved and when a row or column starts contradicting its clues, if enabled.
+    pub bell: Bell,
+    /// How many rows and columns contradicted their clues when the clues were last drawn.
+    pub contradicted_line_count: usize,
+    /// The label of how hard the rest of the grid is, as last drawn.
+    pub difficulty_label: Option<Cow<'static, str>>,
+    pub glyphs: GlyphSet,
+    /// How often the player checked whether the grid contradicts its clues.
+    pub check_count: usize,
+    /// Whether the player cleared the grid, which ends the streak of clean solves.
+    pub cleared_at_least_once: bool,
+    /// Whether the editor was enabled for the grid, which keeps it from counting towards the records.
+    pub edited_at_least_once: bool,
+    /// Whether a legend of the cells is shown below the basic controls help.
+    pub legend: bool,
+    /// Whether the terminal is too narrow for the left clues and the picture,
+    /// so that the clues of the hovered row and column are shown in a single line at the top instead.
+    pub compact_clues: bool,
 }
 
 impl Builder {
-    pub fn new(terminal: &Terminal, grid: Grid) -> Self {
+    pub fn new(terminal: &Terminal, grid: Grid, palette: Palette, settings: &Settings) -> Self {
         let point = centered_point(terminal, &grid);
+        let compact_clues = !fits_left_clues(terminal, &grid);
+
+        Self {
+            grid,
+            point,
+            braille_picture: false,
+            picture_zoom: 1,
+            clue_scroll_y: 0,
+            palette,
+            mode: settings.mode,
+            zero_clues: settings.zero_clues,
+            mouse: settings.mouse,
+            empty_lines: settings.empty_lines,
+            remaining_counts: false,
+            bell: Bell::for_terminal(settings.bell),
+            contradicted_line_count: 0,
+            difficulty_label: None,
+            check_count: 0,
+            cleared_at_least_once: false,
+            edited_at_least_once: false,
+            legend: settings.legend,
+            glyphs: GlyphSet::new(settings.ascii),
+            compact_clues,
+        }
+    }
 
-        Self { grid, point }
+    /// Creates a builder for tests with a grid of the size full of filled cells, at the point and with the default settings.
+    #[cfg(test)]
+    pub fn for_test(size: Size, point: Point) -> Self {
+        Self {
+            grid: Grid::new(size, vec![Cell::Filled; size.product() as usize]).unwrap(),
+            point,
+            braille_picture: false,
+            picture_zoom: 1,
+            clue_scroll_y: 0,
+            palette: Palette::default(),
+            mode: Mode::Normal,
+            zero_clues: false,
+            mouse: true,
+            empty_lines: true,
+            remaining_counts: false,
+            bell: Bell::default(),
+            contradicted_line_count: 0,
+            difficulty_label: None,
+            check_count: 0,
+            cleared_at_least_once: false,
+            edited_at_least_once: false,
+            legend: true,
+            glyphs: GlyphSet::default(),
+            compact_clues: false,
+        }
     }
 
-    /// Checks whether the point is within the grid on the screen.
-    pub fn contains(&self, point: Point) -> bool {
-        (self.point.y..self.point.y + self.grid.size.height).contains(&point.y)
-            && (self.point.x..self.point.x + self.grid.size.width * 2).contains(&point.x)
+    /// Checks whether solved clues are grayed out, which is not the case in zen mode to take away the pressure.
+    fn grays_out_solved_clues(&self) -> bool {
+        self.palette.has_colors() && self.mode != Mode::Zen
+    }
+
+    /// Sets the colors for the clues of a row or column with the status.
+    ///
+    /// Solved clues are grayed out or, with high contrast, inverted and contradicted clues are shown as errors.
+    fn set_clue_status_colors(&self, terminal: &mut Terminal, status: LineStatus) {
+        match status {
+            LineStatus::Incomplete => {}
+            LineStatus::Solved => self.set_solved_clue_colors(terminal),
+            LineStatus::Contradicted => {
+                if self.palette.has_colors() {
+                    terminal.set_foreground_color(CONTRADICTED_CLUE_COLOR);
+                }
+            }
+        }
+    }
+
+    /// Checks whether every other clue row and column is underlined, which is done when it can't be highlighted with a background color.
+    fn underlines_highlighted_clues(&self) -> bool {
+        !self.palette.has_colors()
+    }
+
+    /// Checks whether the clues of a line with the status are bold, which solved clues are to make them stand out, except in zen mode.
+    fn is_bold_clue_status(&self, status: LineStatus) -> bool {
+        status == LineStatus::Solved && self.mode != Mode::Zen
+    }
+
+    fn set_solved_clue_colors(&self, terminal: &mut Terminal) {
+        if !self.grays_out_solved_clues() {
+            return;
+        }
+
+        if self.palette.is_high_contrast() {
+            let (foreground_color, background_color) = HIGH_CONTRAST_SOLVED_CLUE_COLORS;
+            terminal.set_foreground_color(foreground_color);
+            terminal.set_background_color(background_color);
+        } else {
+            terminal.set_foreground_color(Color::DarkGray);
+        }
+    }
+
+    /// Converts the point on the screen to the point of the cell it is on, if it is within the grid.
+    ///
+    /// This is the only place that decides what is part of the grid, so that points on the clues, the progress bar or the resize icon are never taken for cells.
+    pub fn screen_to_cell(&self, point: Point) -> Option<Point> {
+        let within_grid = (self.point.y..self.point.y + self.grid.size.height).contains(&point.y)
+            && (self.point.x..self.point.x + self.grid.size.width * 2).contains(&point.x);
+
+        within_grid.then(|| Point {
+            // Divide by 2 because cells are 2 characters wide
+            x: (point.x - self.point.x) / 2,
+            y: point.y - self.point.y,
+        })
+    }
+
+    /// Gets the point on the screen where the cell at the point starts and the size that the cell takes up on the screen.
+    ///
+    /// This is the inverse of [`Builder::screen_to_cell`].
+    pub const fn get_cell_screen_rect(&self, cell_point: Point) -> (Point, Size) {
+        (
+            Point {
+                x: self.point.x + cell_point.x * 2,
+                y: self.point.y + cell_point.y,
+            },
+            Size {
+                width: 2,
+                height: 1,
+            },
+        )
     }
 
     pub fn get_center(&self) -> Point {
@@ -53,13 +372,88 @@ impl Builder {
         }
     }
 
-    /// Reconstructs the clues associated with the given `cell_point`.
-    pub fn rebuild_clues(&mut self, terminal: &mut Terminal, cell_point: Point) {
+    /// Checks whether the point is within the left clues on the screen.
+    pub fn left_clues_contain(&self, point: Point) -> bool {
+        !self.compact_clues
+            && (self.point.y..self.point.y + self.grid.size.height).contains(&point.y)
+            && (self.point.x.saturating_sub(self.grid.max_clues_size.width)..self.point.x)
+                .contains(&point.x)
+    }
+
+    /// Gets the clues of the row and the column of the cell in a single line, as shown with compact clues.
+    pub fn get_compact_clues_text(&self, cell_point: Point) -> String {
+        let format_clues = |clues_solution: &[Clue]| {
+            let clues = get_displayed_clues(clues_solution, self.zero_clues);
+            if clues.is_empty() {
+                "-".to_string()
+            } else {
+                clues.iter().map(Clue::to_string).join(" ")
+            }
+        };
+
+        format!(
+            "Row {}: {} {} Col {}: {}",
+            cell_point.y + 1,
+            format_clues(&self.grid.horizontal_clues_solutions[cell_point.y as usize]),
+            self.glyphs.get_separator(),
+            cell_point.x + 1,
+            format_clues(&self.grid.vertical_clues_solutions[cell_point.x as usize]),
+        )
+    }
+
+    /// Gets the amount of rows above the grid that the top clues can be drawn on.
+    ///
+    /// With compact clues, the first row of the screen is kept for them.
+    const fn get_top_clue_height(&self) -> u16 {
+        self.point.y.saturating_sub(self.compact_clues as u16)
+    }
+
+    /// Gets the amount of top clue rows that do not fit above the grid.
+    pub fn get_max_clue_scroll_y(&self) -> u16 {
+        self.grid
+            .max_clues_size
+            .height
+            .saturating_sub(self.get_top_clue_height())
+    }
+
+    /// Scrolls the top clues by one row, revealing the upper clues when scrolling up, and returns whether anything changed.
+    pub fn scroll_clues(&mut self, terminal: &mut Terminal, up: bool) -> bool {
+        let clue_scroll_y = if up {
+            cmp::min(self.clue_scroll_y + 1, self.get_max_clue_scroll_y())
+        } else {
+            self.clue_scroll_y.saturating_sub(1)
+        };
+
+        if clue_scroll_y == self.clue_scroll_y {
+            false
+        } else {
+            self.clear_top_clues(terminal);
+            self.clue_scroll_y = clue_scroll_y;
+            self.draw_top_clues(terminal);
+            true
+        }
+    }
+
+    /// Reconstructs the clues associated with the given `cell_point` and returns whether the space reserved for the clues grew,
+    /// in which case everything needs to be laid out again.
+    #[must_use]
+    pub fn rebuild_clues(&mut self, terminal: &mut Terminal, cell_point: Point) -> bool {
         self.clear_clues(terminal);
-        self.grid.horizontal_clues_solutions[cell_point.y as usize] =
-            self.grid.get_horizontal_clues(cell_point.y).collect();
-        self.grid.vertical_clues_solutions[cell_point.x as usize] =
-            self.grid.get_vertical_clues(cell_point.x).collect();
+        self.grid.rebuild_clues(cell_point)
+    }
+
+    /// Gets the clues of a top clue column together with the rows they are drawn on, skipping the clues that are scrolled out of view.
+    fn get_top_clue_rows<'a>(
+        &self,
+        vertical_clues_solution: &'a [Clue],
+    ) -> impl Iterator<Item = (Clue, u16)> + 'a {
+        let point_y = self.point.y;
+        vertical_clues_solution
+            .iter()
+            .rev()
+            .skip(self.clue_scroll_y as usize)
+            .zip(1..=self.get_top_clue_height())
+            .map(move |(clue, offset)| (*clue, point_y - offset))
     }
 
     /// Draws the top clues while also returning the amount of solved clue rows.
@@ -68,28 +462,48 @@ impl Builder {
 
         let mut highlighted = true;
         let mut solved_rows = 0;
-        for (x, vertical_clues_solution) in self.grid.vertical_clues_solutions.iter().enumerate() {
-            let vertical_clues = self.grid.get_vertical_clues(x as u16);
-            let solved = vertical_clues.eq(vertical_clues_solution.iter().copied());
+        let mut buffer = [0; 8];
+        for x in 0..self.grid.size.width {
+            let status = self.grid.get_column_status(x);
 
+            let underline = highlighted && self.underlines_highlighted_clues();
             if highlighted {
-                terminal.set_background_color(HIGHLIGHTED_CLUE_BACKGROUND_COLOR);
+                if let Some(color) = self.palette.get_highlighted_clue_background_color() {
+                    terminal.set_background_color(color);
+                }
+            }
+            if underline {
+                util::set_underline(terminal, true);
             }
-            if solved {
-                terminal.set_foreground_color(Color::DarkGray);
+            if status == LineStatus::Solved {
                 solved_rows += 1;
             }
+            self.set_clue_status_colors(terminal, status);
+            let bold = self.is_bold_clue_status(status);
+            if bold {
+                util::set_bold(terminal, true);
+            }
 
-            let previous_point_y = self.point.y;
-            for clue in vertical_clues_solution.iter().rev() {
-                self.point.y -= 1;
-                terminal.set_cursor(self.point);
-                terminal.write(&format!("{:<2}", clue));
+            let vertical_clues_solution = get_displayed_clues(
+                &self.grid.vertical_clues_solutions[x as usize],
+                self.zero_clues,
+            );
+            for (clue, y) in self.get_top_clue_rows(vertical_clues_solution) {
+                util::print_at(
+                    terminal,
+                    Point { y, ..self.point },
+                    util::format_padded_number(&mut buffer, clue, 2, true),
+                );
             }
-            self.point.y = previous_point_y;
 
             // We need to reset the colors because we don't always set both the background and foreground color
             terminal.reset_colors();
+            if bold {
+                util::set_bold(terminal, false);
+            }
+            if underline {
+                util::set_underline(terminal, false);
+            }
             highlighted = !highlighted;
             self.point.x += 2;
         }
@@ -104,13 +518,11 @@ impl Builder {
 
         let mut highlighted = true;
         for vertical_clues_solution in self.grid.vertical_clues_solutions.iter() {
-            let previous_point_y = self.point.y;
-            for _ in vertical_clues_solution.iter().rev() {
-                self.point.y -= 1;
-                terminal.set_cursor(self.point);
-                terminal.write("  ");
+            let vertical_clues_solution =
+                get_displayed_clues(vertical_clues_solution, self.zero_clues);
+            for (_, y) in self.get_top_clue_rows(vertical_clues_solution) {
+                util::print_at(terminal, Point { y, ..self.point }, "  ");
             }
-            self.point.y = previous_point_y;
 
             highlighted = !highlighted;
             self.point.x += 2;
@@ -120,33 +532,65 @@ impl Builder {
     }
 
     /// Draws the left clues while also returning the amount of solved clue rows.
+    ///
+    /// With compact clues, nothing is drawn but the solved clue rows are still counted.
     fn draw_left_clues(&mut self, terminal: &mut Terminal) -> usize {
+        if self.compact_clues {
+            return (0..self.grid.size.height)
+                .filter(|y| self.grid.get_row_status(*y) == LineStatus::Solved)
+                .count();
+        }
+
         let previous_point = self.point;
 
-        self.point.x -= 2;
+        let clue_width = self.grid.get_left_clue_width();
+        self.point.x -= clue_width;
         let mut highlighted = true;
         let mut solved_rows = 0;
-        for (y, horizontal_clues_solution) in
-            self.grid.horizontal_clues_solutions.iter().enumerate()
-        {
+        let mut buffer = [0; 8];
+        for y in 0..self.grid.size.height {
             terminal.set_cursor(self.point);
-            let horizontal_clues = self.grid.get_horizontal_clues(y as u16);
-            let solved = horizontal_clues.eq(horizontal_clues_solution.iter().copied());
+            let status = self.grid.get_row_status(y);
 
+            let underline = highlighted && self.underlines_highlighted_clues();
             if highlighted {
-                terminal.set_background_color(HIGHLIGHTED_CLUE_BACKGROUND_COLOR);
+                if let Some(color) = self.palette.get_highlighted_clue_background_color() {
+                    terminal.set_background_color(color);
+                }
             }
-            if solved {
-                terminal.set_foreground_color(Color::DarkGray);
+            if underline {
+                util::set_underline(terminal, true);
+            }
+            if status == LineStatus::Solved {
                 solved_rows += 1;
             }
+            self.set_clue_status_colors(terminal, status);
+            let bold = self.is_bold_clue_status(status);
+            if bold {
+                util::set_bold(terminal, true);
+            }
 
+            let horizontal_clues_solution = get_displayed_clues(
+                &self.grid.horizontal_clues_solutions[y as usize],
+                self.zero_clues,
+            );
             for clue in horizontal_clues_solution.iter().rev() {
-                terminal.write(&format!("{:>2}", clue));
-                terminal.move_cursor_left_by(4);
+                terminal.write(util::format_padded_number(
+                    &mut buffer,
+                    *clue,
+                    clue_width as usize,
+                    false,
+                ));
+                terminal.move_cursor_left_by(clue_width * 2);
             }
             // We need to reset the colors because we don't always set both the background and foreground color
             terminal.reset_colors();
+            if bold {
+                util::set_bold(terminal, false);
+            }
+            if underline {
+                util::set_underline(terminal, false);
+            }
             highlighted = !highlighted;
             self.point.y += 1;
         }
@@ -157,15 +601,22 @@ impl Builder {
     }
     /// Clears the left clues, only graphically.
     fn clear_left_clues(&mut self, terminal: &mut Terminal) {
+        if self.compact_clues {
+            return;
+        }
+
         let previous_point = self.point;
 
-        self.point.x -= 2;
+        let clue_width = self.grid.get_left_clue_width();
+        self.point.x -= clue_width;
         let mut highlighted = true;
         for horizontal_clues_solution in self.grid.horizontal_clues_solutions.iter() {
             terminal.set_cursor(self.point);
+            let horizontal_clues_solution =
+                get_displayed_clues(horizontal_clues_solution, self.zero_clues);
             for _ in horizontal_clues_solution.iter().rev() {
-                terminal.write("  ");
-                terminal.move_cursor_left_by(4);
+                terminal.write(&" ".repeat(clue_width as usize));
+                terminal.move_cursor_left_by(clue_width * 2);
             }
             terminal.reset_colors();
             highlighted = !highlighted;
@@ -206,7 +657,7 @@ impl Builder {
                     x: x as u16,
                     y: y as u16,
                 };
-                cell.draw(terminal, point, false);
+                cell.draw(terminal, point, false, self.palette);
                 terminal.reset_colors();
                 self.point.x += 2;
             }
@@ -236,8 +687,9 @@ impl Builder {
 
     /// Draws an empty grid.
     pub fn draw_empty_grid(&mut self, terminal: &mut Terminal) {
+        let palette = self.palette;
         self.empty_grid(terminal, |terminal, point| {
-            Cell::Empty.draw(terminal, point, false);
+            Cell::Empty.draw(terminal, point, false, palette);
         });
     }
 
@@ -248,17 +700,116 @@ impl Builder {
         });
     }
 
+    /// Draws a lower half block, which is colored with both the foreground and the background color.
     fn draw_half_block(terminal: &mut Terminal) {
         terminal.write("▄");
     }
 
-    /// Draws the grid in smaller form on the top left, making it easier to see the whole picture.
+    /// Draws the picture on the top left as blocks or, if enabled, in braille.
+    pub fn draw_picture(&mut self, terminal: &mut Terminal) {
+        // The picture takes the space above the left clues, which isn't there with compact clues
+        if self.compact_clues {
+            return;
+        }
+
+        if self.braille_picture && self.glyphs.has_braille() {
+            self.draw_picture_braille(terminal);
+        } else {
+            self.draw_picture_zoom(terminal, self.picture_zoom);
+        }
+    }
+
+    /// Clears the picture, only graphically.
+    ///
+    /// The area of the half block picture is cleared because it is always at least as big as the braille picture.
+    pub fn clear_picture(&mut self, terminal: &mut Terminal) {
+        if self.compact_clues {
+            return;
+        }
+
+        let picture_size = self.get_picture_size(self.picture_zoom);
+
+        for y in self.point.y - picture_size.height..self.point.y {
+            terminal.set_cursor(Point {
+                x: self.point.x - picture_size.width,
+                y,
+            });
+            terminal.write(&" ".repeat(picture_size.width as usize));
+        }
+    }
+
+    /// Gets the size that the picture takes up on the screen at the zoom.
+    fn get_picture_size(&self, zoom: u8) -> Size {
+        let zoom = zoom as u16;
+        Size {
+            width: self.grid.size.width * zoom,
+            height: crate::get_picture_height(self.grid.size, zoom as u8),
+        }
+    }
+
+    /// Gets the highest zoom at which the picture still fits on the top left, between the screen border and the clues.
+    ///
+    /// Room is left above it for the top text.
+    pub fn get_max_picture_zoom(&self) -> u8 {
+        if self.compact_clues {
+            return 1;
+        }
+
+        (2..=MAX_PICTURE_ZOOM)
+            .rev()
+            .find(|zoom| {
+                let picture_size = self.get_picture_size(*zoom);
+                picture_size.width <= self.point.x
+                    && picture_size.height + crate::TOP_TEXT_HEIGHT < self.point.y
+            })
+            .unwrap_or(1)
+    }
+
+    /// Draws the picture with the zoom.
+    ///
+    /// At a zoom of 1, two vertically adjacent cells are drawn per character.
+    /// Above that, every cell is drawn as that many characters next to each other, so a zoom of 2 doubles both the width and the height.
+    pub fn draw_picture_zoom(&mut self, terminal: &mut Terminal, zoom: u8) {
+        if zoom <= 1 {
+            self.draw_picture_half_blocks(terminal);
+            return;
+        }
+
+        let picture_size = self.get_picture_size(zoom);
+        let mut point = Point {
+            x: self.point.x - picture_size.width,
+            y: self.point.y - picture_size.height,
+        };
+
+        let has_colors = self.palette.has_colors();
+
+        for row in self.grid.cells.chunks(self.grid.size.width as usize) {
+            terminal.set_cursor(point);
+            for cell in row {
+                if has_colors {
+                    terminal.set_background_color(self.palette.get_picture_cell_color(*cell));
+                    terminal.write(&" ".repeat(zoom as usize));
+                } else {
+                    terminal.write(
+                        &self
+                            .glyphs
+                            .get_monochrome_half_block(*cell, *cell)
+                            .repeat(zoom as usize),
+                    );
+                }
+            }
+            point.y += 1;
+        }
+        terminal.reset_colors();
+    }
+
+    /// Draws the grid in smaller form on the top left with two vertically adjacent cells per character, making it easier to see the whole picture.
     ///
     /// NOTE: Perhaps at some point in the future [sixel](https://en.wikipedia.org/wiki/Sixel) can be supported.
     ///       Maybe exclusively for cases where the window size does not suffice.
     ///
     /// NOTE: Perhaps at some point, if stabilized, `array_chunks` can be used to implement this.
-    pub fn draw_picture(&mut self, terminal: &mut Terminal) {
+    fn draw_picture_half_blocks(&mut self, terminal: &mut Terminal) {
         let previous_point = self.point;
 
         self.point.x -= self.grid.size.width;
@@ -267,13 +818,20 @@ impl Builder {
 
         let mut chunks = self.grid.cells.chunks(self.grid.size.width as usize);
 
+        // Half blocks with two colors can't be drawn without them
+        let has_colors = self.palette.has_colors() && self.glyphs == GlyphSet::Unicode;
+
         if self.grid.size.height % 2 == 1 {
             let uneven_chunk = chunks.next().unwrap(); // There will be one more chunk
 
             terminal.set_cursor(self.point);
             for cell in uneven_chunk {
-                terminal.set_foreground_color(cell.get_color());
-                Self::draw_half_block(terminal);
+                if has_colors {
+                    terminal.set_foreground_color(self.palette.get_picture_cell_color(*cell));
+                    Self::draw_half_block(terminal);
+                } else {
+                    terminal.write(self.glyphs.get_monochrome_half_block(Cell::Empty, *cell));
+                }
             }
         }
 
@@ -281,15 +839,41 @@ impl Builder {
             self.point.y += 1;
             terminal.set_cursor(self.point);
             for (upper_cell, lower_cell) in first_row.iter().zip(second_row) {
-                terminal.set_background_color(upper_cell.get_color());
-                terminal.set_foreground_color(lower_cell.get_color());
-                Self::draw_half_block(terminal);
+                if has_colors {
+                    terminal.set_background_color(self.palette.get_picture_cell_color(*upper_cell));
+                    terminal.set_foreground_color(self.palette.get_picture_cell_color(*lower_cell));
+                    Self::draw_half_block(terminal);
+                } else {
+                    terminal.write(
+                        self.glyphs
+                            .get_monochrome_half_block(*upper_cell, *lower_cell),
+                    );
+                }
             }
         }
 
         self.point = previous_point;
     }
 
+    /// Draws the picture with 2x4 cells per character, showing only the filled cells.
+    pub fn draw_picture_braille(&mut self, terminal: &mut Terminal) {
+        let rows = get_braille_rows(&self.grid);
+
+        let mut point = Point {
+            x: self.point.x - self.grid.size.width.div_ceil(2),
+            y: self.point.y - rows.len() as u16,
+        };
+
+        if self.palette.has_colors() {
+            terminal.set_foreground_color(Color::White);
+        }
+        for row in rows {
+            util::print_at(terminal, point, &row);
+            point.y += 1;
+        }
+        terminal.reset_colors();
+    }
+
     /// Draws the progress of solved clue rows as a bar at the bottom.
     fn draw_progress_bar(&mut self, terminal: &mut Terminal, solved_rows: usize) {
         terminal.set_cursor(Point {
@@ -301,16 +885,32 @@ impl Builder {
         let percentage = solved_rows as f64 / (self.grid.size.width + self.grid.size.height) as f64;
         let width = (percentage * grid_width as f64) as u16;
 
-        terminal.set_foreground_color(Color::Gray);
+        let has_colors = self.palette.has_colors();
+        let (solved_color, unsolved_color) = if self.palette.is_high_contrast() {
+            (Color::White, Color::Black)
+        } else {
+            (Color::Gray, Color::DarkGray)
+        };
+
+        if has_colors {
+            terminal.set_foreground_color(solved_color);
+        }
         for _ in 0..width {
-            Self::draw_half_block(terminal);
+            terminal.write(self.glyphs.get_progress_bar(true));
         }
 
         let rest = grid_width - width;
         if rest > 0 {
-            terminal.set_foreground_color(Color::DarkGray);
-            for _ in 0..rest {
-                Self::draw_half_block(terminal);
+            if has_colors {
+                terminal.set_foreground_color(unsolved_color);
+                for _ in 0..rest {
+                    terminal.write(self.glyphs.get_progress_bar(false));
+                }
+            } else {
+                // The rest can't be told apart by its color
+                for _ in 0..rest {
+                    terminal.write(" ");
+                }
             }
         }
     }
@@ -331,25 +931,78 @@ impl Builder {
         self.clear_resize_icon(terminal);
     }
 
+    /// Gets the point right of the grid where the remaining counts of the rows start.
+    fn get_row_remaining_counts_point(&self) -> Point {
+        Point {
+            x: self.point.x + self.grid.size.width * 2 + 1,
+            y: self.point.y,
+        }
+    }
+
+    /// Draws how many cells still have to be filled for each row right of the grid
+    /// and for each column below the grid, in place of the progress bar.
+    fn draw_remaining_counts(&mut self, terminal: &mut Terminal) {
+        if self.palette.has_colors() {
+            terminal.set_foreground_color(Color::DarkGray);
+        }
+
+        let row_point = self.get_row_remaining_counts_point();
+        // The counts of the rows would be cut off
+        if row_point.x + ROW_REMAINING_COUNT_WIDTH <= terminal.size.width {
+            for y in 0..self.grid.size.height {
+                let text = format!(
+                    "{:<width$}",
+                    self.grid.remaining_in_row(y),
+                    width = ROW_REMAINING_COUNT_WIDTH as usize
+                );
+                util::print_at(
+                    terminal,
+                    Point {
+                        y: row_point.y + y,
+                        ..row_point
+                    },
+                    &text,
+                );
+            }
+        }
+
+        terminal.set_cursor(Point {
+            y: self.point.y + self.grid.size.height,
+            ..self.point
+        });
+        for x in 0..self.grid.size.width {
+            // Like the top clues, the counts of the columns take two characters
+            terminal.write(&format!("{:<2}", self.grid.remaining_in_column(x) % 100));
+        }
+
+        terminal.reset_colors();
+    }
+
+    /// Clears the remaining counts of the rows. The ones of the columns are cleared with the progress bar.
+    pub fn clear_row_remaining_counts(&mut self, terminal: &mut Terminal) {
+        let row_point = self.get_row_remaining_counts_point();
+        if row_point.x + ROW_REMAINING_COUNT_WIDTH > terminal.size.width {
+            return;
+        }
+
+        for y in 0..self.grid.size.height {
+            terminal.set_cursor(Point {
+                y: row_point.y + y,
+                ..row_point
+            });
+            for _ in 0..ROW_REMAINING_COUNT_WIDTH {
+                terminal.write(" ");
+            }
+        }
+    }
+
     /// Draws the resize icon, at the bottom right, next to the progress bar.
     fn draw_resize_icon(&mut self, terminal: &mut Terminal) {
-        terminal.set_foreground_color(Color::DarkGray);
-
-        #[cfg(not(windows))]
-        terminal.write(" ↘");
+        if self.palette.has_colors() {
+            terminal.set_foreground_color(Color::DarkGray);
+        }
 
-        // The above doesn't render in many cases on Windows so at least for now,
-        // until the situation improves (and https://en.wikipedia.org/wiki/Windows_Terminal becomes the new default on Windows?),
-        // this is used as an alternative.
-        // Someday the arrow could be used on Windows too.
-        // Then the resize icon could also be renamed to a resize arrow.
-        //
-        // In the future it might be helpful to take a look at the market share of Windows 10 or 11 and decide by that.
-        //
-        // In this regard, for good Windows terminal compatibility,
-        // I generally recommend sticking to the characters listed on https://en.wikipedia.org/wiki/Code_page_437
-        #[cfg(windows)]
-        terminal.write(" +");
+        terminal.write(self.glyphs.get_resize_icon());
     }
 
     /// Clears the resize icon.
@@ -357,6 +1010,56 @@ impl Builder {
         terminal.write("  ");
     }
 
+    /// Estimates again how hard the rest of the grid is and draws the label of that if it changed.
+    ///
+    /// If the filled cells contradict the clues, there is no label.
+    pub fn update_difficulty_label(&mut self, terminal: &mut Terminal) {
+        let difficulty_label = self
+            .grid
+            .get_remaining_difficulty_score()
+            .map(|score| Cow::Borrowed(get_difficulty_label(score)));
+
+        if difficulty_label != self.difficulty_label {
+            self.clear_difficulty_label(terminal);
+            self.difficulty_label = difficulty_label;
+            self.draw_difficulty_label(terminal);
+        }
+    }
+
+    /// Updates the label of how hard the rest of the grid is after the grid changed.
+    ///
+    /// While editing, there is no label because the clues are changing.
+    pub fn refresh_difficulty_label(&mut self, terminal: &mut Terminal, editor_toggled: bool) {
+        if editor_toggled {
+            self.clear_difficulty_label(terminal);
+        } else {
+            self.update_difficulty_label(terminal);
+        }
+    }
+
+    /// Draws the label of how hard the rest of the grid is in the top right corner, if there is one.
+    pub fn draw_difficulty_label(&self, terminal: &mut Terminal) {
+        if let Some(difficulty_label) = &self.difficulty_label {
+            let text = format!("{}{}", DIFFICULTY_LABEL_PREFIX, difficulty_label);
+            crate::set_cursor_for_top_right_text(
+                terminal,
+                self,
+                text.len(),
+                DIFFICULTY_LABEL_Y_ALIGNMENT,
+            );
+            terminal.write(&text);
+        }
+    }
+
+    /// Clears the label of how hard the rest of the grid is and removes it.
+    pub fn clear_difficulty_label(&mut self, terminal: &mut Terminal) {
+        if let Some(difficulty_label) = self.difficulty_label.take() {
+            let len = DIFFICULTY_LABEL_PREFIX.len() + difficulty_label.len();
+            crate::set_cursor_for_top_right_text(terminal, self, len, DIFFICULTY_LABEL_Y_ALIGNMENT);
+            terminal.write(&" ".repeat(len));
+        }
+    }
+
     /// Draws the grid, the picture and the clues while also returning whether all the drawn clues were solved ones (i.e. whether the grid was solved).
     #[must_use]
     pub fn draw_all(&mut self, terminal: &mut Terminal) -> bool {
@@ -366,9 +1069,30 @@ impl Builder {
 
         let solved_rows = self.draw_clues(terminal);
 
-        self.draw_progress_bar(terminal, solved_rows);
+        // The statuses were just checked for drawing the clues so this doesn't check them again
+        let (contradicted_rows, contradicted_columns) = self.grid.get_contradicted_line_counts();
+        let contradicted_line_count = (contradicted_rows + contradicted_columns) as usize;
+        if contradicted_line_count > self.contradicted_line_count {
+            self.bell.ring(terminal);
+        }
+        self.contradicted_line_count = contradicted_line_count;
+
+        if self.remaining_counts {
+            self.draw_remaining_counts(terminal);
+        } else if self.mode == Mode::Zen {
+            // Leave the space of the progress bar empty
+            terminal.set_cursor(Point {
+                x: self.point.x + self.grid.size.width * 2,
+                y: self.point.y + self.grid.size.height,
+            });
+        } else {
+            self.draw_progress_bar(terminal, solved_rows);
+        }
 
-        self.draw_resize_icon(terminal);
+        // The grid can only be resized by dragging the icon
+        if self.mouse {
+            self.draw_resize_icon(terminal);
+        }
 
         solved_rows == (self.grid.size.width + self.grid.size.height) as usize
     }
@@ -379,33 +1103,114 @@ mod tests {
     use super::*;
     use crate::grid::Cell;
     use std::io;
-    use terminal::util::Size;
 
     fn get_terminal_and_builder(stdout: io::StdoutLock) -> (Terminal, Builder) {
         let size = Size {
             width: 10,
             height: 5,
         };
-        let grid = Grid::new(size, vec![Cell::Empty; size.product() as usize]);
+        let grid = Grid::new(size, vec![Cell::Empty; size.product() as usize]).unwrap();
         let terminal = Terminal::new(stdout).unwrap();
-        let builder = Builder::new(&terminal, grid);
+        let builder = Builder::new(&terminal, grid, Palette::default(), &Settings::default());
         (terminal, builder)
     }
 
     #[test]
-    fn test_contains() {
+    fn test_is_bold_clue_status() {
+        let stdout = io::stdout();
+        let (_, mut builder) = get_terminal_and_builder(stdout.lock());
+
+        assert!(builder.is_bold_clue_status(LineStatus::Solved));
+        assert!(!builder.is_bold_clue_status(LineStatus::Incomplete));
+        assert!(!builder.is_bold_clue_status(LineStatus::Contradicted));
+        builder.mode = Mode::Zen;
+        assert!(!builder.is_bold_clue_status(LineStatus::Solved));
+    }
+
+    #[test]
+    fn test_grays_out_solved_clues() {
+        let stdout = io::stdout();
+        let (_, mut builder) = get_terminal_and_builder(stdout.lock());
+
+        assert!(builder.grays_out_solved_clues());
+        builder.mode = Mode::Zen;
+        assert!(!builder.grays_out_solved_clues());
+    }
+
+    #[test]
+    fn test_screen_to_cell() {
         let stdout = io::stdout();
         let (_, builder) = get_terminal_and_builder(stdout.lock());
+        let Point { x, y } = builder.point;
+        let right = x + builder.grid.size.width * 2;
+        let bottom = y + builder.grid.size.height;
+
+        assert_eq!(
+            builder.screen_to_cell(builder.point),
+            Some(Point { x: 0, y: 0 })
+        );
+        // Both halves of the last cell
+        for last_cell_x in [right - 2, right - 1] {
+            assert_eq!(
+                builder.screen_to_cell(Point {
+                    x: last_cell_x,
+                    y: bottom - 1
+                }),
+                Some(Point { x: 9, y: 4 })
+            );
+        }
 
-        assert!(!builder.contains(Point {
-            x: builder.point.x - 1,
-            y: builder.point.y - 1
-        }));
-        assert!(builder.contains(builder.point));
-        assert!(!builder.contains(Point {
-            x: builder.point.x + builder.grid.size.width,
-            y: builder.point.y + builder.grid.size.height
-        }));
+        for cell_point in [
+            Point { x: 0, y: 0 },
+            Point { x: 3, y: 2 },
+            Point { x: 9, y: 4 },
+        ] {
+            let (screen_point, size) = builder.get_cell_screen_rect(cell_point);
+            assert_eq!(
+                size,
+                Size {
+                    width: 2,
+                    height: 1
+                }
+            );
+            // Every character of the cell is on the cell
+            for x in 0..size.width {
+                assert_eq!(
+                    builder.screen_to_cell(Point {
+                        x: screen_point.x + x,
+                        ..screen_point
+                    }),
+                    Some(cell_point)
+                );
+            }
+        }
+
+        // One character outside each edge
+        assert_eq!(builder.screen_to_cell(Point { x: x - 1, y }), None);
+        assert_eq!(builder.screen_to_cell(Point { x: right, y }), None);
+        assert_eq!(builder.screen_to_cell(Point { x, y: y - 1 }), None);
+        assert_eq!(builder.screen_to_cell(Point { x, y: bottom }), None);
+        assert_eq!(builder.screen_to_cell(Point { x: x - 1, y: y - 1 }), None);
+
+        // The top clues and the left clues
+        assert_eq!(builder.screen_to_cell(Point { x: x + 4, y: y - 1 }), None);
+        assert_eq!(builder.screen_to_cell(Point { x: x - 2, y: y + 2 }), None);
+
+        // The progress bar and the resize icon
+        assert_eq!(
+            builder.screen_to_cell(Point {
+                x: x + 4,
+                y: bottom
+            }),
+            None
+        );
+        assert_eq!(
+            builder.screen_to_cell(Point {
+                x: right + 1,
+                y: bottom
+            }),
+            None
+        );
     }
 
     #[test]
@@ -418,6 +1223,28 @@ mod tests {
         assert_eq!(previous_point, builder.point);
     }
 
+    #[test]
+    fn test_rebuild_clues() {
+        let stdout = io::stdout();
+        let (mut terminal, mut builder) = get_terminal_and_builder(stdout.lock());
+
+        let previous_point = builder.point;
+        for x in [0, 2] {
+            let cell_point = Point { x, y: 0 };
+            *builder.grid.get_mut_cell(cell_point) = Cell::Filled;
+            // Space for one clue is reserved even for the empty grid
+            assert_eq!(builder.rebuild_clues(&mut terminal, cell_point), x != 0);
+        }
+        assert_eq!(
+            builder.grid.max_clues_size,
+            Size {
+                width: 4,
+                height: 1
+            }
+        );
+        assert_eq!(previous_point, builder.point);
+    }
+
     #[test]
     fn test_draw_grid() {
         let stdout = io::stdout();
@@ -428,6 +1255,93 @@ mod tests {
         assert_eq!(previous_point, builder.point);
     }
 
+    #[test]
+    fn test_centered_point_on_small_terminal() {
+        let stdout = std::io::stdout();
+        let mut terminal = Terminal::new(stdout.lock()).unwrap();
+        terminal.size = Size {
+            width: 10,
+            height: 5,
+        };
+        let size = Size {
+            width: 20,
+            height: 20,
+        };
+        let grid = Grid::new(size, vec![Cell::Empty; size.product() as usize]).unwrap();
+
+        assert_eq!(centered_point(&terminal, &grid), Point { x: 0, y: 0 });
+    }
+
+    #[test]
+    fn test_compact_clues() {
+        let stdout = std::io::stdout();
+        let mut terminal = Terminal::new(stdout.lock()).unwrap();
+        #[rustfmt::skip]
+        let grid = Grid::from_lines(&[
+            "1 1 1",
+            "     ",
+            "11 11",
+        ]).unwrap();
+
+        // The grid takes 10 characters and the left clues 6 more
+        terminal.size = Size {
+            width: 15,
+            height: 24,
+        };
+        assert!(!fits_left_clues(&terminal, &grid));
+        let mut builder = Builder::new(
+            &terminal,
+            grid.clone(),
+            Palette::default(),
+            &Settings::default(),
+        );
+        assert!(builder.compact_clues);
+        assert_eq!(builder.point.x, 2);
+        assert!(!builder.left_clues_contain(Point {
+            x: 1,
+            y: builder.point.y
+        }));
+        assert_eq!(
+            builder.get_compact_clues_text(Point { x: 1, y: 0 }),
+            "Row 1: 1 1 1 · Col 2: 1"
+        );
+        assert_eq!(
+            builder.get_compact_clues_text(Point { x: 2, y: 1 }),
+            "Row 2: - · Col 3: 1"
+        );
+        // Nothing is drawn left of the grid
+        let _ = builder.draw_all(&mut terminal);
+
+        terminal.size.width = 16;
+        assert!(fits_left_clues(&terminal, &grid));
+        let builder = Builder::new(&terminal, grid, Palette::default(), &Settings::default());
+        assert!(!builder.compact_clues);
+    }
+
+    #[test]
+    fn test_ascii_glyph_set() {
+        let glyphs = GlyphSet::Ascii;
+        let cells = [Cell::Empty, Cell::Filled];
+        let mut strs: Vec<&str> = cells
+            .iter()
+            .flat_map(|upper_cell| {
+                cells.iter().map(move |lower_cell| {
+                    glyphs.get_monochrome_half_block(*upper_cell, *lower_cell)
+                })
+            })
+            .collect();
+        strs.extend([
+            glyphs.get_progress_bar(true),
+            glyphs.get_progress_bar(false),
+            glyphs.get_resize_icon(),
+            glyphs.get_separator(),
+        ]);
+        strs.extend(glyphs.get_box());
+
+        assert!(strs.iter().all(|str| str.is_ascii()));
+        assert!(!glyphs.has_braille());
+    }
+
     #[test]
     fn test_draw_picture() {
         let stdout = io::stdout();
@@ -438,6 +1352,26 @@ mod tests {
         assert_eq!(previous_point, builder.point);
     }
 
+    #[test]
+    fn test_get_max_picture_zoom() {
+        let stdout = io::stdout();
+        let (mut terminal, mut builder) = get_terminal_and_builder(stdout.lock());
+
+        builder.point = Point { x: 31, y: 10 };
+        assert_eq!(builder.get_max_picture_zoom(), 3);
+        builder.picture_zoom = 3;
+        builder.draw_picture(&mut terminal);
+        builder.clear_picture(&mut terminal);
+
+        // At 3 times the width, the picture would go past the left of the screen
+        builder.point.x = 25;
+        assert_eq!(builder.get_max_picture_zoom(), 2);
+
+        // There would be no room for the top text above it
+        builder.point.y = 7;
+        assert_eq!(builder.get_max_picture_zoom(), 1);
+    }
+
     #[test]
     fn test_draw_empty_grid() {
         let stdout = io::stdout();
@@ -460,4 +1394,122 @@ mod tests {
         }
         assert_eq!(previous_point, builder.point);
     }
+
+    /// Measures how long redrawing takes while dragging the mouse across every cell of the largest square grid,
+    /// once with the progress bar and once with the remaining counts.
+    ///
+    /// Run with `cargo test --release bench_drag -- --ignored --nocapture`.
+    #[test]
+    #[ignore]
+    fn bench_drag() {
+        let size = Size {
+            width: 99,
+            height: 99,
+        };
+        let stdout = io::stdout();
+        let mut terminal = Terminal::new(stdout.lock()).unwrap();
+
+        for remaining_counts in [false, true] {
+            let grid =
+                Grid::random_with_rng(size, true, &mut fastrand::Rng::with_seed(42)).unwrap();
+            let mut builder =
+                Builder::new(&terminal, grid, Palette::default(), &Settings::default());
+            builder.remaining_counts = remaining_counts;
+
+            let starting_time = std::time::Instant::now();
+            for y in 0..size.height {
+                for x in 0..size.width {
+                    *builder.grid.get_mut_cell(Point { x, y }) = Cell::Filled;
+                    #[allow(unused_must_use)]
+                    {
+                        builder.draw_all(&mut terminal);
+                    }
+                }
+            }
+            let elapsed = starting_time.elapsed();
+
+            eprintln!(
+                "remaining counts {}: {} redraws in {:?} ({:?} per redraw)",
+                remaining_counts,
+                size.product(),
+                elapsed,
+                elapsed / size.product()
+            );
+        }
+    }
+
+    #[test]
+    fn test_get_braille_rows() {
+        #[rustfmt::skip]
+        let mut grid = Grid::from_lines(&[
+            "111",
+            "111",
+            "111",
+            "111",
+            "111",
+        ]).unwrap();
+        assert_eq!(get_braille_rows(&grid), ["⠀⠀", "⠀⠀"]);
+
+        for cell in &mut grid.cells {
+            *cell = Cell::Filled;
+        }
+        assert_eq!(get_braille_rows(&grid), ["⣿⡇", "⠉⠁"]);
+
+        *grid.get_mut_cell(Point { x: 0, y: 0 }) = Cell::Crossed;
+        *grid.get_mut_cell(Point { x: 1, y: 3 }) = Cell::Empty;
+        assert_eq!(get_braille_rows(&grid), ["⡾⡇", "⠉⠁"]);
+    }
+
+    #[test]
+    fn test_get_displayed_clues() {
+        assert_eq!(get_displayed_clues(&[], false), []);
+        assert_eq!(get_displayed_clues(&[], true), [0]);
+        assert_eq!(get_displayed_clues(&[1, 2], true), [1, 2]);
+    }
+
+    #[test]
+    fn test_get_top_clue_rows() {
+        #[rustfmt::skip]
+        let grid = Grid::from_lines(&[
+            "1",
+            "1",
+            " ",
+            "1",
+            " ",
+            "1",
+            "1",
+            "1",
+        ]).unwrap();
+        let mut builder = Builder::for_test(grid.size, Point { x: 10, y: 2 });
+        builder.grid = grid;
+        let clues = builder.grid.vertical_clues_solutions[0].clone();
+        assert_eq!(clues, [2, 1, 3]);
+
+        // Only two of the three clues fit above the grid
+        assert_eq!(builder.get_max_clue_scroll_y(), 1);
+        assert_eq!(
+            builder.get_top_clue_rows(&clues).collect::<Vec<_>>(),
+            [(3, 1), (1, 0)]
+        );
+
+        builder.clue_scroll_y = 1;
+        assert_eq!(
+            builder.get_top_clue_rows(&clues).collect::<Vec<_>>(),
+            [(1, 1), (2, 0)]
+        );
+    }
+
+    #[test]
+    fn test_update_difficulty_label() {
+        let stdout = io::stdout();
+        let (mut terminal, mut builder) = get_terminal_and_builder(stdout.lock());
+
+        // There are no clues so every cell is empty
+        builder.update_difficulty_label(&mut terminal);
+        assert_eq!(builder.difficulty_label.as_deref(), Some("trivial"));
+
+        *builder.grid.get_mut_cell(Point { x: 0, y: 0 }) = Cell::Filled;
+        builder.update_difficulty_label(&mut terminal);
+        assert_eq!(builder.difficulty_label, None);
+    }
 }
@@ -1,7 +1,15 @@
-use crate::{grid::builder::Builder, undo_redo_buffer, util, Grid, State};
-use std::{borrow::Cow, time::Instant};
+use crate::{
+    grid::{builder::Builder, palette::Palette, Mistake},
+    undo_redo_buffer, util, AlertSeverity, Grid, State,
+};
+use std::{
+    borrow::Cow,
+    collections::HashSet,
+    time::{Duration, Instant},
+};
 use terminal::{
-    util::{Color, Point},
+    event::Event,
+    util::{Color, Point, Size},
     Terminal,
 };
 
@@ -44,7 +52,17 @@ impl Cell {
         }
     }
 
-    fn get_highlighted_color(&self) -> Color {
+    /// Gets the cell that follows this one when cycling through the cells that the player can place.
+    pub const fn cycle(&self) -> Self {
+        match self {
+            Cell::Empty | Cell::Measured(_, _) => Cell::Filled,
+            Cell::Filled => Cell::Crossed,
+            Cell::Crossed => Cell::Maybed,
+            Cell::Maybed => Cell::Empty,
+        }
+    }
+
+    pub fn get_highlighted_color(&self) -> Color {
         match self {
             Cell::Empty => Color::DarkGray,
             Cell::Filled => Color::Gray,
@@ -54,10 +72,21 @@ impl Cell {
         }
     }
 
-    pub fn draw(&self, terminal: &mut Terminal, point: Point, highlight: bool) {
-        /// Every 5 cells, the color changes to make the grid and its cells easier to look at and distinguish.
-        const SEPARATION_POINT: u16 = 5;
+    /// Gets what the cell is drawn as without colors, where cells are told apart by their characters.
+    pub fn get_monochrome_content(&self, highlight: bool) -> Cow<'static, str> {
+        match self {
+            Cell::Empty if highlight => "::".into(),
+            Cell::Empty => "··".into(),
+            Cell::Filled => "##".into(),
+            Cell::Maybed => "??".into(),
+            Cell::Crossed => "xx".into(),
+            // Only the last two digits fit into a cell
+            Cell::Measured(Some(index), _) => format!("{:>2}", index % 100).into(),
+            Cell::Measured(None, _) => "--".into(),
+        }
+    }
 
+    pub fn draw(&self, terminal: &mut Terminal, point: Point, highlight: bool, palette: Palette) {
         fn draw(
             terminal: &mut Terminal,
             foreground_color: Option<Color>,
@@ -71,33 +100,23 @@ impl Cell {
             terminal.write(&content);
         }
 
-        let mut background_color = if highlight {
-            self.get_highlighted_color()
-        } else {
-            self.get_color()
-        };
+        if !palette.has_colors() {
+            terminal.write(&self.get_monochrome_content(highlight));
+            return;
+        }
+
+        let mut background_color = palette.get_cell_color(*self, highlight);
 
         let (foreground_color, background_color, content) = match self {
             Cell::Empty => {
-                let x_reached_point = point.x / SEPARATION_POINT % 2 == 0;
-                let y_reached_point = point.y / SEPARATION_POINT % 2 == 0;
-                let mut background_color_byte = if x_reached_point ^ y_reached_point {
-                    238
-                } else {
-                    240
-                };
-
-                if highlight {
-                    background_color_byte -= 3;
-                }
-
-                background_color = Color::Byte(background_color_byte);
+                background_color = palette.get_empty_cell_color(point, highlight);
 
                 (None, background_color, "  ".into())
             }
             Cell::Measured(index, _) => {
                 let (foreground_color, content) = if let Some(index) = index {
-                    (Some(Color::Black), format!("{:>2}", index).into())
+                    // Only the last two digits fit into a cell
+                    (Some(Color::Black), format!("{:>2}", index % 100).into())
                 } else {
                     (None, "  ".into())
                 };
@@ -109,6 +128,30 @@ impl Cell {
 
         draw(terminal, foreground_color, background_color, content);
     }
+
+    /// Draws the cell with brackets on it, marking it as the cell of the keyboard cursor.
+    pub fn draw_cursor(&self, terminal: &mut Terminal, point: Point, palette: Palette) {
+        if palette.has_colors() {
+            let background_color = if let Cell::Empty = self {
+                palette.get_empty_cell_color(point, false)
+            } else {
+                palette.get_cell_color(*self, false)
+            };
+
+            // The brackets need to stand out on both bright and dark cells
+            let (red, green, blue) = super::export::get_rgb(background_color);
+            let foreground_color = if red as u16 + green as u16 + blue as u16 > 3 * 128 {
+                Color::Black
+            } else {
+                Color::White
+            };
+
+            terminal.set_background_color(background_color);
+            terminal.set_foreground_color(foreground_color);
+        }
+
+        terminal.write("[]");
+    }
 }
 
 #[derive(Default)]
@@ -116,19 +159,171 @@ pub struct CellPlacement {
     pub cell: Option<Cell>,
     /// The time of when the first cell was placed.
     pub starting_time: Option<Instant>,
+    /// The point of the keyboard cursor, which the arrow keys move and which Q, W, E and Tab place cells at.
+    ///
+    /// The mouse doesn't move it so that the keyboard position isn't lost when the mouse is touched.
     pub selected_cell_point: Option<Point>,
-    pub measurement_point: Option<Point>,
+    /// The point of the cell under the mouse, if it is on the grid.
+    pub hovered_cell_point: Option<Point>,
+    /// The waypoints of the measurement in progress, through which the measured line goes.
+    pub measurement_points: Vec<Point>,
     /// Whether the next cell placement will flood-fill.
     pub fill: bool,
+    /// The cells that the next flood-fill fills, no matter what cell it starts at.
+    ///
+    /// If this is `None`, the flood-fill fills the cells that are the same as the one it starts at.
+    pub fill_target: Option<Cell>,
+    /// Whether measurements are several cells thick, for measuring areas.
+    pub thick_measurement: bool,
+    /// Whether flood-filled cells appear ring by ring instead of all at once.
+    pub animate_fill: bool,
+    /// Whether dragging with filled cells overwrites maybed and crossed out cells.
+    pub drag_overwrite: bool,
+    /// Whether placed cells that contradict the solution are marked.
+    pub show_mistakes: bool,
+    /// Whether the empty cells of rows and columns that a placement solves are crossed out.
+    pub auto_cross: bool,
+    /// Whether the terminal cursor is shown on the selected cell.
+    pub show_cursor: bool,
+    /// The cell that the next placement places as it is, without toggling.
+    pub pending_cell: Option<Cell>,
+    /// Whether the mouse erases cells instead of placing the cell of its button.
+    pub erase: bool,
+    /// Whether the mouse places cells in the rectangle spanned by pressing and releasing it.
+    pub rect_fill_mode: bool,
+    /// The cell point where the rectangle of the rectangle fill starts.
+    pub rect_start: Option<Point>,
+    /// The time of the last mouse press, used to detect double-clicks.
+    pub last_click_time: Option<Instant>,
+    /// The cursor point of the last mouse press, used to detect double-clicks.
+    pub last_click_point: Option<Point>,
 }
 
-pub const fn get_cell_point_from_cursor_point(cursor_point: Point, builder: &Builder) -> Point {
-    Point {
-        x: (cursor_point.x - builder.point.x) / 2,
-        y: cursor_point.y - builder.point.y,
+/// How long after a mouse press another press at the same point counts as a double-click.
+const DOUBLE_CLICK_DELAY: Duration = Duration::from_millis(300);
+
+/// How many cells away from the center of a row or column a double-click may be to fill it.
+const DOUBLE_CLICK_CENTER_DISTANCE: u16 = 2;
+
+/// A whole row or column of the grid.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Line {
+    Row,
+    Column,
+}
+
+impl Line {
+    /// Gets the line that a double-click on the cell point fills, if any.
+    ///
+    /// A double-click near the horizontal center fills the row and one near the vertical center fills the column.
+    pub fn from_double_click(cell_point: Point, size: Size) -> Option<Self> {
+        // Doubled so that the center of lines with an even length is a whole number
+        let is_near_center = |index: u16, len: u16| {
+            (index * 2).abs_diff(len - 1) <= DOUBLE_CLICK_CENTER_DISTANCE * 2
+        };
+
+        if is_near_center(cell_point.x, size.width) {
+            Some(Self::Row)
+        } else if is_near_center(cell_point.y, size.height) {
+            Some(Self::Column)
+        } else {
+            None
+        }
+    }
+
+    /// Gets all cell points of this line that goes through the cell point.
+    pub fn get_points(&self, cell_point: Point, size: Size) -> Vec<Point> {
+        match self {
+            Self::Row => (0..size.width).map(|x| Point { x, ..cell_point }).collect(),
+            Self::Column => (0..size.height)
+                .map(|y| Point { y, ..cell_point })
+                .collect(),
+        }
+    }
+}
+
+/// Converts the cursor point to the point of the cell under it, if the cursor is on the grid.
+pub fn get_cell_point_from_cursor_point(cursor_point: Point, builder: &Builder) -> Option<Point> {
+    builder.screen_to_cell(cursor_point)
+}
+
+/// Cancels pending tool state when the editor is toggled so that nothing that was set up in one mode
+/// executes with the semantics of the other mode.
+///
+/// Returns a message describing what was canceled, if anything.
+pub fn on_mode_change(
+    cell_placement: &mut CellPlacement,
+    builder: &Builder,
+) -> Option<&'static str> {
+    // Terminate any active stroke. The cells placed so far are already in the undo-redo buffer.
+    cell_placement.cell = None;
+    // Likewise, a rectangle that is still being spanned is dropped.
+    cell_placement.rect_start = None;
+
+    // The grid might have moved so the selected and hovered cell points might not point to a cell anymore.
+    for point in [
+        &mut cell_placement.selected_cell_point,
+        &mut cell_placement.hovered_cell_point,
+    ] {
+        if point.is_some_and(|point| builder.screen_to_cell(point).is_none()) {
+            *point = None;
+        }
+    }
+
+    let fill = cell_placement.fill;
+    cell_placement.fill = false;
+    cell_placement.fill_target = None;
+
+    let measurement = !cell_placement.measurement_points.is_empty();
+    cell_placement.measurement_points.clear();
+
+    match (fill, measurement) {
+        (true, true) => Some("fill and measurement canceled"),
+        (true, false) => Some("fill canceled"),
+        (false, true) => Some("measurement canceled"),
+        (false, false) => None,
     }
 }
 
+/// How many cells thick the lines of a thick measurement are.
+const THICK_MEASUREMENT_THICKNESS: u16 = 3;
+
+/// Gets the points of the lines from each waypoint to the next, without repeating the waypoints where the lines meet.
+///
+/// If `thickness` is more than 1, the lines are as many cells thick, clipped to the bounds, and no point is repeated.
+pub fn get_polyline_points(waypoints: &[Point], thickness: u16, bounds: Size) -> Vec<Point> {
+    let mut points = Vec::<Point>::new();
+
+    if thickness > 1 {
+        let segments: Vec<(Point, Point)> = match waypoints {
+            [waypoint] => vec![(*waypoint, *waypoint)],
+            _ => waypoints
+                .windows(2)
+                .map(|segment| (segment[0], segment[1]))
+                .collect(),
+        };
+        let mut seen_points = HashSet::<Point>::new();
+        for (start_point, end_point) in segments {
+            for point in util::get_thick_line_points(start_point, end_point, thickness, bounds) {
+                if seen_points.insert(point) {
+                    points.push(point);
+                }
+            }
+        }
+
+        return points;
+    }
+
+    if let [first_waypoint, ..] = waypoints {
+        points.push(*first_waypoint);
+    }
+    for segment in waypoints.windows(2) {
+        points.extend(util::get_line_points(segment[0], segment[1]).skip(1));
+    }
+
+    points
+}
+
 pub fn set_measured_cells(grid: &mut Grid, line_points: &[Point]) {
     let measurement_counter = grid.measurement_counter;
     for (index, point) in line_points.iter().enumerate() {
@@ -153,58 +348,236 @@ pub fn set_measured_cells(grid: &mut Grid, line_points: &[Point]) {
     grid.measurement_counter += 1;
 }
 
+/// Highlights the row and the column of the hovered cell. Nothing is highlighted if the point is not on the grid.
 pub fn draw_highlighted_cells(
     terminal: &mut Terminal,
     builder: &Builder,
     hovered_cell_point: Point,
 ) {
-    fn highlight_cell(terminal: &mut Terminal, mut cursor_point: Point, builder: &Builder) {
-        if (cursor_point.x - builder.point.x) % 2 != 0 {
-            cursor_point.x -= 1;
-        }
-        terminal.set_cursor(cursor_point);
-        let cell_point = get_cell_point_from_cursor_point(cursor_point, builder);
-        let cell = builder.grid.get_cell(cell_point);
-        cell.draw(terminal, cell_point, true);
-    }
+    let hovered_cell_point = if let Some(cell_point) = builder.screen_to_cell(hovered_cell_point) {
+        cell_point
+    } else {
+        return;
+    };
 
-    // From the left of the grid to the pointer
-    for x in builder.point.x..=hovered_cell_point.x - 2 {
-        let point = Point {
+    let row = (0..builder.grid.size.width)
+        .filter(|x| *x != hovered_cell_point.x)
+        .map(|x| Point {
             x,
             ..hovered_cell_point
-        };
-        highlight_cell(terminal, point, builder);
-    }
-    // From the pointer to the right of the grid
-    for x in hovered_cell_point.x + 2..builder.point.x + builder.grid.size.width * 2 {
-        let point = Point {
-            x,
-            ..hovered_cell_point
-        };
-        highlight_cell(terminal, point, builder);
-    }
-    // From the top of the grid to the pointer
-    for y in builder.point.y..hovered_cell_point.y {
-        let point = Point {
+        });
+    let column = (0..builder.grid.size.height)
+        .filter(|y| *y != hovered_cell_point.y)
+        .map(|y| Point {
             y,
             ..hovered_cell_point
-        };
-        highlight_cell(terminal, point, builder);
+        });
+    for cell_point in row.chain(column) {
+        terminal.set_cursor(builder.get_cell_screen_rect(cell_point).0);
+        let cell = builder.grid.get_cell(cell_point);
+        cell.draw(terminal, cell_point, true, builder.palette);
     }
-    // From the pointer to the bottom of the grid
-    for y in hovered_cell_point.y + 1..builder.point.y + builder.grid.size.height {
-        let point = Point {
-            y,
-            ..hovered_cell_point
-        };
-        highlight_cell(terminal, point, builder);
+
+    terminal.reset_colors();
+
+    crate::draw_hover_status(terminal, builder, hovered_cell_point);
+    crate::draw_compact_clues(terminal, builder, hovered_cell_point);
+}
+
+/// Marks the cell of the keyboard cursor, if there is one, so that it can be told apart from the highlighted cells of the mouse.
+pub fn draw_keyboard_cursor(
+    terminal: &mut Terminal,
+    builder: &Builder,
+    cell_placement: &CellPlacement,
+) {
+    let cell_point = cell_placement
+        .selected_cell_point
+        .and_then(|selected_cell_point| builder.screen_to_cell(selected_cell_point));
+
+    if let Some(cell_point) = cell_point {
+        terminal.set_cursor(builder.get_cell_screen_rect(cell_point).0);
+        builder
+            .grid
+            .get_cell(cell_point)
+            .draw_cursor(terminal, cell_point, builder.palette);
+        terminal.reset_colors();
+    }
+}
+
+/// Moves the terminal cursor onto the selected cell and shows it, or hides it if there is no selected cell.
+///
+/// Drawing moves the terminal cursor all over the screen so this has to be done right before each flush.
+pub fn place_terminal_cursor(
+    terminal: &mut Terminal,
+    builder: &Builder,
+    cell_placement: &CellPlacement,
+) {
+    if !cell_placement.show_cursor {
+        return;
     }
 
+    let cell_point = cell_placement
+        .selected_cell_point
+        .and_then(|selected_cell_point| builder.screen_to_cell(selected_cell_point));
+
+    if let Some(cell_point) = cell_point {
+        terminal.set_cursor(builder.get_cell_screen_rect(cell_point).0);
+        terminal.show_cursor();
+    } else {
+        terminal.hide_cursor();
+    }
+}
+
+/// Converts the cursor point to a cell point, moving it onto the grid if it is outside.
+pub fn get_clamped_cell_point_from_cursor_point(cursor_point: Point, builder: &Builder) -> Point {
+    Point {
+        x: (cursor_point.x.saturating_sub(builder.point.x) / 2).min(builder.grid.size.width - 1),
+        y: cursor_point
+            .y
+            .saturating_sub(builder.point.y)
+            .min(builder.grid.size.height - 1),
+    }
+}
+
+/// Gets all cell points within the rectangle spanned by the two corners, row by row.
+pub fn get_rect_points(first_corner: Point, second_corner: Point) -> impl Iterator<Item = Point> {
+    let (left, right) = (
+        first_corner.x.min(second_corner.x),
+        first_corner.x.max(second_corner.x),
+    );
+    let (top, bottom) = (
+        first_corner.y.min(second_corner.y),
+        first_corner.y.max(second_corner.y),
+    );
+
+    (top..=bottom).flat_map(move |y| (left..=right).map(move |x| Point { x, y }))
+}
+
+/// How long each ring of the flood fill animation is shown.
+const FILL_ANIMATION_FRAME_DELAY: Duration = Duration::from_millis(20);
+
+/// Draws the flood-filled cells ring by ring. Pressing any key skips the rest of the animation.
+fn animate_fill(terminal: &mut Terminal, builder: &Builder, rings: &[Vec<Point>]) {
+    for ring in rings {
+        for cell_point in ring {
+            terminal.set_cursor(builder.get_cell_screen_rect(*cell_point).0);
+            builder
+                .grid
+                .get_cell(*cell_point)
+                .draw(terminal, *cell_point, false, builder.palette);
+        }
+        terminal.reset_colors();
+        terminal.flush();
+
+        if let Some(Event::Key(_)) = terminal.poll_event(FILL_ANIMATION_FRAME_DELAY) {
+            break;
+        }
+    }
+}
+
+/// Marks those of the cells that are mistakes until the grid is drawn again, in a color for each kind of mistake.
+///
+/// The bell rings once if there is any mistake.
+fn draw_mistakes(terminal: &mut Terminal, builder: &Builder, cell_points: &[Point]) {
+    let mut any_mistake = false;
+    for cell_point in cell_points {
+        let mistake = if let Some(mistake) = builder.grid.get_mistake(*cell_point) {
+            mistake
+        } else {
+            continue;
+        };
+        any_mistake = true;
+
+        terminal.set_cursor(builder.get_cell_screen_rect(*cell_point).0);
+        if builder.palette.has_colors() {
+            terminal.set_foreground_color(Color::Black);
+            terminal.set_background_color(builder.palette.get_mistake_color(mistake));
+            terminal.write("!!");
+        } else {
+            terminal.write(match mistake {
+                Mistake::WrongFill => "#!",
+                Mistake::WrongCross => "x!",
+            });
+        }
+    }
     terminal.reset_colors();
+
+    if any_mistake {
+        builder.bell.ring(terminal);
+    }
+}
+
+/// Places multiple cells at once as a single operation and draws the grid again.
+///
+/// Returns the state to continue with if the grid was solved or the clues need more space.
+fn place_cells(
+    terminal: &mut Terminal,
+    builder: &mut Builder,
+    cells: Vec<(Point, Cell)>,
+    starting_time: &Instant,
+    editor_toggled: bool,
+) -> Option<State> {
+    for (cell_point, cell) in &cells {
+        *builder.grid.get_mut_cell(*cell_point) = *cell;
+    }
+
+    let mut clues_grew = false;
+    if editor_toggled {
+        for (cell_point, _) in &cells {
+            clues_grew |= builder.rebuild_clues(terminal, *cell_point);
+        }
+    }
+
+    builder.grid.undo_redo_buffer.push_cells(cells, false);
+
+    if clues_grew {
+        // The clues need more space now
+        return Some(State::Relayout);
+    }
+
+    let all_clues_solved = builder.draw_all(terminal);
+
+    // The grid shouldn't be solved while editing it
+    if all_clues_solved && !editor_toggled {
+        return Some(State::Solved(starting_time.elapsed()));
+    }
+
+    builder.refresh_difficulty_label(terminal, editor_toggled);
+
+    None
 }
 
 impl CellPlacement {
+    /// Gets the point that keys place cells at: the keyboard cursor or, if no cell was selected with the keyboard yet, the hovered cell.
+    pub fn get_key_point(&self) -> Option<Point> {
+        self.selected_cell_point.or(self.hovered_cell_point)
+    }
+
+    /// Marks those of the placed cells that contradict the solution if mistakes are shown, which they aren't while editing.
+    fn draw_placed_mistakes(
+        &self,
+        terminal: &mut Terminal,
+        builder: &Builder,
+        cell_points: &[Point],
+        editor_toggled: bool,
+    ) {
+        if self.show_mistakes && !editor_toggled {
+            draw_mistakes(terminal, builder, cell_points);
+        }
+    }
+
+    /// Gets the time of when the first cell was placed, starting the timer if this is the first placement.
+    ///
+    /// Placements in the editor don't start the timer because the grid is only being made.
+    /// For them, the current time is returned.
+    fn start_timer(&mut self, editor_toggled: bool) -> Instant {
+        if editor_toggled {
+            Instant::now()
+        } else {
+            *self.starting_time.get_or_insert_with(Instant::now)
+        }
+    }
+
     pub fn place(
         &mut self,
         terminal: &mut Terminal,
@@ -213,14 +586,34 @@ impl CellPlacement {
         mut cell_to_place: Cell,
         editor_toggled: bool,
     ) -> State {
-        let starting_time = self.starting_time.get_or_insert(Instant::now());
+        let pending_cell = self.pending_cell.take();
+        if let Some(pending_cell) = pending_cell {
+            crate::clear_pending_cell(terminal);
+
+            // The pending cell starts a new stroke
+            cell_to_place = pending_cell;
+            self.cell = None;
+        }
+
+        let cell_point = if let Some(cell_point) =
+            get_cell_point_from_cursor_point(selected_cell_point, builder)
+        {
+            cell_point
+        } else {
+            return State::Continue;
+        };
 
-        let cell_point = get_cell_point_from_cursor_point(selected_cell_point, builder);
+        let starting_time = self.start_timer(editor_toggled);
 
         let grid_cell = builder.grid.get_mut_cell(cell_point);
 
         *grid_cell = if let Some(cell) = self.cell {
-            if *grid_cell == cell {
+            // Dragging with filled cells keeps the marks of maybed and crossed out cells unless they may be overwritten
+            let keeps_mark = cell == Cell::Filled
+                && matches!(*grid_cell, Cell::Maybed | Cell::Crossed)
+                && !self.drag_overwrite;
+
+            if *grid_cell == cell || keeps_mark {
                 builder.draw_grid(terminal);
 
                 // We know that this point is hovered
@@ -231,7 +624,7 @@ impl CellPlacement {
 
             cell
         } else {
-            if *grid_cell == cell_to_place {
+            if *grid_cell == cell_to_place && pending_cell.is_none() {
                 cell_to_place = Cell::default();
             }
             self.cell = Some(cell_to_place);
@@ -239,24 +632,52 @@ impl CellPlacement {
             if self.fill {
                 let cell = *grid_cell;
 
-                super::tools::fill::fill(&mut builder.grid, cell_point, cell, cell_to_place);
+                let (rings, operation) = if let Some(target_cell) = self.fill_target {
+                    (
+                        super::tools::fill::fill_target(
+                            &mut builder.grid,
+                            cell_point,
+                            target_cell,
+                            cell_to_place,
+                        ),
+                        undo_redo_buffer::Operation::FillTarget {
+                            point: cell_point,
+                            target_cell,
+                            fill_cell: cell_to_place,
+                        },
+                    )
+                } else {
+                    (
+                        super::tools::fill::fill(
+                            &mut builder.grid,
+                            cell_point,
+                            cell,
+                            cell_to_place,
+                        ),
+                        undo_redo_buffer::Operation::Fill {
+                            point: cell_point,
+                            first_cell: cell,
+                            fill_cell: cell_to_place,
+                        },
+                    )
+                };
 
-                builder
-                    .grid
-                    .undo_redo_buffer
-                    .push(undo_redo_buffer::Operation::Fill {
-                        point: cell_point,
-                        first_cell: cell,
-                        fill_cell: cell_to_place,
-                    });
+                builder.grid.undo_redo_buffer.push(operation);
 
                 self.fill = false;
+                self.fill_target = None;
+
+                if self.animate_fill {
+                    animate_fill(terminal, builder, &rings);
+                }
 
                 let all_clues_solved = builder.draw_all(terminal);
 
-                if all_clues_solved {
+                // The grid shouldn't be solved while editing it
+                if all_clues_solved && !editor_toggled {
                     return State::Solved(starting_time.elapsed());
                 } else {
+                    builder.refresh_difficulty_label(terminal, editor_toggled);
                     return State::ClearAlert;
                 }
             }
@@ -265,52 +686,300 @@ impl CellPlacement {
         };
         let cell = *grid_cell;
 
-        builder
-            .grid
-            .undo_redo_buffer
-            .push(undo_redo_buffer::Operation::SetCell {
-                point: cell_point,
-                cell,
-            });
+        // Erasing a cell of a solved line shouldn't cross it out right away
+        let auto_crossed_cells = if self.auto_cross && !editor_toggled && cell != Cell::Empty {
+            super::tools::auto_cross::auto_cross(&mut builder.grid, cell_point)
+        } else {
+            Vec::new()
+        };
+
+        if auto_crossed_cells.is_empty() {
+            builder
+                .grid
+                .undo_redo_buffer
+                .push(undo_redo_buffer::Operation::SetCell {
+                    point: cell_point,
+                    cell,
+                });
+        } else {
+            // The cell and the cells crossed out because of it are undone in one step
+            let mut changes = vec![(cell_point, cell)];
+            changes.extend(&auto_crossed_cells);
+            builder.grid.undo_redo_buffer.push_cells(changes, false);
+        }
 
         if editor_toggled {
-            builder.rebuild_clues(terminal, cell_point);
+            if builder.rebuild_clues(terminal, cell_point) {
+                // The clues need more space now
+                return State::Relayout;
+            }
 
             // The grid shouldn't be solved while editing it
             #[allow(unused_must_use)]
             {
                 builder.draw_all(terminal);
             }
+
+            // The clues are changing
+            builder.clear_difficulty_label(terminal);
         } else {
             let all_clues_solved = builder.draw_all(terminal);
 
             if all_clues_solved {
                 return State::Solved(starting_time.elapsed());
             }
+
+            builder.update_difficulty_label(terminal);
         }
 
         // We know that this point is hovered
         draw_highlighted_cells(terminal, builder, selected_cell_point);
 
+        let placed_points: Vec<Point> = std::iter::once(cell_point)
+            .chain(auto_crossed_cells.iter().map(|(point, _)| *point))
+            .collect();
+        self.draw_placed_mistakes(terminal, builder, &placed_points, editor_toggled);
+
         State::Continue
     }
 
+    /// Starts spanning a rectangle at the cursor point, to be filled with the given cell or the pending cell.
+    pub fn start_rect(
+        &mut self,
+        terminal: &mut Terminal,
+        builder: &mut Builder,
+        selected_cell_point: Point,
+        cell_to_place: Cell,
+    ) {
+        let rect_start = if let Some(cell_point) =
+            get_cell_point_from_cursor_point(selected_cell_point, builder)
+        {
+            cell_point
+        } else {
+            return;
+        };
+
+        let cell_to_place = if let Some(pending_cell) = self.pending_cell.take() {
+            crate::clear_pending_cell(terminal);
+            pending_cell
+        } else {
+            cell_to_place
+        };
+        self.cell = Some(cell_to_place);
+        self.rect_start = Some(rect_start);
+
+        self.preview_rect(terminal, builder, selected_cell_point);
+    }
+
+    /// Draws the outline of the rectangle from its start to the cursor point over the grid.
+    pub fn preview_rect(
+        &mut self,
+        terminal: &mut Terminal,
+        builder: &mut Builder,
+        selected_cell_point: Point,
+    ) {
+        // Restore the previous preview
+        builder.draw_grid(terminal);
+
+        if let (Some(rect_start), Some(cell)) = (self.rect_start, self.cell) {
+            let rect_end = get_clamped_cell_point_from_cursor_point(selected_cell_point, builder);
+
+            for cell_point in get_rect_points(rect_start, rect_end) {
+                let on_outline = cell_point.x == rect_start.x
+                    || cell_point.x == rect_end.x
+                    || cell_point.y == rect_start.y
+                    || cell_point.y == rect_end.y;
+                if on_outline {
+                    terminal.set_cursor(builder.get_cell_screen_rect(cell_point).0);
+                    cell.draw(terminal, cell_point, true, builder.palette);
+                }
+            }
+            terminal.reset_colors();
+        }
+    }
+
+    /// Places the cell in all cells of the rectangle from its start to the cursor point.
+    pub fn place_rect(
+        &mut self,
+        terminal: &mut Terminal,
+        builder: &mut Builder,
+        selected_cell_point: Point,
+    ) -> State {
+        let (rect_start, cell) = match (self.rect_start.take(), self.cell.take()) {
+            (Some(rect_start), Some(cell)) => (rect_start, cell),
+            _ => return State::Continue,
+        };
+        let starting_time = self.start_timer(false);
+
+        let rect_end = get_clamped_cell_point_from_cursor_point(selected_cell_point, builder);
+        let cell_points: Vec<Point> = get_rect_points(rect_start, rect_end).collect();
+        let cells: Vec<(Point, Cell)> = cell_points
+            .iter()
+            .map(|cell_point| (*cell_point, cell))
+            .collect();
+
+        if let Some(state) = place_cells(terminal, builder, cells, &starting_time, false) {
+            return state;
+        }
+
+        self.draw_placed_mistakes(terminal, builder, &cell_points, false);
+
+        State::ClearAlert
+    }
+
+    /// Registers a mouse press at the cursor point and returns whether it completes a double-click.
+    pub fn register_click(&mut self, selected_cell_point: Point, now: Instant) -> bool {
+        let double_click = self.last_click_point == Some(selected_cell_point)
+            && self.last_click_time.is_some_and(|last_click_time| {
+                now.saturating_duration_since(last_click_time) <= DOUBLE_CLICK_DELAY
+            });
+
+        if double_click {
+            // A third click starts over
+            self.last_click_time = None;
+            self.last_click_point = None;
+        } else {
+            self.last_click_time = Some(now);
+            self.last_click_point = Some(selected_cell_point);
+        }
+
+        double_click
+    }
+
+    /// Places the cell in the whole row or column of the cursor point, depending on where the double-click was.
+    pub fn place_line(
+        &mut self,
+        terminal: &mut Terminal,
+        builder: &mut Builder,
+        selected_cell_point: Point,
+        cell_to_place: Cell,
+        editor_toggled: bool,
+    ) -> State {
+        let cell_point = if let Some(cell_point) =
+            get_cell_point_from_cursor_point(selected_cell_point, builder)
+        {
+            cell_point
+        } else {
+            return State::Continue;
+        };
+        let line = if let Some(line) = Line::from_double_click(cell_point, builder.grid.size) {
+            line
+        } else {
+            return State::Continue;
+        };
+
+        // The double-click ends the stroke of its first click
+        self.cell = None;
+
+        self.fill_line(
+            terminal,
+            builder,
+            selected_cell_point,
+            line,
+            cell_to_place,
+            editor_toggled,
+        )
+    }
+
+    /// Places the cell in the whole row or column of the cursor point as one undoable operation.
+    pub fn fill_line(
+        &mut self,
+        terminal: &mut Terminal,
+        builder: &mut Builder,
+        selected_cell_point: Point,
+        line: Line,
+        cell_to_place: Cell,
+        editor_toggled: bool,
+    ) -> State {
+        let cell_point = if let Some(cell_point) =
+            get_cell_point_from_cursor_point(selected_cell_point, builder)
+        {
+            cell_point
+        } else {
+            return State::Continue;
+        };
+
+        let starting_time = self.start_timer(editor_toggled);
+
+        let cells =
+            super::tools::fill::fill_line(&mut builder.grid, cell_point, &line, cell_to_place);
+        if cells.is_empty() {
+            return match line {
+                Line::Row => State::Alert("Row is already filled".into(), AlertSeverity::Info),
+                Line::Column => {
+                    State::Alert("Column is already filled".into(), AlertSeverity::Info)
+                }
+            };
+        }
+
+        let cell_points: Vec<Point> = cells.iter().map(|(cell_point, _)| *cell_point).collect();
+
+        if let Some(state) = place_cells(terminal, builder, cells, &starting_time, editor_toggled) {
+            return state;
+        }
+
+        // We know that this point is hovered
+        draw_highlighted_cells(terminal, builder, selected_cell_point);
+
+        self.draw_placed_mistakes(terminal, builder, &cell_points, editor_toggled);
+
+        match line {
+            Line::Row => State::Alert("Row filled".into(), AlertSeverity::Info),
+            Line::Column => State::Alert("Column filled".into(), AlertSeverity::Info),
+        }
+    }
+
+    /// Starts a measurement at the selected cell or finishes it there, measuring the line through all waypoints.
     pub fn place_measured_cells(
         &mut self,
         terminal: &mut Terminal,
         builder: &mut Builder,
     ) -> State {
         if let Some(selected_cell_point) = self.selected_cell_point {
-            if let Some(measurement_point) = self.measurement_point {
+            if self.measurement_points.is_empty() {
+                self.measurement_points.push(selected_cell_point);
+
+                if builder.mouse {
+                    State::Alert(
+                        "Click to add waypoints; press X to set last measurement point".into(),
+                        AlertSeverity::Info,
+                    )
+                } else {
+                    State::Alert(
+                        "Press X to set last measurement point".into(),
+                        AlertSeverity::Info,
+                    )
+                }
+            } else {
+                if self.measurement_points.last() != Some(&selected_cell_point) {
+                    self.measurement_points.push(selected_cell_point);
+                }
+
                 // The points we have are screen points so now we convert them to values that we can use
                 // to index the grid.
-                let start_point =
-                    super::get_cell_point_from_cursor_point(measurement_point, builder);
-                let end_point =
-                    super::get_cell_point_from_cursor_point(selected_cell_point, builder);
+                // The grid might have moved since a point was set so that it is not on the grid anymore.
+                let waypoints: Vec<Point> = if let Some(waypoints) = self
+                    .measurement_points
+                    .drain(..)
+                    .map(|measurement_point| {
+                        super::get_cell_point_from_cursor_point(measurement_point, builder)
+                    })
+                    .collect()
+                {
+                    waypoints
+                } else {
+                    return State::Alert(
+                        "Measurement canceled because a point was outside of the grid".into(),
+                        AlertSeverity::Warning,
+                    );
+                };
 
-                let line_points: Vec<Point> =
-                    util::get_line_points(start_point, end_point).collect();
+                let thickness = if self.thick_measurement {
+                    THICK_MEASUREMENT_THICKNESS
+                } else {
+                    1
+                };
+                let line_points = get_polyline_points(&waypoints, thickness, builder.grid.size);
 
                 set_measured_cells(&mut builder.grid, &line_points);
 
@@ -325,16 +994,587 @@ impl CellPlacement {
                 // We know that this point is hovered
                 super::draw_highlighted_cells(terminal, builder, selected_cell_point);
 
-                self.measurement_point = None;
-
                 State::ClearAlert
-            } else {
-                self.measurement_point = Some(selected_cell_point);
-
-                State::Alert("Press X to set second measurement point".into())
             }
         } else {
             State::Continue
         }
     }
+
+    /// Adds a waypoint to the measurement in progress.
+    pub fn add_measurement_waypoint(
+        &mut self,
+        terminal: &mut Terminal,
+        builder: &Builder,
+        selected_cell_point: Point,
+    ) -> State {
+        if self.measurement_points.last() != Some(&selected_cell_point) {
+            self.measurement_points.push(selected_cell_point);
+        }
+
+        // Preview the waypoints
+        for measurement_point in &self.measurement_points {
+            let cell_point = if let Some(cell_point) =
+                get_cell_point_from_cursor_point(*measurement_point, builder)
+            {
+                cell_point
+            } else {
+                continue;
+            };
+            terminal.set_cursor(builder.get_cell_screen_rect(cell_point).0);
+            Cell::Measured(None, None).draw(terminal, cell_point, true, builder.palette);
+        }
+        terminal.reset_colors();
+
+        State::Alert(
+            format!(
+                "{} measurement points set; press X to set the last one",
+                self.measurement_points.len()
+            )
+            .into(),
+            AlertSeverity::Info,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io;
+    use terminal::util::Size;
+
+    fn get_builder() -> Builder {
+        Builder::for_test(
+            Size {
+                width: 10,
+                height: 5,
+            },
+            Point { x: 20, y: 10 },
+        )
+    }
+
+    #[test]
+    fn test_get_monochrome_content() {
+        assert_eq!(Cell::Filled.get_monochrome_content(false), "##");
+        assert_eq!(Cell::Crossed.get_monochrome_content(true), "xx");
+        assert_eq!(Cell::Empty.get_monochrome_content(true), "::");
+        assert_eq!(
+            Cell::Measured(Some(123), None).get_monochrome_content(false),
+            "23"
+        );
+    }
+
+    #[test]
+    fn test_place_pending_cell() {
+        let stdout = io::stdout();
+        let mut terminal = Terminal::new(stdout.lock()).unwrap();
+        let mut builder = get_builder();
+        let point = builder.point;
+        let cell_point = Point { x: 0, y: 0 };
+        *builder.grid.get_mut_cell(cell_point) = Cell::Filled;
+
+        // Placing the same cell again without a pending cell removes it
+        let mut cell_placement = CellPlacement::default();
+        let _ = cell_placement.place(&mut terminal, &mut builder, point, Cell::Filled, false);
+        assert_eq!(builder.grid.get_cell(cell_point), Cell::Empty);
+
+        // The pending cell is placed regardless of the previous cell and the cell to place
+        for previous_cell in [Cell::Empty, Cell::Filled, Cell::Crossed] {
+            *builder.grid.get_mut_cell(cell_point) = previous_cell;
+            let mut cell_placement = CellPlacement {
+                pending_cell: Some(Cell::Filled),
+                ..Default::default()
+            };
+            let _ = cell_placement.place(&mut terminal, &mut builder, point, Cell::Filled, false);
+            assert_eq!(builder.grid.get_cell(cell_point), Cell::Filled);
+            assert_eq!(cell_placement.pending_cell, None);
+        }
+    }
+
+    #[test]
+    fn test_place_in_editor_does_not_start_timer() {
+        let stdout = io::stdout();
+        let mut terminal = Terminal::new(stdout.lock()).unwrap();
+        let mut builder = get_builder();
+        let point = builder.point;
+
+        let mut cell_placement = CellPlacement::default();
+        let _ = cell_placement.place(&mut terminal, &mut builder, point, Cell::Filled, true);
+        assert_eq!(builder.grid.get_cell(Point { x: 0, y: 0 }), Cell::Filled);
+        assert_eq!(cell_placement.starting_time, None);
+
+        cell_placement.cell = None;
+        let _ = cell_placement.place(&mut terminal, &mut builder, point, Cell::Crossed, false);
+        assert!(cell_placement.starting_time.is_some());
+    }
+
+    #[test]
+    fn test_place_erase_stroke() {
+        let stdout = io::stdout();
+        let mut terminal = Terminal::new(stdout.lock()).unwrap();
+        let mut builder = get_builder();
+        let point = builder.point;
+        *builder.grid.get_mut_cell(Point { x: 0, y: 0 }) = Cell::Filled;
+        *builder.grid.get_mut_cell(Point { x: 2, y: 0 }) = Cell::Crossed;
+
+        // Unlike other cells, empty cells never toggle, so a stroke starting on an empty cell still erases
+        let mut cell_placement = CellPlacement {
+            erase: true,
+            ..Default::default()
+        };
+        for x in 0..3 {
+            let selected_cell_point = Point {
+                x: point.x + x * 2,
+                ..point
+            };
+            let _ = cell_placement.place(
+                &mut terminal,
+                &mut builder,
+                selected_cell_point,
+                Cell::Empty,
+                false,
+            );
+        }
+
+        assert!(builder.grid.cells.iter().all(|cell| *cell == Cell::Empty));
+    }
+
+    #[test]
+    fn test_place_drag() {
+        let stdout = io::stdout();
+        let mut terminal = Terminal::new(stdout.lock()).unwrap();
+
+        for drag_overwrite in [false, true] {
+            let mut builder = get_builder();
+            let point = builder.point;
+            *builder.grid.get_mut_cell(Point { x: 1, y: 0 }) = Cell::Filled;
+            *builder.grid.get_mut_cell(Point { x: 2, y: 0 }) = Cell::Maybed;
+            *builder.grid.get_mut_cell(Point { x: 3, y: 0 }) = Cell::Crossed;
+
+            let mut cell_placement = CellPlacement {
+                drag_overwrite,
+                ..Default::default()
+            };
+            for x in 0..5 {
+                let selected_cell_point = Point {
+                    x: point.x + x * 2,
+                    ..point
+                };
+                let _ = cell_placement.place(
+                    &mut terminal,
+                    &mut builder,
+                    selected_cell_point,
+                    Cell::Filled,
+                    false,
+                );
+            }
+
+            // Only the cells that changed are recorded; not the one that was already filled
+            let placed_xs: Vec<u16> = builder
+                .grid
+                .undo_redo_buffer
+                .buffer
+                .iter()
+                .map(|operation| match operation {
+                    undo_redo_buffer::Operation::SetCell {
+                        point,
+                        cell: Cell::Filled,
+                    } => point.x,
+                    _ => unreachable!(),
+                })
+                .collect();
+            let row = &builder.grid.cells[..5];
+            if drag_overwrite {
+                assert_eq!(placed_xs, [0, 2, 3, 4]);
+                assert_eq!(row, [Cell::Filled; 5]);
+            } else {
+                assert_eq!(placed_xs, [0, 4]);
+                assert_eq!(
+                    row,
+                    [
+                        Cell::Filled,
+                        Cell::Filled,
+                        Cell::Maybed,
+                        Cell::Crossed,
+                        Cell::Filled
+                    ]
+                );
+            }
+        }
+
+        // Erasing still clears the marks
+        let mut builder = get_builder();
+        let point = builder.point;
+        *builder.grid.get_mut_cell(Point { x: 0, y: 0 }) = Cell::Filled;
+        *builder.grid.get_mut_cell(Point { x: 1, y: 0 }) = Cell::Crossed;
+        let mut cell_placement = CellPlacement::default();
+        for x in 0..2 {
+            let selected_cell_point = Point {
+                x: point.x + x * 2,
+                ..point
+            };
+            let _ = cell_placement.place(
+                &mut terminal,
+                &mut builder,
+                selected_cell_point,
+                Cell::Filled,
+                false,
+            );
+        }
+        assert_eq!(&builder.grid.cells[..2], [Cell::Empty; 2]);
+        assert_eq!(builder.grid.undo_redo_buffer.buffer.len(), 2);
+    }
+
+    #[test]
+    fn test_register_click() {
+        let mut cell_placement = CellPlacement::default();
+        let point = Point { x: 20, y: 10 };
+        let now = Instant::now();
+
+        assert!(!cell_placement.register_click(point, now));
+        assert!(cell_placement.register_click(point, now + Duration::from_millis(100)));
+        // A third click is not another double-click
+        assert!(!cell_placement.register_click(point, now + Duration::from_millis(200)));
+
+        // Too slow
+        assert!(!cell_placement.register_click(point, now + Duration::from_secs(1)));
+        // Somewhere else
+        assert!(!cell_placement
+            .register_click(Point { x: 22, y: 10 }, now + Duration::from_millis(1100)));
+    }
+
+    #[test]
+    fn test_line_from_double_click() {
+        let size = Size {
+            width: 10,
+            height: 9,
+        };
+
+        assert_eq!(
+            Line::from_double_click(Point { x: 5, y: 0 }, size),
+            Some(Line::Row)
+        );
+        assert_eq!(
+            Line::from_double_click(Point { x: 3, y: 0 }, size),
+            Some(Line::Row)
+        );
+        assert_eq!(
+            Line::from_double_click(Point { x: 0, y: 2 }, size),
+            Some(Line::Column)
+        );
+        assert_eq!(Line::from_double_click(Point { x: 0, y: 0 }, size), None);
+        assert_eq!(Line::from_double_click(Point { x: 9, y: 8 }, size), None);
+    }
+
+    #[test]
+    fn test_get_polyline_points() {
+        let bounds = Size {
+            width: 3,
+            height: 3,
+        };
+
+        assert!(get_polyline_points(&[], 1, bounds).is_empty());
+        assert_eq!(
+            get_polyline_points(&[Point { x: 1, y: 1 }], 1, bounds),
+            [Point { x: 1, y: 1 }]
+        );
+
+        let waypoints = [
+            Point { x: 0, y: 0 },
+            Point { x: 2, y: 0 },
+            Point { x: 2, y: 2 },
+        ];
+        let points = get_polyline_points(&waypoints, 1, bounds);
+        assert_eq!(
+            points,
+            [
+                Point { x: 0, y: 0 },
+                Point { x: 1, y: 0 },
+                Point { x: 2, y: 0 },
+                Point { x: 2, y: 1 },
+                Point { x: 2, y: 2 },
+            ]
+        );
+
+        // Where the thick lines overlap, the points are only there once
+        let points = get_polyline_points(&waypoints, 3, bounds);
+        assert_eq!(points.len(), 8);
+        assert!(!points.contains(&Point { x: 0, y: 2 }));
+        assert_eq!(
+            get_polyline_points(&[Point { x: 1, y: 1 }], 3, bounds).len(),
+            3
+        );
+    }
+
+    #[test]
+    fn test_place_measured_cells_polyline() {
+        let stdout = io::stdout();
+        let mut terminal = Terminal::new(stdout.lock()).unwrap();
+        let mut builder = get_builder();
+        let point = builder.point;
+        let get_cursor_point = |x: u16, y: u16| Point {
+            x: point.x + x * 2,
+            y: point.y + y,
+        };
+
+        let mut cell_placement = CellPlacement {
+            selected_cell_point: Some(get_cursor_point(0, 0)),
+            ..Default::default()
+        };
+        let _ = cell_placement.place_measured_cells(&mut terminal, &mut builder);
+        let _ = cell_placement.add_measurement_waypoint(
+            &mut terminal,
+            &builder,
+            get_cursor_point(3, 0),
+        );
+        cell_placement.selected_cell_point = Some(get_cursor_point(3, 2));
+        let _ = cell_placement.place_measured_cells(&mut terminal, &mut builder);
+
+        assert!(cell_placement.measurement_points.is_empty());
+        // The indices count along the whole polyline
+        assert_eq!(
+            builder.grid.get_cell(Point { x: 3, y: 0 }),
+            Cell::Measured(Some(4), Some(0))
+        );
+        assert_eq!(
+            builder.grid.get_cell(Point { x: 3, y: 2 }),
+            Cell::Measured(Some(6), Some(0))
+        );
+
+        // The whole polyline is undone at once
+        assert!(builder.grid.undo_last_cell());
+        assert!(builder.grid.cells.iter().all(|cell| *cell == Cell::Empty));
+    }
+
+    #[test]
+    fn test_get_rect_points() {
+        let points: Vec<Point> =
+            get_rect_points(Point { x: 3, y: 2 }, Point { x: 2, y: 1 }).collect();
+        assert_eq!(
+            points,
+            [
+                Point { x: 2, y: 1 },
+                Point { x: 3, y: 1 },
+                Point { x: 2, y: 2 },
+                Point { x: 3, y: 2 },
+            ]
+        );
+
+        assert_eq!(
+            get_rect_points(Point { x: 4, y: 4 }, Point { x: 4, y: 4 }).count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_get_cell_point_from_cursor_point() {
+        let builder = get_builder();
+
+        assert_eq!(
+            get_cell_point_from_cursor_point(Point { x: 20, y: 10 }, &builder),
+            Some(Point { x: 0, y: 0 })
+        );
+        assert_eq!(
+            get_cell_point_from_cursor_point(Point { x: 39, y: 14 }, &builder),
+            Some(Point { x: 9, y: 4 })
+        );
+
+        // Just outside of each edge
+        for cursor_point in [
+            Point { x: 19, y: 10 },
+            Point { x: 40, y: 10 },
+            Point { x: 20, y: 9 },
+            Point { x: 20, y: 15 },
+        ] {
+            assert_eq!(
+                get_cell_point_from_cursor_point(cursor_point, &builder),
+                None
+            );
+        }
+        // Left of and above the grid where subtracting the grid point would underflow
+        assert_eq!(
+            get_cell_point_from_cursor_point(Point { x: 0, y: 0 }, &builder),
+            None
+        );
+    }
+
+    #[test]
+    fn test_place_measured_cells_outside_of_grid() {
+        let stdout = io::stdout();
+        let mut terminal = Terminal::new(stdout.lock()).unwrap();
+        let mut builder = get_builder();
+        builder.grid.clear();
+
+        let mut cell_placement = CellPlacement {
+            selected_cell_point: Some(builder.point),
+            ..Default::default()
+        };
+        let _ = cell_placement.place_measured_cells(&mut terminal, &mut builder);
+
+        // The grid moved so that the first measurement point is not on it anymore
+        builder.point.x += 4;
+        cell_placement.selected_cell_point = Some(builder.point);
+        let state = cell_placement.place_measured_cells(&mut terminal, &mut builder);
+
+        assert!(matches!(state, State::Alert(_, AlertSeverity::Warning)));
+        assert!(cell_placement.measurement_points.is_empty());
+        assert!(builder.grid.cells.iter().all(|cell| *cell == Cell::Empty));
+    }
+
+    #[test]
+    fn test_get_clamped_cell_point_from_cursor_point() {
+        let builder = get_builder();
+
+        assert_eq!(
+            get_clamped_cell_point_from_cursor_point(Point { x: 23, y: 11 }, &builder),
+            Point { x: 1, y: 1 }
+        );
+        assert_eq!(
+            get_clamped_cell_point_from_cursor_point(Point { x: 0, y: 0 }, &builder),
+            Point { x: 0, y: 0 }
+        );
+        assert_eq!(
+            get_clamped_cell_point_from_cursor_point(Point { x: 100, y: 100 }, &builder),
+            Point { x: 9, y: 4 }
+        );
+    }
+
+    #[test]
+    fn test_place_rect() {
+        let stdout = io::stdout();
+        let mut terminal = Terminal::new(stdout.lock()).unwrap();
+        let mut builder = get_builder();
+        let point = builder.point;
+        *builder.grid.get_mut_cell(Point { x: 1, y: 1 }) = Cell::Crossed;
+
+        let mut cell_placement = CellPlacement {
+            rect_fill_mode: true,
+            ..Default::default()
+        };
+        cell_placement.start_rect(&mut terminal, &mut builder, point, Cell::Filled);
+        let _ = cell_placement.place_rect(
+            &mut terminal,
+            &mut builder,
+            Point {
+                x: point.x + 2 * 2,
+                y: point.y + 1,
+            },
+        );
+
+        let filled_cells = builder
+            .grid
+            .cells
+            .iter()
+            .filter(|cell| **cell == Cell::Filled)
+            .count();
+        assert_eq!(filled_cells, 3 * 2);
+        assert_eq!(builder.grid.get_cell(Point { x: 1, y: 1 }), Cell::Filled);
+        assert_eq!(cell_placement.rect_start, None);
+
+        // The whole rectangle is undone at once
+        assert!(builder.grid.undo_last_cell());
+        assert!(builder.grid.cells.iter().all(|cell| *cell == Cell::Empty));
+    }
+
+    #[test]
+    fn test_cycle() {
+        let mut cell = Cell::Empty;
+        let mut cells = Vec::<Cell>::new();
+        for _ in 0..4 {
+            cell = cell.cycle();
+            cells.push(cell);
+        }
+        assert_eq!(
+            cells,
+            [Cell::Filled, Cell::Crossed, Cell::Maybed, Cell::Empty]
+        );
+
+        assert_eq!(Cell::Measured(Some(1), None).cycle(), Cell::Filled);
+    }
+
+    #[test]
+    fn test_on_mode_change_without_pending_state() {
+        let builder = get_builder();
+        let mut cell_placement = CellPlacement {
+            selected_cell_point: Some(builder.point),
+            ..Default::default()
+        };
+
+        assert_eq!(on_mode_change(&mut cell_placement, &builder), None);
+        assert_eq!(cell_placement.selected_cell_point, Some(builder.point));
+    }
+
+    #[test]
+    fn test_on_mode_change_with_pending_fill() {
+        let builder = get_builder();
+        let mut cell_placement = CellPlacement {
+            fill: true,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            on_mode_change(&mut cell_placement, &builder),
+            Some("fill canceled")
+        );
+        assert!(!cell_placement.fill);
+    }
+
+    #[test]
+    fn test_on_mode_change_with_pending_measurement() {
+        let builder = get_builder();
+        let mut cell_placement = CellPlacement {
+            measurement_points: vec![builder.point],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            on_mode_change(&mut cell_placement, &builder),
+            Some("measurement canceled")
+        );
+        assert!(cell_placement.measurement_points.is_empty());
+    }
+
+    #[test]
+    fn test_on_mode_change_with_pending_fill_and_measurement() {
+        let builder = get_builder();
+        let mut cell_placement = CellPlacement {
+            fill: true,
+            measurement_points: vec![builder.point],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            on_mode_change(&mut cell_placement, &builder),
+            Some("fill and measurement canceled")
+        );
+        assert!(!cell_placement.fill);
+        assert!(cell_placement.measurement_points.is_empty());
+    }
+
+    #[test]
+    fn test_on_mode_change_with_active_stroke() {
+        let builder = get_builder();
+        let mut cell_placement = CellPlacement {
+            cell: Some(Cell::Crossed),
+            ..Default::default()
+        };
+
+        assert_eq!(on_mode_change(&mut cell_placement, &builder), None);
+        assert_eq!(cell_placement.cell, None);
+    }
+
+    #[test]
+    fn test_on_mode_change_with_selected_cell_point_outside_grid() {
+        let builder = get_builder();
+        let mut cell_placement = CellPlacement {
+            selected_cell_point: Some(Point {
+                x: builder.point.x - 1,
+                ..builder.point
+            }),
+            ..Default::default()
+        };
+
+        assert_eq!(on_mode_change(&mut cell_placement, &builder), None);
+        assert_eq!(cell_placement.selected_cell_point, None);
+    }
 }
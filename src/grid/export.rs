@@ -0,0 +1,306 @@
+use super::{
+    board::TextStyle,
+    palette::{ColorSupport, Palette, HIGHLIGHTED_CLUE_BACKGROUND_COLOR},
+    Cell, Grid,
+};
+use terminal::util::{Color, Point};
+
+/// The side length of a cell in the SVG, in pixels.
+const SVG_CELL_SIZE: u16 = 20;
+
+/// Gets the red, green and blue components of a color from the 256-color palette as it typically appears in terminals.
+fn get_byte_rgb(byte: u8) -> (u8, u8, u8) {
+    const BASIC_COLORS: [(u8, u8, u8); 16] = [
+        (0, 0, 0),
+        (128, 0, 0),
+        (0, 128, 0),
+        (128, 128, 0),
+        (0, 0, 128),
+        (128, 0, 128),
+        (0, 128, 128),
+        (192, 192, 192),
+        (128, 128, 128),
+        (255, 0, 0),
+        (0, 255, 0),
+        (255, 255, 0),
+        (0, 0, 255),
+        (255, 0, 255),
+        (0, 255, 255),
+        (255, 255, 255),
+    ];
+
+    match byte {
+        0..=15 => BASIC_COLORS[byte as usize],
+        16..=231 => {
+            let index = byte - 16;
+            let level = |value: u8| if value == 0 { 0 } else { 55 + value * 40 };
+            (level(index / 36), level(index / 6 % 6), level(index % 6))
+        }
+        232..=255 => {
+            let level = 8 + (byte - 232) * 10;
+            (level, level, level)
+        }
+    }
+}
+
+/// Gets the red, green and blue components of the color as it typically appears in terminals.
+pub(super) fn get_rgb(color: Color) -> (u8, u8, u8) {
+    match color {
+        Color::Black => get_byte_rgb(0),
+        Color::DarkRed => get_byte_rgb(1),
+        Color::DarkGreen => get_byte_rgb(2),
+        Color::DarkYellow => get_byte_rgb(3),
+        Color::DarkBlue => get_byte_rgb(4),
+        Color::DarkCyan => get_byte_rgb(6),
+        Color::Gray => get_byte_rgb(7),
+        Color::DarkGray => get_byte_rgb(8),
+        Color::Red => get_byte_rgb(9),
+        Color::Green => get_byte_rgb(10),
+        Color::Yellow => get_byte_rgb(11),
+        Color::Blue => get_byte_rgb(12),
+        Color::Cyan => get_byte_rgb(14),
+        Color::White => get_byte_rgb(15),
+        Color::Byte(byte) => get_byte_rgb(byte),
+        // Assume a black terminal background for everything else
+        _ => get_byte_rgb(0),
+    }
+}
+
+/// Converts the color to a hexadecimal color code as the color typically appears in terminals.
+fn get_hex_color(color: Color) -> String {
+    let (red, green, blue) = get_rgb(color);
+
+    format!("#{:02x}{:02x}{:02x}", red, green, blue)
+}
+
+fn write_svg_rect(svg: &mut String, x: u16, y: u16, width: u16, height: u16, color: Color) {
+    svg.push_str(&format!(
+        "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" style=\"fill:{}\"/>\n",
+        x,
+        y,
+        width,
+        height,
+        get_hex_color(color)
+    ));
+}
+
+fn write_svg_clue(svg: &mut String, x: u16, y: u16, clue: u16, color: Color) {
+    svg.push_str(&format!(
+        "<text x=\"{}\" y=\"{}\" style=\"fill:{};font-family:monospace;font-size:{}px;text-anchor:middle;dominant-baseline:central\">{}</text>\n",
+        x + SVG_CELL_SIZE / 2,
+        y + SVG_CELL_SIZE / 2,
+        get_hex_color(color),
+        SVG_CELL_SIZE * 3 / 5,
+        clue
+    ));
+}
+
+impl Grid {
+    /// Renders the grid with its clues as an SVG image, using the same colors as in the game.
+    pub fn to_svg(&self) -> String {
+        // The clue width is in characters and every left clue takes the same amount of characters
+        let clues_width = self.max_clues_size.width / self.get_left_clue_width() * SVG_CELL_SIZE;
+        let clues_height = self.max_clues_size.height * SVG_CELL_SIZE;
+
+        let width = clues_width + self.size.width * SVG_CELL_SIZE;
+        let height = clues_height + self.size.height * SVG_CELL_SIZE;
+
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" viewBox=\"0 0 {width} {height}\">\n"
+        );
+        svg.push_str(&format!(
+            "<title>yayagram {}x{}</title>\n",
+            self.size.width, self.size.height
+        ));
+
+        write_svg_rect(&mut svg, 0, 0, width, height, Color::Black);
+
+        // The top clues
+        for (x, vertical_clues_solution) in self.vertical_clues_solutions.iter().enumerate() {
+            let clue_color = if self.get_column_progress(x as u16).solved {
+                Color::DarkGray
+            } else {
+                Color::White
+            };
+
+            let clue_x = clues_width + x as u16 * SVG_CELL_SIZE;
+
+            if x % 2 == 0 {
+                write_svg_rect(
+                    &mut svg,
+                    clue_x,
+                    clues_height - vertical_clues_solution.len() as u16 * SVG_CELL_SIZE,
+                    SVG_CELL_SIZE,
+                    vertical_clues_solution.len() as u16 * SVG_CELL_SIZE,
+                    HIGHLIGHTED_CLUE_BACKGROUND_COLOR,
+                );
+            }
+
+            for (index, clue) in vertical_clues_solution.iter().rev().enumerate() {
+                let clue_y = clues_height - (index as u16 + 1) * SVG_CELL_SIZE;
+                write_svg_clue(&mut svg, clue_x, clue_y, *clue, clue_color);
+            }
+        }
+
+        // The left clues
+        for (y, horizontal_clues_solution) in self.horizontal_clues_solutions.iter().enumerate() {
+            let clue_color = if self.get_row_progress(y as u16).solved {
+                Color::DarkGray
+            } else {
+                Color::White
+            };
+
+            let clue_y = clues_height + y as u16 * SVG_CELL_SIZE;
+
+            if y % 2 == 0 {
+                write_svg_rect(
+                    &mut svg,
+                    clues_width - horizontal_clues_solution.len() as u16 * SVG_CELL_SIZE,
+                    clue_y,
+                    horizontal_clues_solution.len() as u16 * SVG_CELL_SIZE,
+                    SVG_CELL_SIZE,
+                    HIGHLIGHTED_CLUE_BACKGROUND_COLOR,
+                );
+            }
+
+            for (index, clue) in horizontal_clues_solution.iter().rev().enumerate() {
+                let clue_x = clues_width - (index as u16 + 1) * SVG_CELL_SIZE;
+                write_svg_clue(&mut svg, clue_x, clue_y, *clue, clue_color);
+            }
+        }
+
+        // The cells
+        let palette = Palette::new(ColorSupport::TrueColor);
+        for (y, row) in self.cells.chunks(self.size.width as usize).enumerate() {
+            for (x, cell) in row.iter().enumerate() {
+                let point = Point {
+                    x: x as u16,
+                    y: y as u16,
+                };
+                let color = if let Cell::Empty = cell {
+                    palette.get_empty_cell_color(point, false)
+                } else {
+                    cell.get_color()
+                };
+
+                write_svg_rect(
+                    &mut svg,
+                    clues_width + point.x * SVG_CELL_SIZE,
+                    clues_height + point.y * SVG_CELL_SIZE,
+                    SVG_CELL_SIZE,
+                    SVG_CELL_SIZE,
+                    color,
+                );
+            }
+        }
+
+        svg.push_str("</svg>\n");
+
+        svg
+    }
+}
+
+impl Grid {
+    /// Renders the grid with its clues as text, which is useful for sharing it.
+    ///
+    /// If `solved` is `false`, all cells are rendered as empty, hiding the progress.
+    pub fn to_unicode_art(&self, solved: bool) -> String {
+        let get_cell_char: fn(Cell) -> char = if solved {
+            |cell| match cell {
+                Cell::Empty | Cell::Measured(_, _) => '·',
+                Cell::Filled => '█',
+                Cell::Maybed => '?',
+                Cell::Crossed => 'X',
+            }
+        } else {
+            |_| '·'
+        };
+
+        self.to_text(&TextStyle {
+            get_cell_char,
+            column_spacing: 0,
+            zero_clue: false,
+            percentage: false,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_svg() {
+        #[rustfmt::skip]
+        let mut grid = Grid::from_lines(&[
+            "11",
+            "11",
+        ]).unwrap();
+        assert!(grid.solve());
+
+        let svg = grid.to_svg();
+
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.ends_with("</svg>\n"));
+        assert!(svg.matches("rect").count() >= 4);
+        assert_eq!(
+            svg.matches(&format!("fill:{}", get_hex_color(Color::White)))
+                .count(),
+            4
+        );
+    }
+
+    #[test]
+    fn test_get_hex_color() {
+        assert_eq!(get_hex_color(Color::White), "#ffffff");
+        assert_eq!(get_hex_color(Color::Byte(238)), "#444444");
+        assert_eq!(get_hex_color(Color::Byte(196)), "#ff0000");
+    }
+
+    #[test]
+    fn test_to_unicode_art() {
+        #[rustfmt::skip]
+        let mut grid = Grid::from_lines(&[
+            "1 1",
+            "111",
+            "  1",
+        ]).unwrap();
+        *grid.get_mut_cell(Point { x: 0, y: 0 }) = Cell::Filled;
+        *grid.get_mut_cell(Point { x: 1, y: 0 }) = Cell::Crossed;
+        *grid.get_mut_cell(Point { x: 2, y: 0 }) = Cell::Maybed;
+
+        let width = "1 1".len() + grid.size.width as usize * 2;
+
+        let art = grid.to_unicode_art(true);
+        for line in art.lines() {
+            assert_eq!(line.chars().count(), width);
+        }
+        assert_eq!(
+            art,
+            concat!("    2 1 3\n", "1 1██XX??\n", "  3······\n", "  1······\n",)
+        );
+
+        let art = grid.to_unicode_art(false);
+        for line in art.lines() {
+            assert_eq!(line.chars().count(), width);
+        }
+        assert!(!art.contains('█'));
+    }
+
+    #[test]
+    fn test_to_unicode_art_with_multi_digit_clues() {
+        let grid = Grid::from_lines(&["1111111111 1"; 10]).unwrap();
+
+        let art = grid.to_unicode_art(false);
+        let mut lines = art.lines();
+        // The left clues are separated and each column fits the widest top clue and a space
+        assert_eq!(
+            lines.next(),
+            Some(format!("    {}    10", " 10".repeat(10)).as_str())
+        );
+        assert_eq!(
+            lines.next(),
+            Some(format!("10 1{}", "·".repeat(12 * 3)).as_str())
+        );
+    }
+}
@@ -0,0 +1,449 @@
+//! The colors that the grid is drawn with, which depend on how many colors the terminal supports.
+
+use super::{Cell, Mistake};
+use std::{env, ffi::OsString};
+use terminal::util::{Color, Point};
+
+/// How many colors the terminal supports.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColorSupport {
+    /// No colors at all, either because the terminal can't show them or because the user doesn't want them.
+    None,
+    /// The 16 basic colors.
+    Ansi16,
+    /// The 256-color palette.
+    Ansi256,
+    /// Any RGB color.
+    TrueColor,
+}
+
+impl ColorSupport {
+    /// Detects the color support using environment variables.
+    ///
+    /// If [`NO_COLOR`](https://no-color.org) is set, there are no colors.
+    /// Otherwise `COLORTERM` and `TERM` are checked, which is only a guess because not all terminals set them accurately.
+    pub fn detect() -> Self {
+        Self::from_env(
+            env::var_os("NO_COLOR"),
+            env::var("COLORTERM").ok().as_deref(),
+            env::var("TERM").ok().as_deref(),
+        )
+    }
+
+    fn from_env(no_color: Option<OsString>, colorterm: Option<&str>, term: Option<&str>) -> Self {
+        if no_color.is_some_and(|no_color| !no_color.is_empty()) {
+            return Self::None;
+        }
+
+        if let Some("truecolor" | "24bit") = colorterm {
+            return Self::TrueColor;
+        }
+
+        match term {
+            Some("dumb") => Self::None,
+            Some(term) if term.contains("256color") => Self::Ansi256,
+            Some(_) => Self::Ansi16,
+            // Terminals on Windows usually don't set `TERM` but they support the 256-color palette
+            None if cfg!(windows) => Self::Ansi256,
+            None => Self::Ansi16,
+        }
+    }
+
+    /// Parses the value of the `--colors` option.
+    pub fn parse(str: &str) -> Option<Self> {
+        match str {
+            "none" => Some(Self::None),
+            "16" => Some(Self::Ansi16),
+            "256" => Some(Self::Ansi256),
+            "truecolor" => Some(Self::TrueColor),
+            _ => None,
+        }
+    }
+
+    /// The name that [`ColorSupport::parse`] accepts.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Ansi16 => "16",
+            Self::Ansi256 => "256",
+            Self::TrueColor => "truecolor",
+        }
+    }
+}
+
+/// A kind of color blindness that the colors can accommodate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum ColorBlindMode {
+    #[default]
+    None,
+    /// Red and green are hard to tell apart, because of the green cones.
+    Deuteranopia,
+    /// Red and green are hard to tell apart, because of the red cones.
+    Protanopia,
+    /// Blue and yellow are hard to tell apart.
+    Tritanopia,
+}
+
+impl ColorBlindMode {
+    /// Parses the value of the `--color-blind` option.
+    pub fn parse(str: &str) -> Option<Self> {
+        match str {
+            "none" => Some(Self::None),
+            "deuteranopia" => Some(Self::Deuteranopia),
+            "protanopia" => Some(Self::Protanopia),
+            "tritanopia" => Some(Self::Tritanopia),
+            _ => None,
+        }
+    }
+
+    /// The name that [`ColorBlindMode::parse`] accepts.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Deuteranopia => "deuteranopia",
+            Self::Protanopia => "protanopia",
+            Self::Tritanopia => "tritanopia",
+        }
+    }
+}
+
+/// The background color of every other clue row and column.
+pub const HIGHLIGHTED_CLUE_BACKGROUND_COLOR: Color = Color::Byte(238);
+
+/// Chooses the colors to draw with so that the game remains playable with fewer colors.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Palette {
+    pub color_support: ColorSupport,
+    /// Whether only colors that stand out strongly against each other are used, for players who have trouble telling similar colors apart.
+    pub high_contrast: bool,
+    /// Crossed out and measured cells are red and green, which is a problem for many color blind players.
+    pub color_blind_mode: ColorBlindMode,
+}
+
+impl Default for Palette {
+    /// The colors that the game was designed with.
+    fn default() -> Self {
+        Self::new(ColorSupport::Ansi256)
+    }
+}
+
+impl Palette {
+    pub const fn new(color_support: ColorSupport) -> Self {
+        Self {
+            color_support,
+            high_contrast: false,
+            color_blind_mode: ColorBlindMode::None,
+        }
+    }
+
+    pub const fn with_high_contrast(self, high_contrast: bool) -> Self {
+        Self {
+            high_contrast,
+            ..self
+        }
+    }
+
+    pub const fn with_color_blind_mode(self, color_blind_mode: ColorBlindMode) -> Self {
+        Self {
+            color_blind_mode,
+            ..self
+        }
+    }
+
+    /// Whether the colors are high-contrast ones. Without colors, there is nothing to change.
+    pub const fn is_high_contrast(&self) -> bool {
+        self.high_contrast && self.has_colors()
+    }
+
+    /// Whether anything is drawn with colors. If not, cells need to be told apart by their characters.
+    pub const fn has_colors(&self) -> bool {
+        !matches!(self.color_support, ColorSupport::None)
+    }
+
+    /// Gets the color of an empty cell at the given point, which depends on the position.
+    pub fn get_empty_cell_color(&self, point: Point, highlight: bool) -> Color {
+        /// Every 5 cells, the color changes to make the grid and its cells easier to look at and distinguish.
+        const SEPARATION_POINT: u16 = 5;
+
+        let x_reached_point = point.x / SEPARATION_POINT % 2 == 0;
+        let y_reached_point = point.y / SEPARATION_POINT % 2 == 0;
+        let alternate_section = x_reached_point ^ y_reached_point;
+
+        if self.is_high_contrast() {
+            return if highlight {
+                Color::Cyan
+            } else if alternate_section {
+                Color::Black
+            } else {
+                Color::White
+            };
+        }
+
+        match self.color_support {
+            ColorSupport::None | ColorSupport::Ansi16 => {
+                if highlight {
+                    // This is a color that none of the other cells use
+                    Color::DarkCyan
+                } else if alternate_section {
+                    Color::DarkGray
+                } else {
+                    Color::Black
+                }
+            }
+            ColorSupport::Ansi256 | ColorSupport::TrueColor => {
+                let mut background_color_byte = if alternate_section { 238 } else { 240 };
+
+                if highlight {
+                    background_color_byte -= 3;
+                }
+
+                Color::Byte(background_color_byte)
+            }
+        }
+    }
+
+    /// Gets the color of a cell. Empty cells are drawn with [`Palette::get_empty_cell_color`] instead.
+    pub fn get_cell_color(&self, cell: Cell, highlight: bool) -> Color {
+        if self.color_blind_mode != ColorBlindMode::None {
+            if let Some(color) = self.get_color_blind_cell_color(cell, highlight) {
+                return color;
+            }
+        }
+
+        if !self.is_high_contrast() {
+            return if highlight {
+                cell.get_highlighted_color()
+            } else {
+                cell.get_color()
+            };
+        }
+
+        let (color, highlighted_color) = match cell {
+            Cell::Empty => (Color::White, Color::Cyan),
+            Cell::Filled => (Color::Blue, Color::DarkBlue),
+            Cell::Maybed => (Color::Yellow, Color::DarkYellow),
+            Cell::Crossed => (Color::Red, Color::DarkRed),
+            Cell::Measured(_, _) => (Color::Green, Color::DarkGreen),
+        };
+
+        if highlight {
+            highlighted_color
+        } else {
+            color
+        }
+    }
+
+    /// Gets the color of a crossed out or measured cell as orange and sky blue,
+    /// which can be told apart with any kind of color blindness, unlike red and green.
+    fn get_color_blind_cell_color(&self, cell: Cell, highlight: bool) -> Option<Color> {
+        let color = match (self.color_support, cell, highlight) {
+            (ColorSupport::None, _, _) => return None,
+            (ColorSupport::Ansi16, Cell::Crossed, false) => Color::Yellow,
+            (ColorSupport::Ansi16, Cell::Crossed, true) => Color::DarkYellow,
+            (ColorSupport::Ansi16, Cell::Measured(_, _), false) => Color::Cyan,
+            (ColorSupport::Ansi16, Cell::Measured(_, _), true) => Color::DarkCyan,
+            (_, Cell::Crossed, false) => Color::Byte(208),
+            (_, Cell::Crossed, true) => Color::Byte(166),
+            (_, Cell::Measured(_, _), false) => Color::Byte(74),
+            (_, Cell::Measured(_, _), true) => Color::Byte(31),
+            _ => return None,
+        };
+
+        Some(color)
+    }
+
+    /// Gets the color of a cell in the picture, which only has black and white with high contrast.
+    pub fn get_picture_cell_color(&self, cell: Cell) -> Color {
+        if !self.is_high_contrast() {
+            cell.get_color()
+        } else if cell == Cell::Filled {
+            Color::White
+        } else {
+            Color::Black
+        }
+    }
+
+    /// Gets the color that a mistake is marked with. No cell has these colors so that mistakes stand out.
+    pub const fn get_mistake_color(&self, mistake: Mistake) -> Color {
+        match mistake {
+            Mistake::WrongFill => Color::Magenta,
+            Mistake::WrongCross => Color::Yellow,
+        }
+    }
+
+    /// Gets the background color of every other clue row and column, if there is one.
+    ///
+    /// With high contrast, every other line isn't highlighted because the white background is used to mark solved lines.
+    pub const fn get_highlighted_clue_background_color(&self) -> Option<Color> {
+        if self.is_high_contrast() {
+            return None;
+        }
+
+        match self.color_support {
+            // Only the basic colors would be too distracting
+            ColorSupport::None | ColorSupport::Ansi16 => None,
+            ColorSupport::Ansi256 | ColorSupport::TrueColor => {
+                Some(HIGHLIGHTED_CLUE_BACKGROUND_COLOR)
+            }
+        }
+    }
+}
+
+/// The color of the clues of a row or column whose filled cells contradict them.
+pub const CONTRADICTED_CLUE_COLOR: Color = Color::Red;
+
+/// The colors of the clues of a solved line with high contrast, instead of the usual grayed out clues.
+pub const HIGH_CONTRAST_SOLVED_CLUE_COLORS: (Color, Color) = (Color::Black, Color::White);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::export;
+
+    /// Simulates how the color looks with the color blindness, using the matrices of Machado et al. (2009) at full severity.
+    fn simulate_color_blindness(color: Color, color_blind_mode: ColorBlindMode) -> [f64; 3] {
+        let matrix = match color_blind_mode {
+            ColorBlindMode::None => [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+            ColorBlindMode::Deuteranopia => [
+                [0.367322, 0.860646, -0.227968],
+                [0.280085, 0.672501, 0.047413],
+                [-0.011820, 0.042940, 0.968881],
+            ],
+            ColorBlindMode::Protanopia => [
+                [0.152286, 1.052583, -0.204868],
+                [0.114503, 0.786281, 0.099216],
+                [-0.003882, -0.048116, 1.051998],
+            ],
+            ColorBlindMode::Tritanopia => [
+                [1.255528, -0.076749, -0.178779],
+                [-0.078411, 0.930809, 0.147602],
+                [0.004733, 0.691367, 0.303900],
+            ],
+        };
+
+        let (red, green, blue) = export::get_rgb(color);
+        let rgb = [red as f64, green as f64, blue as f64];
+
+        matrix.map(|row| (row[0] * rgb[0] + row[1] * rgb[1] + row[2] * rgb[2]).clamp(0.0, 255.0))
+    }
+
+    fn get_distance(first: [f64; 3], second: [f64; 3]) -> f64 {
+        first
+            .iter()
+            .zip(second)
+            .map(|(first, second)| (first - second).powi(2))
+            .sum::<f64>()
+            .sqrt()
+    }
+
+    #[test]
+    fn test_detect_color_support() {
+        assert_eq!(
+            ColorSupport::from_env(Some("1".into()), Some("truecolor"), Some("xterm-256color")),
+            ColorSupport::None
+        );
+        // An empty `NO_COLOR` is ignored
+        assert_eq!(
+            ColorSupport::from_env(Some("".into()), Some("truecolor"), Some("xterm-256color")),
+            ColorSupport::TrueColor
+        );
+        assert_eq!(
+            ColorSupport::from_env(None, Some("24bit"), Some("xterm")),
+            ColorSupport::TrueColor
+        );
+        assert_eq!(
+            ColorSupport::from_env(None, None, Some("xterm-256color")),
+            ColorSupport::Ansi256
+        );
+        assert_eq!(
+            ColorSupport::from_env(None, None, Some("linux")),
+            ColorSupport::Ansi16
+        );
+        assert_eq!(
+            ColorSupport::from_env(None, None, Some("dumb")),
+            ColorSupport::None
+        );
+    }
+
+    #[test]
+    fn test_get_empty_cell_color() {
+        let point = Point { x: 0, y: 0 };
+        let alternate_point = Point { x: 5, y: 0 };
+
+        let palette = Palette::default();
+        assert_eq!(palette.get_empty_cell_color(point, false), Color::Byte(240));
+        assert_eq!(
+            palette.get_empty_cell_color(alternate_point, false),
+            Color::Byte(238)
+        );
+        assert_eq!(palette.get_empty_cell_color(point, true), Color::Byte(237));
+
+        // No byte colors are used with the basic colors
+        let palette = Palette::new(ColorSupport::Ansi16);
+        assert_eq!(palette.get_empty_cell_color(point, false), Color::Black);
+        assert_eq!(
+            palette.get_empty_cell_color(alternate_point, false),
+            Color::DarkGray
+        );
+        assert_eq!(palette.get_highlighted_clue_background_color(), None);
+    }
+
+    #[test]
+    fn test_high_contrast() {
+        let point = Point { x: 0, y: 0 };
+        let alternate_point = Point { x: 5, y: 0 };
+
+        let palette = Palette::default().with_high_contrast(true);
+        assert!(palette.is_high_contrast());
+        assert_eq!(palette.get_empty_cell_color(point, false), Color::White);
+        assert_ne!(
+            palette.get_empty_cell_color(point, false),
+            Palette::new(ColorSupport::Ansi16).get_empty_cell_color(point, false)
+        );
+        assert_eq!(
+            palette.get_empty_cell_color(alternate_point, false),
+            Color::Black
+        );
+        assert_eq!(palette.get_highlighted_clue_background_color(), None);
+
+        // Filled cells stand out from both kinds of empty cells
+        for highlight in [false, true] {
+            let filled_cell_color = palette.get_cell_color(Cell::Filled, highlight);
+            assert_ne!(filled_cell_color, Color::White);
+            assert_ne!(filled_cell_color, Color::Black);
+        }
+
+        let palette = Palette::new(ColorSupport::None).with_high_contrast(true);
+        assert!(!palette.is_high_contrast());
+    }
+
+    #[test]
+    fn test_color_blind_mode() {
+        for color_blind_mode in [
+            ColorBlindMode::Deuteranopia,
+            ColorBlindMode::Protanopia,
+            ColorBlindMode::Tritanopia,
+        ] {
+            assert_eq!(
+                ColorBlindMode::parse(color_blind_mode.name()),
+                Some(color_blind_mode)
+            );
+
+            for color_support in [ColorSupport::Ansi16, ColorSupport::Ansi256] {
+                let palette = Palette::new(color_support).with_color_blind_mode(color_blind_mode);
+                let simulate = |cell| {
+                    simulate_color_blindness(palette.get_cell_color(cell, false), color_blind_mode)
+                };
+
+                let crossed_cell_color = simulate(Cell::Crossed);
+                let measured_cell_color = simulate(Cell::Measured(None, None));
+                let maybed_cell_color = simulate(Cell::Maybed);
+
+                assert!(get_distance(crossed_cell_color, measured_cell_color) > 100.0);
+                assert!(get_distance(crossed_cell_color, maybed_cell_color) > 100.0);
+                assert!(get_distance(measured_cell_color, maybed_cell_color) > 100.0);
+            }
+        }
+        assert_eq!(ColorBlindMode::parse("monochromacy"), None);
+    }
+}
@@ -1,18 +1,102 @@
-use super::{Cell, Grid};
+use super::{Cell, Grid, GridError};
 use terminal::util::Size;
 
-fn random_cells(size: u32) -> Vec<Cell> {
-    let mut cells = Vec::<Cell>::with_capacity(size as usize);
+fn random_cells(size: Size, empty_lines: bool, rng: &mut fastrand::Rng) -> Vec<Cell> {
+    let mut cells = Vec::<Cell>::with_capacity(size.product() as usize);
 
-    for _ in 0..size {
-        cells.push(Cell::from(fastrand::bool()));
+    for _ in 0..size.product() {
+        cells.push(Cell::from(rng.bool()));
+    }
+
+    if !empty_lines {
+        fill_empty_lines(&mut cells, size, rng);
     }
 
     cells
 }
 
+/// Fills a random cell in every row and column that has no filled cells.
+///
+/// On a 1x1 grid this means that the only cell is always filled.
+fn fill_empty_lines(cells: &mut [Cell], size: Size, rng: &mut fastrand::Rng) {
+    let width = size.width as usize;
+
+    for row in cells.chunks_mut(width) {
+        if !row.contains(&Cell::Filled) {
+            row[rng.usize(..width)] = Cell::Filled;
+        }
+    }
+
+    // Filling cells in the columns can't empty any of the rows again
+    for x in 0..width {
+        let column_is_empty = cells
+            .iter()
+            .skip(x)
+            .step_by(width)
+            .all(|cell| *cell != Cell::Filled);
+
+        if column_is_empty {
+            let y = rng.usize(..size.height as usize);
+            cells[y * width + x] = Cell::Filled;
+        }
+    }
+}
+
 impl Grid {
-    pub fn random(size: Size) -> Grid {
-        Self::new(size, random_cells(size.product()))
+    /// Generates a grid with random cells. If `empty_lines` is `false`, every row and column has at least one filled cell.
+    pub fn random(size: Size, empty_lines: bool) -> Result<Grid, GridError> {
+        Self::random_with_rng(size, empty_lines, &mut fastrand::Rng::new())
+    }
+
+    pub fn random_with_rng(
+        size: Size,
+        empty_lines: bool,
+        rng: &mut fastrand::Rng,
+    ) -> Result<Grid, GridError> {
+        // Filling the empty lines requires at least one cell in every line
+        if size.width == 0 || size.height == 0 {
+            return Err(GridError::ZeroDimension);
+        }
+
+        Self::new(size, random_cells(size, empty_lines, rng))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_random_without_empty_lines() {
+        let size = Size {
+            width: 10,
+            height: 10,
+        };
+        let mut rng = fastrand::Rng::with_seed(42);
+
+        for _ in 0..100 {
+            let grid = Grid::random_with_rng(size, false, &mut rng).unwrap();
+            assert!(grid
+                .horizontal_clues_solutions
+                .iter()
+                .chain(&grid.vertical_clues_solutions)
+                .all(|clues_solution| !clues_solution.is_empty()));
+        }
+
+        let size = Size {
+            width: 1,
+            height: 1,
+        };
+        let grid = Grid::random_with_rng(size, false, &mut rng).unwrap();
+        assert_eq!(grid.solution, Some(vec![Cell::Filled]));
+
+        let size = Size {
+            width: 3,
+            height: 0,
+        };
+        assert_eq!(
+            Grid::random_with_rng(size, false, &mut rng).err(),
+            Some(GridError::ZeroDimension)
+        );
     }
 }
@@ -0,0 +1,663 @@
+//! Finds solutions to grids using only their clues.
+//!
+//! Each line is solved by determining which cells are filled or empty in every placement of the clues that is
+//! consistent with what is already known about the line.
+//! When that does not lead anywhere anymore, a cell is guessed and the guess is backtracked if it leads to a contradiction.
+
+use super::{get_index, Cell, Clue, Grid};
+use terminal::util::{Point, Size};
+
+/// What is known about a cell while solving: `Some(true)` if it is filled, `Some(false)` if it is empty and `None` if it is unknown.
+type Knowledge = Option<bool>;
+
+/// Deduces as much as possible about the given line using its clues.
+///
+/// Returns whether anything new was deduced or `None` if the line contradicts the clues.
+pub fn solve_line(clues: &[Clue], line: &mut [Knowledge]) -> Option<bool> {
+    let len = line.len();
+    let clue_count = clues.len();
+
+    let can_be_empty = |index: usize| line[index] != Some(true);
+
+    // The amount of cells known to be empty before each index, used to check whether a clue fits somewhere
+    let mut empty_counts = Vec::<usize>::with_capacity(len + 1);
+    empty_counts.push(0);
+    for (index, knowledge) in line.iter().enumerate() {
+        empty_counts.push(empty_counts[index] + (*knowledge == Some(false)) as usize);
+    }
+    let fits = |start: usize, clue: usize| empty_counts[start + clue] == empty_counts[start];
+
+    // `forward[clue_index * (len + 1) + index]`: whether the first `clue_index` clues fit into the first `index` cells.
+    let mut forward = vec![false; (clue_count + 1) * (len + 1)];
+    forward[0] = true;
+    for index in 1..=len {
+        for clue_index in 0..=clue_count {
+            let mut possible =
+                can_be_empty(index - 1) && forward[clue_index * (len + 1) + index - 1];
+
+            if !possible && clue_index > 0 {
+                let clue = clues[clue_index - 1] as usize;
+                if index >= clue && fits(index - clue, clue) {
+                    let start = index - clue;
+                    possible = if start == 0 {
+                        clue_index == 1
+                    } else {
+                        can_be_empty(start - 1) && forward[(clue_index - 1) * (len + 1) + start - 1]
+                    };
+                }
+            }
+
+            forward[clue_index * (len + 1) + index] = possible;
+        }
+    }
+
+    if !forward[clue_count * (len + 1) + len] {
+        return None;
+    }
+
+    // `backward[clue_index * (len + 1) + index]`: whether the clues from `clue_index` on fit into the cells from `index` on.
+    let mut backward = vec![false; (clue_count + 1) * (len + 1)];
+    backward[clue_count * (len + 1) + len] = true;
+    for index in (0..len).rev() {
+        for clue_index in 0..=clue_count {
+            let mut possible = can_be_empty(index) && backward[clue_index * (len + 1) + index + 1];
+
+            if !possible && clue_index < clue_count {
+                let clue = clues[clue_index] as usize;
+                if index + clue <= len && fits(index, clue) {
+                    let end = index + clue;
+                    possible = if end == len {
+                        clue_index + 1 == clue_count
+                    } else {
+                        can_be_empty(end) && backward[(clue_index + 1) * (len + 1) + end + 1]
+                    };
+                }
+            }
+
+            backward[clue_index * (len + 1) + index] = possible;
+        }
+    }
+
+    // Mark the ranges that any clue can cover using a difference array
+    let mut fillable = vec![0isize; len + 1];
+    for (clue_index, clue) in clues.iter().enumerate() {
+        let clue = *clue as usize;
+        if clue > len {
+            return None;
+        }
+        for start in 0..=len - clue {
+            let end = start + clue;
+
+            let fits_left = if start == 0 {
+                clue_index == 0
+            } else {
+                can_be_empty(start - 1) && forward[clue_index * (len + 1) + start - 1]
+            };
+            let fits_right = if end == len {
+                clue_index + 1 == clue_count
+            } else {
+                can_be_empty(end) && backward[(clue_index + 1) * (len + 1) + end + 1]
+            };
+
+            if fits(start, clue) && fits_left && fits_right {
+                fillable[start] += 1;
+                fillable[end] -= 1;
+            }
+        }
+    }
+
+    let mut deductions = Vec::<Knowledge>::with_capacity(len);
+    let mut fill_coverage = 0;
+    for index in 0..len {
+        fill_coverage += fillable[index];
+
+        let can_be_filled = fill_coverage > 0;
+        let can_be_empty = can_be_empty(index)
+            && (0..=clue_count).any(|clue_index| {
+                forward[clue_index * (len + 1) + index]
+                    && backward[clue_index * (len + 1) + index + 1]
+            });
+
+        deductions.push(match (can_be_filled, can_be_empty) {
+            (true, true) => None,
+            (true, false) => Some(true),
+            (false, true) => Some(false),
+            (false, false) => return None,
+        });
+    }
+
+    let mut changed = false;
+    for (knowledge, deduction) in line.iter_mut().zip(deductions) {
+        if deduction.is_some() && *knowledge != deduction {
+            *knowledge = deduction;
+            changed = true;
+        }
+    }
+
+    Some(changed)
+}
+
+/// Solves every line repeatedly until nothing new can be deduced.
+///
+/// Returns how many rounds of solving the rows and then the columns it took or `None` if the knowledge contradicts the clues.
+fn propagate(
+    size: Size,
+    horizontal_clues_solutions: &[Vec<Clue>],
+    vertical_clues_solutions: &[Vec<Clue>],
+    knowledge: &mut [Knowledge],
+) -> Option<usize> {
+    let mut dirty_rows = vec![true; size.height as usize];
+    let mut dirty_columns = vec![true; size.width as usize];
+    let mut line = Vec::<Knowledge>::with_capacity(size.width.max(size.height) as usize);
+    let mut rounds = 0;
+
+    while dirty_rows.contains(&true) || dirty_columns.contains(&true) {
+        rounds += 1;
+
+        for y in 0..size.height {
+            if !dirty_rows[y as usize] {
+                continue;
+            }
+            dirty_rows[y as usize] = false;
+
+            line.clear();
+            line.extend((0..size.width).map(|x| knowledge[get_index(size.width, Point { x, y })]));
+            match solve_line(&horizontal_clues_solutions[y as usize], &mut line) {
+                None => return None,
+                Some(false) => {}
+                Some(true) => {
+                    for (x, cell) in line.iter().enumerate() {
+                        let index = get_index(size.width, Point { x: x as u16, y });
+                        if knowledge[index] != *cell {
+                            knowledge[index] = *cell;
+                            dirty_columns[x] = true;
+                        }
+                    }
+                }
+            }
+        }
+
+        for x in 0..size.width {
+            if !dirty_columns[x as usize] {
+                continue;
+            }
+            dirty_columns[x as usize] = false;
+
+            line.clear();
+            line.extend((0..size.height).map(|y| knowledge[get_index(size.width, Point { x, y })]));
+            match solve_line(&vertical_clues_solutions[x as usize], &mut line) {
+                None => return None,
+                Some(false) => {}
+                Some(true) => {
+                    for (y, cell) in line.iter().enumerate() {
+                        let index = get_index(size.width, Point { x, y: y as u16 });
+                        if knowledge[index] != *cell {
+                            knowledge[index] = *cell;
+                            dirty_rows[y] = true;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    Some(rounds)
+}
+
+/// Finds solutions by deducing and guessing, calling `on_solution` for each solution found until it returns `false`.
+///
+/// Returns whether the search was stopped by `on_solution`.
+fn search<F>(
+    size: Size,
+    horizontal_clues_solutions: &[Vec<Clue>],
+    vertical_clues_solutions: &[Vec<Clue>],
+    mut knowledge: Vec<Knowledge>,
+    on_solution: &mut F,
+) -> bool
+where
+    F: FnMut(&[Knowledge]) -> bool,
+{
+    if propagate(
+        size,
+        horizontal_clues_solutions,
+        vertical_clues_solutions,
+        &mut knowledge,
+    )
+    .is_none()
+    {
+        return false;
+    }
+
+    if let Some(unknown_index) = knowledge.iter().position(Option::is_none) {
+        for guess in [true, false] {
+            let mut guessed_knowledge = knowledge.clone();
+            guessed_knowledge[unknown_index] = Some(guess);
+            if search(
+                size,
+                horizontal_clues_solutions,
+                vertical_clues_solutions,
+                guessed_knowledge,
+                on_solution,
+            ) {
+                return true;
+            }
+        }
+        false
+    } else {
+        !on_solution(&knowledge)
+    }
+}
+
+/// How much reasoning a grid takes to solve.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Difficulty {
+    /// Solving the rows and then the columns at most twice solves the grid.
+    Easy,
+    /// Solving the lines one at a time solves the grid but takes more rounds.
+    Medium,
+    /// Solving the lines one at a time gets stuck, so cells have to be reasoned about across lines or guessed.
+    /// This is also the case if the clues have multiple solutions or none.
+    Hard,
+}
+
+/// The most rounds of line solving that an easy grid takes.
+const MAX_EASY_ROUNDS: usize = 2;
+
+/// The lowest score of [`Grid::get_remaining_difficulty_score`] for cells that cannot be decided by solving the lines one at a time.
+const STUCK_SCORE: f32 = 0.6;
+
+/// Gets a label for a score of [`Grid::get_remaining_difficulty_score`].
+pub fn get_difficulty_label(score: f32) -> &'static str {
+    if score < 0.2 {
+        "trivial"
+    } else if score < 0.4 {
+        "easy"
+    } else if score < STUCK_SCORE {
+        "medium"
+    } else if score < 0.8 {
+        "hard"
+    } else {
+        "expert"
+    }
+}
+
+impl Difficulty {
+    /// Parses the value of the `--difficulty` option.
+    pub fn parse(str: &str) -> Option<Self> {
+        match str {
+            "easy" => Some(Self::Easy),
+            "medium" => Some(Self::Medium),
+            "hard" => Some(Self::Hard),
+            _ => None,
+        }
+    }
+
+    /// The name that [`Difficulty::parse`] accepts.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Easy => "easy",
+            Self::Medium => "medium",
+            Self::Hard => "hard",
+        }
+    }
+}
+
+impl Grid {
+    /// Estimates the difficulty by solving the lines one at a time, as a player would,
+    /// and counting how many rounds that takes. No guessing is done so this is fast even for big grids.
+    pub fn estimate_difficulty(&self) -> Difficulty {
+        let mut knowledge = vec![None; self.size.product() as usize];
+
+        let rounds = propagate(
+            self.size,
+            &self.horizontal_clues_solutions,
+            &self.vertical_clues_solutions,
+            &mut knowledge,
+        );
+
+        match rounds {
+            Some(rounds) if knowledge.iter().all(Option::is_some) => {
+                if rounds <= MAX_EASY_ROUNDS {
+                    Difficulty::Easy
+                } else {
+                    Difficulty::Medium
+                }
+            }
+            _ => Difficulty::Hard,
+        }
+    }
+
+    /// Scores how hard the cells that are not filled yet are to decide, from `0.0` for nothing left to decide to `1.0`.
+    ///
+    /// Filled cells are taken as decided. Like [`Grid::estimate_difficulty`], this only solves the lines one at a time
+    /// and never guesses, so it is fast enough to be done after every placed cell even for big grids.
+    /// Returns `None` if the filled cells contradict the clues.
+    pub fn get_remaining_difficulty_score(&self) -> Option<f32> {
+        let mut knowledge: Vec<Knowledge> = self
+            .cells
+            .iter()
+            .map(|cell| (*cell == Cell::Filled).then_some(true))
+            .collect();
+        let undecided_count = knowledge
+            .iter()
+            .filter(|knowledge| knowledge.is_none())
+            .count();
+        if undecided_count == 0 {
+            return Some(0.0);
+        }
+
+        let rounds = propagate(
+            self.size,
+            &self.horizontal_clues_solutions,
+            &self.vertical_clues_solutions,
+            &mut knowledge,
+        )?;
+
+        let stuck_count = knowledge
+            .iter()
+            .filter(|knowledge| knowledge.is_none())
+            .count();
+        let score = if stuck_count == 0 {
+            // The more rounds it takes, the closer it gets to where line solving gets stuck
+            STUCK_SCORE * (1.0 - 1.0 / rounds as f32)
+        } else {
+            STUCK_SCORE + (1.0 - STUCK_SCORE) * stuck_count as f32 / undecided_count as f32
+        };
+
+        Some(score)
+    }
+
+    /// Solves the grid using only its clues, replacing all cells with the solution, and returns whether a solution was found.
+    ///
+    /// If there are multiple solutions, the first one found is used.
+    pub fn solve(&mut self) -> bool {
+        let mut solution = None;
+
+        search(
+            self.size,
+            &self.horizontal_clues_solutions,
+            &self.vertical_clues_solutions,
+            vec![None; self.size.product() as usize],
+            &mut |knowledge| {
+                solution = Some(knowledge.to_vec());
+                false
+            },
+        );
+
+        if let Some(solution) = solution {
+            for (cell, knowledge) in self.cells.iter_mut().zip(solution) {
+                *cell = Cell::from(knowledge == Some(true));
+            }
+            self.invalidate_line_caches();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Counts the solutions of the clues, stopping at `limit` so that not all of them have to be found.
+    ///
+    /// A `limit` of 2 is enough to tell whether the grid has no solution, a unique one or multiple ones.
+    pub fn count_solutions(&self, limit: usize) -> usize {
+        if limit == 0 {
+            return 0;
+        }
+
+        let mut count = 0;
+
+        search(
+            self.size,
+            &self.horizontal_clues_solutions,
+            &self.vertical_clues_solutions,
+            vec![None; self.size.product() as usize],
+            &mut |_| {
+                count += 1;
+                count < limit
+            },
+        );
+
+        count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_line(line: &str) -> Vec<Knowledge> {
+        line.chars()
+            .map(|char| match char {
+                '1' => Some(true),
+                'X' => Some(false),
+                '?' => None,
+                _ => panic!("the strings must only contain '1', 'X' or '?'"),
+            })
+            .collect()
+    }
+
+    fn solve(clues: &[Clue], line: &str) -> Option<Vec<Knowledge>> {
+        let mut line = parse_line(line);
+        solve_line(clues, &mut line)?;
+        Some(line)
+    }
+
+    #[test]
+    fn test_solve_line() {
+        assert_eq!(solve(&[3], "????"), Some(parse_line("?11?")));
+        assert_eq!(solve(&[2, 1], "????"), Some(parse_line("11X1")));
+        assert_eq!(solve(&[], "???"), Some(parse_line("XXX")));
+        assert_eq!(solve(&[3], "???"), Some(parse_line("111")));
+        assert_eq!(solve(&[1], "?1??"), Some(parse_line("X1XX")));
+        assert_eq!(solve(&[1, 1], "?????"), Some(parse_line("?????")));
+        assert_eq!(solve(&[2], "??X??"), Some(parse_line("??X??")));
+        assert_eq!(solve(&[3], "?X???"), Some(parse_line("XX111")));
+    }
+
+    #[test]
+    fn test_solve_line_contradiction() {
+        assert_eq!(solve(&[4], "???"), None);
+        assert_eq!(solve(&[2], "1X1"), None);
+        assert_eq!(solve(&[], "?1?"), None);
+        assert_eq!(solve(&[1, 1], "11?"), None);
+    }
+
+    #[test]
+    fn test_solve() {
+        #[rustfmt::skip]
+        let lines = [
+            "1 1 111 1 ",
+            " 1 11 111 ",
+            "1111 11  1",
+            "1 11 1  11",
+            "1  111  11",
+        ];
+        let mut grid = Grid::from_lines(&lines).unwrap();
+
+        assert!(grid.solve());
+
+        // The solution needs to satisfy all clues even if it differs from the picture
+        for y in 0..grid.size.height {
+            assert!(grid.get_row_progress(y).solved);
+        }
+        for x in 0..grid.size.width {
+            assert!(grid.get_column_progress(x).solved);
+        }
+    }
+
+    #[test]
+    fn test_estimate_difficulty() {
+        #[rustfmt::skip]
+        let easy_grid = Grid::from_lines(&[
+            "11111",
+            "1   1",
+            "11111",
+        ]).unwrap();
+        assert_eq!(easy_grid.estimate_difficulty(), Difficulty::Easy);
+
+        #[rustfmt::skip]
+        let medium_grid = Grid::from_lines(&[
+            " 11 11",
+            "11    ",
+            "  11  ",
+            "1  111",
+            "1 1 1 ",
+        ]).unwrap();
+        assert_eq!(medium_grid.estimate_difficulty(), Difficulty::Medium);
+
+        // Two solutions: the diagonal can go either way
+        #[rustfmt::skip]
+        let hard_grid = Grid::from_lines(&[
+            "1 ",
+            " 1",
+        ]).unwrap();
+        assert_eq!(hard_grid.estimate_difficulty(), Difficulty::Hard);
+
+        #[rustfmt::skip]
+        let mut unsolvable_grid = Grid::from_lines(&[
+            "11",
+            "  ",
+        ]).unwrap();
+        unsolvable_grid.vertical_clues_solutions[0].clear();
+        assert_eq!(unsolvable_grid.estimate_difficulty(), Difficulty::Hard);
+    }
+
+    #[test]
+    fn test_get_remaining_difficulty_score() {
+        #[rustfmt::skip]
+        let mut grid = Grid::from_lines(&[
+            "1 ",
+            " 1",
+        ]).unwrap();
+        let get_label = |grid: &Grid| {
+            grid.get_remaining_difficulty_score()
+                .map(get_difficulty_label)
+        };
+
+        // The diagonal can go either way, so no cell can be decided
+        assert_eq!(get_label(&grid), Some("expert"));
+
+        // Filling one cell decides the rest in two rounds
+        *grid.get_mut_cell(Point { x: 0, y: 0 }) = Cell::Filled;
+        assert_eq!(get_label(&grid), Some("easy"));
+
+        *grid.get_mut_cell(Point { x: 1, y: 0 }) = Cell::Filled;
+        assert_eq!(grid.get_remaining_difficulty_score(), None);
+
+        *grid.get_mut_cell(Point { x: 1, y: 0 }) = Cell::Empty;
+        *grid.get_mut_cell(Point { x: 1, y: 1 }) = Cell::Filled;
+        assert_eq!(grid.get_remaining_difficulty_score(), Some(0.0));
+
+        #[rustfmt::skip]
+        let medium_grid = Grid::from_lines(&[
+            " 11 11",
+            "11    ",
+            "  11  ",
+            "1  111",
+            "1 1 1 ",
+        ]).unwrap();
+        let score = medium_grid.get_remaining_difficulty_score().unwrap();
+        assert!(score > 0.0 && score < STUCK_SCORE);
+    }
+
+    #[test]
+    fn test_get_difficulty_label() {
+        assert_eq!(get_difficulty_label(0.1), "trivial");
+        assert_eq!(get_difficulty_label(0.3), "easy");
+        assert_eq!(get_difficulty_label(0.5), "medium");
+        assert_eq!(get_difficulty_label(0.7), "hard");
+        assert_eq!(get_difficulty_label(0.9), "expert");
+    }
+
+    #[test]
+    fn test_difficulty_parse() {
+        for difficulty in [Difficulty::Easy, Difficulty::Medium, Difficulty::Hard] {
+            assert_eq!(Difficulty::parse(difficulty.name()), Some(difficulty));
+        }
+        assert_eq!(Difficulty::parse("impossible"), None);
+    }
+
+    #[test]
+    fn test_count_solutions() {
+        #[rustfmt::skip]
+        let unique_grid = Grid::from_lines(&[
+            "11111",
+            "1   1",
+            "11111",
+        ]).unwrap();
+        assert_eq!(unique_grid.count_solutions(2), 1);
+
+        // Two solutions: the diagonal can go either way
+        #[rustfmt::skip]
+        let ambiguous_grid = Grid::from_lines(&[
+            "1 ",
+            " 1",
+        ]).unwrap();
+        assert_eq!(ambiguous_grid.count_solutions(2), 2);
+        assert_eq!(ambiguous_grid.count_solutions(10), 2);
+        assert_eq!(ambiguous_grid.count_solutions(1), 1);
+        assert_eq!(ambiguous_grid.count_solutions(0), 0);
+
+        // A 4x4 grid with a single filled cell in every line has one solution per permutation
+        let size = Size {
+            width: 4,
+            height: 4,
+        };
+        let mut grid = Grid::from_clues(size, vec![vec![1]; 4], vec![vec![1]; 4]).unwrap();
+        assert_eq!(grid.count_solutions(100), 24);
+        assert_eq!(grid.count_solutions(2), 2);
+
+        grid.vertical_clues_solutions[0].clear();
+        assert_eq!(grid.count_solutions(2), 0);
+        // The cells are left as they are
+        assert!(grid.cells.iter().all(|cell| *cell == Cell::Empty));
+    }
+
+    /// Measures how long checking random grids for uniqueness takes.
+    ///
+    /// Run with `cargo test --release bench_count_solutions -- --ignored --nocapture`.
+    #[test]
+    #[ignore]
+    fn bench_count_solutions() {
+        const GRID_COUNT: u32 = 100;
+
+        for side in [5, 15] {
+            let size = Size {
+                width: side,
+                height: side,
+            };
+            let mut rng = fastrand::Rng::with_seed(42);
+            let grids: Vec<Grid> = (0..GRID_COUNT)
+                .map(|_| Grid::random_with_rng(size, true, &mut rng).unwrap())
+                .collect();
+
+            let starting_time = std::time::Instant::now();
+            let unique_grid_count = grids
+                .iter()
+                .filter(|grid| grid.count_solutions(2) == 1)
+                .count();
+            let elapsed = starting_time.elapsed();
+
+            eprintln!(
+                "{}x{}: {} of {} grids unique in {:?} ({:?} per grid)",
+                side,
+                side,
+                unique_grid_count,
+                GRID_COUNT,
+                elapsed,
+                elapsed / GRID_COUNT
+            );
+        }
+    }
+
+    #[test]
+    fn test_solve_unsolvable() {
+        #[rustfmt::skip]
+        let mut grid = Grid::from_lines(&[
+            "11",
+            "  ",
+        ]).unwrap();
+        grid.vertical_clues_solutions[0].clear();
+
+        assert!(!grid.solve());
+    }
+}
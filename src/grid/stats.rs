@@ -0,0 +1,109 @@
+//! Facts about a grid that can be printed without playing it.
+
+use super::{Clue, Clues, Difficulty, Grid};
+use std::fmt;
+use terminal::util::Size;
+
+#[derive(Debug, PartialEq)]
+pub struct Stats {
+    pub size: Size,
+    /// The amount of cells that are filled in the solution.
+    pub filled_cells: u32,
+    /// The amount of clues of all rows.
+    pub row_clue_groups: usize,
+    /// The amount of clues of all columns.
+    pub column_clue_groups: usize,
+    /// The largest clue, which is the longest run of filled cells.
+    pub longest_run: Clue,
+    pub difficulty: Difficulty,
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let percentage = self.filled_cells as f64 / self.size.product() as f64 * 100.0;
+
+        writeln!(f, "Size: {}x{}", self.size.width, self.size.height)?;
+        writeln!(
+            f,
+            "Filled cells: {} of {} ({:.0}%)",
+            self.filled_cells,
+            self.size.product(),
+            percentage
+        )?;
+        writeln!(
+            f,
+            "Clue groups: {} in rows, {} in columns",
+            self.row_clue_groups, self.column_clue_groups
+        )?;
+        writeln!(f, "Longest run: {}", self.longest_run)?;
+        write!(f, "Estimated difficulty: {}", self.difficulty.name())
+    }
+}
+
+impl Grid {
+    /// Gathers the stats of the grid using its clue solutions, so they are available even if the solution is not known.
+    pub fn get_stats(&self) -> Stats {
+        let clue_count =
+            |clues_solutions: &[Clues]| clues_solutions.iter().map(Vec::len).sum::<usize>();
+
+        Stats {
+            size: self.size,
+            filled_cells: self
+                .horizontal_clues_solutions
+                .iter()
+                .flatten()
+                .map(|clue| *clue as u32)
+                .sum(),
+            row_clue_groups: clue_count(&self.horizontal_clues_solutions),
+            column_clue_groups: clue_count(&self.vertical_clues_solutions),
+            longest_run: self
+                .horizontal_clues_solutions
+                .iter()
+                .chain(&self.vertical_clues_solutions)
+                .flatten()
+                .copied()
+                .max()
+                .unwrap_or(0),
+            difficulty: self.estimate_difficulty(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_stats() {
+        #[rustfmt::skip]
+        let grid = Grid::from_lines(&[
+            "111 ",
+            "1  1",
+        ]).unwrap();
+        let stats = grid.get_stats();
+        assert_eq!(
+            stats,
+            Stats {
+                size: Size {
+                    width: 4,
+                    height: 2,
+                },
+                filled_cells: 5,
+                row_clue_groups: 3,
+                column_clue_groups: 4,
+                longest_run: 3,
+                difficulty: Difficulty::Easy,
+            }
+        );
+        assert_eq!(
+            stats.to_string(),
+            concat!(
+                "Size: 4x2\n",
+                "Filled cells: 5 of 8 (62%)\n",
+                "Clue groups: 3 in rows, 4 in columns\n",
+                "Longest run: 3\n",
+                "Estimated difficulty: easy",
+            )
+        );
+    }
+}
@@ -1 +1,3 @@
+pub mod auto_cross;
 pub mod fill;
+pub mod transform;
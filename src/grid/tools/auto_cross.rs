@@ -0,0 +1,64 @@
+use crate::grid::{Cell, Grid, Line, LineStatus};
+use terminal::util::Point;
+
+/// Crosses out the empty cells of the row and the column through `point` if they are solved.
+///
+/// This doesn't cascade: only filled cells count towards solving a line so crossing out a cell never solves the line across it.
+///
+/// Returns the points of the cells that were crossed out, together with [`Cell::Crossed`].
+pub fn auto_cross(grid: &mut Grid, point: Point) -> Vec<(Point, Cell)> {
+    let mut changes = Vec::<(Point, Cell)>::new();
+
+    for (line, index) in [(Line::Row, point.y), (Line::Column, point.x)] {
+        if grid.get_line_status(&line, index) != LineStatus::Solved {
+            continue;
+        }
+
+        for point in line.get_points(point, grid.size) {
+            let cell = grid.get_mut_cell(point);
+            // Maybed and measured cells are kept because they are notes of the player
+            if *cell == Cell::Empty {
+                *cell = Cell::Crossed;
+                changes.push((point, Cell::Crossed));
+            }
+        }
+    }
+
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_auto_cross() {
+        #[rustfmt::skip]
+        let mut grid = Grid::from_lines(&[
+            "11 ",
+            "  1",
+            "   ",
+        ]).unwrap();
+        *grid.get_mut_cell(Point { x: 0, y: 0 }) = Cell::Filled;
+        *grid.get_mut_cell(Point { x: 2, y: 0 }) = Cell::Maybed;
+
+        // The first row isn't solved yet and the second column only has an empty cell
+        let changes = auto_cross(&mut grid, Point { x: 1, y: 0 });
+        assert!(changes.is_empty());
+
+        *grid.get_mut_cell(Point { x: 1, y: 0 }) = Cell::Filled;
+        let changes = auto_cross(&mut grid, Point { x: 1, y: 0 });
+
+        // The maybed cell of the first row is kept and the second column is crossed out below the filled cell
+        assert_eq!(
+            changes,
+            [
+                (Point { x: 1, y: 1 }, Cell::Crossed),
+                (Point { x: 1, y: 2 }, Cell::Crossed),
+            ]
+        );
+        assert_eq!(grid.get_cell(Point { x: 2, y: 0 }), Cell::Maybed);
+        // The third row is solved too but nothing was placed in it
+        assert_eq!(grid.get_cell(Point { x: 0, y: 2 }), Cell::Empty);
+    }
+}
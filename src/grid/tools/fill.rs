@@ -1,61 +1,258 @@
-use crate::grid::{Cell, Grid};
+use crate::grid::{Cell, Grid, Line};
 use terminal::util::Point;
 
-pub fn fill(grid: &mut Grid, point: Point, first_cell: Cell, fill_cell: Cell) {
-    let cell = grid.get_mut_cell(point);
+/// Checks whether the cell is the same as `first_cell`.
+///
+/// Multiple measured cells are filled as one, regardless of the index.
+fn matches_cell(first_cell: Cell, cell: Cell) -> bool {
+    cell == first_cell
+        || matches!(cell, Cell::Measured(_, _)) && matches!(first_cell, Cell::Measured(_, _))
+}
+
+/// Flood-fills the cells starting at `point` that are the same as `first_cell` with `fill_cell`.
+///
+/// Returns the filled points in the order they were filled, in rings of increasing distance to `point`.
+pub fn fill(grid: &mut Grid, point: Point, first_cell: Cell, fill_cell: Cell) -> Vec<Vec<Point>> {
+    fill_matching(
+        grid,
+        point,
+        |cell| matches_cell(first_cell, cell),
+        fill_cell,
+    )
+}
 
-    // We want to fill multiple measured cells as one, regardless of the index
-    let measured_cell =
-        matches!(*cell, Cell::Measured(_, _)) && matches!(first_cell, Cell::Measured(_, _));
+/// Flood-fills the cells of the target cell kind that are connected to `point` with `fill_cell`,
+/// no matter what the cell at `point` is. That cell is only filled if it is of the target kind too.
+///
+/// This way, for example all maybed cells around a filled cell can be filled.
+pub fn fill_target(
+    grid: &mut Grid,
+    point: Point,
+    target_cell: Cell,
+    fill_cell: Cell,
+) -> Vec<Vec<Point>> {
+    let matches_target_cell = |cell| matches_cell(target_cell, cell);
 
-    if *cell == first_cell || measured_cell {
-        *cell = fill_cell;
-    } else {
-        return;
+    if matches_target_cell(grid.get_cell(point)) {
+        return fill_matching(grid, point, matches_target_cell, fill_cell);
     }
 
+    let mut rings = Vec::<Vec<Point>>::new();
+    for neighbor in get_neighbors(grid, point) {
+        // The fill spreads from every neighbor at the same pace, merging the rings of the same distance
+        let neighbor_rings = fill_matching(grid, neighbor, matches_target_cell, fill_cell);
+        for (index, ring) in neighbor_rings.into_iter().enumerate() {
+            if let Some(existing_ring) = rings.get_mut(index) {
+                existing_ring.extend(ring);
+            } else {
+                rings.push(ring);
+            }
+        }
+    }
+    rings
+}
+
+/// Gets the points of the cells above, below, left and right of `point` that are within the grid.
+fn get_neighbors(grid: &Grid, point: Point) -> Vec<Point> {
+    let mut neighbors = Vec::<Point>::new();
+
     if point.y != 0 {
-        fill(
-            grid,
-            Point {
-                y: point.y - 1,
-                ..point
-            },
-            first_cell,
-            fill_cell,
-        );
+        neighbors.push(Point {
+            y: point.y - 1,
+            ..point
+        });
     }
     if point.y < grid.size.height - 1 {
-        fill(
-            grid,
-            Point {
-                y: point.y + 1,
-                ..point
-            },
-            first_cell,
-            fill_cell,
-        );
+        neighbors.push(Point {
+            y: point.y + 1,
+            ..point
+        });
     }
     if point.x != 0 {
-        fill(
-            grid,
-            Point {
-                x: point.x - 1,
-                ..point
-            },
-            first_cell,
-            fill_cell,
-        );
+        neighbors.push(Point {
+            x: point.x - 1,
+            ..point
+        });
     }
     if point.x < grid.size.width - 1 {
-        fill(
-            grid,
-            Point {
-                x: point.x + 1,
-                ..point
-            },
-            first_cell,
-            fill_cell,
+        neighbors.push(Point {
+            x: point.x + 1,
+            ..point
+        });
+    }
+
+    neighbors
+}
+
+/// Flood-fills the cells starting at `point` that match with `fill_cell`.
+///
+/// Returns the filled points in the order they were filled, in rings of increasing distance to `point`.
+fn fill_matching(
+    grid: &mut Grid,
+    point: Point,
+    matches: impl Fn(Cell) -> bool,
+    fill_cell: Cell,
+) -> Vec<Vec<Point>> {
+    // Filled cells would match again
+    if matches(fill_cell) {
+        return Vec::new();
+    }
+
+    let mut rings = Vec::<Vec<Point>>::new();
+    let mut ring = vec![point];
+
+    while !ring.is_empty() {
+        let mut filled_points = Vec::<Point>::new();
+        let mut next_ring = Vec::<Point>::new();
+
+        for point in ring {
+            let cell = grid.get_mut_cell(point);
+            if !matches(*cell) {
+                // Either it doesn't belong to the area or it was already filled as part of this ring
+                continue;
+            }
+            *cell = fill_cell;
+            filled_points.push(point);
+
+            next_ring.extend(get_neighbors(grid, point));
+        }
+
+        if !filled_points.is_empty() {
+            rings.push(filled_points);
+        }
+        ring = next_ring;
+    }
+
+    rings
+}
+
+/// Sets all cells of the row or column through `point` to `fill_cell`.
+///
+/// Returns the points of the cells that changed, together with `fill_cell`.
+pub fn fill_line(
+    grid: &mut Grid,
+    point: Point,
+    line: &Line,
+    fill_cell: Cell,
+) -> Vec<(Point, Cell)> {
+    let mut changes = Vec::<(Point, Cell)>::new();
+
+    for point in line.get_points(point, grid.size) {
+        let cell = grid.get_mut_cell(point);
+        if *cell != fill_cell {
+            *cell = fill_cell;
+            changes.push((point, fill_cell));
+        }
+    }
+
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fill() {
+        #[rustfmt::skip]
+        let mut grid = Grid::from_lines(&[
+            "    ",
+            "    ",
+            "    ",
+        ]).unwrap();
+        *grid.get_mut_cell(Point { x: 1, y: 0 }) = Cell::Crossed;
+        *grid.get_mut_cell(Point { x: 1, y: 1 }) = Cell::Crossed;
+
+        let rings = fill(&mut grid, Point { x: 0, y: 0 }, Cell::Empty, Cell::Filled);
+
+        assert_eq!(
+            rings,
+            [
+                vec![Point { x: 0, y: 0 }],
+                vec![Point { x: 0, y: 1 }],
+                vec![Point { x: 0, y: 2 }],
+                vec![Point { x: 1, y: 2 }],
+                vec![Point { x: 2, y: 2 }],
+                vec![Point { x: 2, y: 1 }, Point { x: 3, y: 2 }],
+                vec![Point { x: 2, y: 0 }, Point { x: 3, y: 1 }],
+                vec![Point { x: 3, y: 0 }],
+            ]
+        );
+        assert_eq!(
+            grid.cells
+                .iter()
+                .filter(|cell| **cell == Cell::Filled)
+                .count(),
+            10
+        );
+        assert_eq!(grid.get_cell(Point { x: 1, y: 0 }), Cell::Crossed);
+    }
+
+    #[test]
+    fn test_fill_target() {
+        #[rustfmt::skip]
+        let mut grid = Grid::from_lines(&[
+            "    ",
+            "    ",
+        ]).unwrap();
+        *grid.get_mut_cell(Point { x: 0, y: 0 }) = Cell::Maybed;
+        *grid.get_mut_cell(Point { x: 1, y: 0 }) = Cell::Maybed;
+        *grid.get_mut_cell(Point { x: 2, y: 0 }) = Cell::Crossed;
+        *grid.get_mut_cell(Point { x: 3, y: 0 }) = Cell::Maybed;
+        *grid.get_mut_cell(Point { x: 0, y: 1 }) = Cell::Filled;
+
+        // Starting at the filled cell, which stays as it is
+        let rings = fill_target(&mut grid, Point { x: 0, y: 1 }, Cell::Maybed, Cell::Filled);
+
+        assert_eq!(
+            rings,
+            [vec![Point { x: 0, y: 0 }], vec![Point { x: 1, y: 0 }]]
+        );
+        #[rustfmt::skip]
+        assert_eq!(
+            grid.cells,
+            [
+                Cell::Filled, Cell::Filled, Cell::Crossed, Cell::Maybed,
+                Cell::Filled, Cell::Empty, Cell::Empty, Cell::Empty,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fill_same_cell() {
+        #[rustfmt::skip]
+        let mut grid = Grid::from_lines(&[
+            "  ",
+        ]).unwrap();
+
+        assert!(fill(&mut grid, Point { x: 0, y: 0 }, Cell::Empty, Cell::Empty).is_empty());
+    }
+
+    #[test]
+    fn test_fill_line() {
+        #[rustfmt::skip]
+        let mut grid = Grid::from_lines(&[
+            "   ",
+            "   ",
+        ]).unwrap();
+        *grid.get_mut_cell(Point { x: 0, y: 1 }) = Cell::Filled;
+        *grid.get_mut_cell(Point { x: 2, y: 1 }) = Cell::Crossed;
+
+        let changes = fill_line(&mut grid, Point { x: 1, y: 1 }, &Line::Row, Cell::Filled);
+        // The cell that was already filled didn't change
+        assert_eq!(
+            changes,
+            [
+                (Point { x: 1, y: 1 }, Cell::Filled),
+                (Point { x: 2, y: 1 }, Cell::Filled)
+            ]
         );
+        assert_eq!(&grid.cells[3..], [Cell::Filled; 3]);
+
+        let changes = fill_line(&mut grid, Point { x: 2, y: 0 }, &Line::Column, Cell::Filled);
+        assert_eq!(changes, [(Point { x: 2, y: 0 }, Cell::Filled)]);
+        assert_eq!(&grid.cells[..3], [Cell::Empty, Cell::Empty, Cell::Filled]);
+
+        assert!(fill_line(&mut grid, Point { x: 0, y: 1 }, &Line::Row, Cell::Filled).is_empty());
     }
 }
@@ -0,0 +1,208 @@
+use crate::grid::{get_index, get_max_clues_size, Cell, Clues, Grid, LineCache};
+use terminal::util::{Point, Size};
+
+/// Mirrors or rotates the whole grid, which is useful for designing symmetric grids in the editor.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Transform {
+    /// Swaps the left and the right.
+    MirrorHorizontally,
+    /// Swaps the top and the bottom.
+    MirrorVertically,
+    RotateClockwise,
+    RotateCounterclockwise,
+}
+
+impl Transform {
+    /// Gets the transform that undoes this one.
+    pub const fn inverse(self) -> Self {
+        match self {
+            Self::MirrorHorizontally | Self::MirrorVertically => self,
+            Self::RotateClockwise => Self::RotateCounterclockwise,
+            Self::RotateCounterclockwise => Self::RotateClockwise,
+        }
+    }
+
+    /// Checks whether the width and height are swapped by this transform.
+    pub const fn swaps_size(self) -> bool {
+        matches!(self, Self::RotateClockwise | Self::RotateCounterclockwise)
+    }
+
+    /// Gets the size of a grid of the given size after this transform.
+    const fn get_size(self, size: Size) -> Size {
+        if self.swaps_size() {
+            Size {
+                width: size.height,
+                height: size.width,
+            }
+        } else {
+            size
+        }
+    }
+
+    /// Gets the point that the cell at the point after this transform was at before it, in a grid of the given size.
+    const fn get_source_point(self, size: Size, point: Point) -> Point {
+        match self {
+            Self::MirrorHorizontally => Point {
+                x: size.width - 1 - point.x,
+                y: point.y,
+            },
+            Self::MirrorVertically => Point {
+                x: point.x,
+                y: size.height - 1 - point.y,
+            },
+            Self::RotateClockwise => Point {
+                x: point.y,
+                y: size.height - 1 - point.x,
+            },
+            Self::RotateCounterclockwise => Point {
+                x: size.width - 1 - point.y,
+                y: point.x,
+            },
+        }
+    }
+
+    /// Transforms the cells of a grid of the given size.
+    fn transform_cells(self, size: Size, cells: &[Cell]) -> Vec<Cell> {
+        let transformed_size = self.get_size(size);
+
+        (0..transformed_size.height)
+            .flat_map(|y| (0..transformed_size.width).map(move |x| Point { x, y }))
+            .map(|point| cells[get_index(size.width, self.get_source_point(size, point))])
+            .collect()
+    }
+
+    /// Transforms the clue solutions of the rows and the columns, returning them in that order.
+    fn transform_clues_solutions(
+        self,
+        horizontal_clues_solutions: &[Clues],
+        vertical_clues_solutions: &[Clues],
+    ) -> (Vec<Clues>, Vec<Clues>) {
+        fn reversed(clues_solutions: &[Clues]) -> Vec<Clues> {
+            clues_solutions.iter().rev().cloned().collect()
+        }
+        fn each_reversed<'a>(clues_solutions: impl Iterator<Item = &'a Clues>) -> Vec<Clues> {
+            clues_solutions
+                .map(|clues_solution| clues_solution.iter().rev().copied().collect())
+                .collect()
+        }
+
+        match self {
+            Self::MirrorHorizontally => (
+                each_reversed(horizontal_clues_solutions.iter()),
+                reversed(vertical_clues_solutions),
+            ),
+            Self::MirrorVertically => (
+                reversed(horizontal_clues_solutions),
+                each_reversed(vertical_clues_solutions.iter()),
+            ),
+            // The columns become the rows, read from the bottom, and the rows from the bottom become the columns
+            Self::RotateClockwise => (
+                each_reversed(vertical_clues_solutions.iter()),
+                reversed(horizontal_clues_solutions),
+            ),
+            // The columns from the right become the rows and the rows become the columns, read from the right
+            Self::RotateCounterclockwise => (
+                reversed(vertical_clues_solutions),
+                each_reversed(horizontal_clues_solutions.iter()),
+            ),
+        }
+    }
+}
+
+impl Grid {
+    /// Transforms only the cells, changing the size along with them.
+    pub(crate) fn transform_cells(&mut self, transform: Transform) {
+        self.cells = transform.transform_cells(self.size, &self.cells);
+        self.size = transform.get_size(self.size);
+        self.row_caches = vec![LineCache::default(); self.size.height as usize];
+        self.column_caches = vec![LineCache::default(); self.size.width as usize];
+    }
+
+    /// Transforms the cells, the clues and the solution.
+    pub fn transform(&mut self, transform: Transform) {
+        if let Some(solution) = &self.solution {
+            self.solution = Some(transform.transform_cells(self.size, solution));
+        }
+        self.transform_cells(transform);
+
+        let (horizontal_clues_solutions, vertical_clues_solutions) = transform
+            .transform_clues_solutions(
+                &self.horizontal_clues_solutions,
+                &self.vertical_clues_solutions,
+            );
+        self.horizontal_clues_solutions = horizontal_clues_solutions;
+        self.vertical_clues_solutions = vertical_clues_solutions;
+        self.max_clues_size = get_max_clues_size(
+            &self.horizontal_clues_solutions,
+            &self.vertical_clues_solutions,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[rustfmt::skip]
+    const LINES: [&str; 2] = [
+        "111",
+        "1  ",
+    ];
+
+    /// Checks that transforming the grid made from the lines gives the same grid as the one made from the transformed lines.
+    fn assert_transform(transform: Transform, transformed_lines: &[&str]) {
+        let mut grid = Grid::from_lines(&LINES).unwrap();
+        *grid.get_mut_cell(Point { x: 2, y: 0 }) = Cell::Crossed;
+        grid.transform(transform);
+
+        let expected_grid = Grid::from_lines(transformed_lines).unwrap();
+        assert_eq!(grid.size, expected_grid.size);
+        assert_eq!(
+            grid.horizontal_clues_solutions,
+            expected_grid.horizontal_clues_solutions
+        );
+        assert_eq!(
+            grid.vertical_clues_solutions,
+            expected_grid.vertical_clues_solutions
+        );
+        assert_eq!(grid.max_clues_size, expected_grid.max_clues_size);
+        assert_eq!(grid.solution, expected_grid.solution);
+        assert_eq!(
+            grid.cells
+                .iter()
+                .filter(|cell| **cell == Cell::Crossed)
+                .count(),
+            1
+        );
+
+        grid.transform(transform.inverse());
+        assert_eq!(grid.size, Grid::from_lines(&LINES).unwrap().size);
+        assert_eq!(grid.get_cell(Point { x: 2, y: 0 }), Cell::Crossed);
+    }
+
+    #[test]
+    fn test_transform() {
+        #[rustfmt::skip]
+        assert_transform(Transform::MirrorHorizontally, &[
+            "111",
+            "  1",
+        ]);
+        #[rustfmt::skip]
+        assert_transform(Transform::MirrorVertically, &[
+            "1  ",
+            "111",
+        ]);
+        #[rustfmt::skip]
+        assert_transform(Transform::RotateClockwise, &[
+            "11",
+            " 1",
+            " 1",
+        ]);
+        #[rustfmt::skip]
+        assert_transform(Transform::RotateCounterclockwise, &[
+            "1 ",
+            "1 ",
+            "11",
+        ]);
+    }
+}
@@ -0,0 +1,384 @@
+//! Converts images into grids by scaling them down and turning dark pixels into filled cells.
+//!
+//! Supported are the Netpbm formats PGM and PPM, both in their plain and raw variants, as well as farbfeld.
+//! To support another format, add a decoding function that produces a [`GrayImage`] and detect the format in [`decode`].
+
+use crate::grid::{Grid, GridError};
+
+/// An image with a brightness from 0 (black) to 255 (white) for each pixel.
+#[derive(Debug, PartialEq)]
+pub struct GrayImage {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<u8>,
+}
+
+/// Converts a color to its perceived brightness.
+fn get_luminance(red: u8, green: u8, blue: u8) -> u8 {
+    ((red as u32 * 299 + green as u32 * 587 + blue as u32 * 114) / 1000) as u8
+}
+
+/// Decodes the image, detecting the format by its first bytes.
+pub fn decode(bytes: &[u8]) -> Result<GrayImage, &'static str> {
+    match bytes {
+        [b'P', format @ (b'2' | b'3' | b'5' | b'6'), ..] => decode_netpbm(*format, &bytes[2..]),
+        _ if bytes.starts_with(FARBFELD_MAGIC) => decode_farbfeld(&bytes[FARBFELD_MAGIC.len()..]),
+        _ => Err("Unsupported image format"),
+    }
+}
+
+/// Decodes the Netpbm image that follows the magic number.
+fn decode_netpbm(format: u8, mut bytes: &[u8]) -> Result<GrayImage, &'static str> {
+    const INVALID: &str = "Invalid Netpbm image";
+
+    /// Reads the next number, skipping whitespace and comments.
+    fn read_number(bytes: &mut &[u8]) -> Option<usize> {
+        loop {
+            match bytes.first()? {
+                byte if byte.is_ascii_whitespace() => *bytes = &bytes[1..],
+                b'#' => {
+                    let line_end = bytes.iter().position(|byte| *byte == b'\n')?;
+                    *bytes = &bytes[line_end..];
+                }
+                _ => break,
+            }
+        }
+
+        let digits = bytes
+            .iter()
+            .take_while(|byte| byte.is_ascii_digit())
+            .count();
+        let number = std::str::from_utf8(&bytes[..digits]).ok()?.parse().ok()?;
+        *bytes = &bytes[digits..];
+        Some(number)
+    }
+
+    let width = read_number(&mut bytes).ok_or(INVALID)?;
+    let height = read_number(&mut bytes).ok_or(INVALID)?;
+    let max_value = read_number(&mut bytes).ok_or(INVALID)?;
+    if width == 0 || height == 0 || !(1..=u16::MAX as usize).contains(&max_value) {
+        return Err(INVALID);
+    }
+
+    let plain = matches!(format, b'2' | b'3');
+    let channels = if matches!(format, b'2' | b'5') { 1 } else { 3 };
+    let sample_count = width
+        .checked_mul(height)
+        .and_then(|pixel_count| pixel_count.checked_mul(channels))
+        .ok_or(INVALID)?;
+
+    let samples: Vec<usize> = if plain {
+        (0..sample_count)
+            .map(|_| read_number(&mut bytes))
+            .collect::<Option<_>>()
+            .ok_or(INVALID)?
+    } else {
+        // A single whitespace character separates the header from the samples
+        let bytes = bytes.get(1..).ok_or(INVALID)?;
+        let sample_size = if max_value > u8::MAX as usize { 2 } else { 1 };
+        let samples = bytes
+            .get(..sample_count * sample_size)
+            .ok_or(INVALID)?
+            .chunks(sample_size)
+            .map(|sample| {
+                sample
+                    .iter()
+                    .fold(0, |value, byte| value << 8 | *byte as usize)
+            })
+            .collect();
+        samples
+    };
+
+    let to_byte = |sample: usize| (sample.min(max_value) * u8::MAX as usize / max_value) as u8;
+    let pixels = samples
+        .chunks(channels)
+        .map(|pixel| match pixel {
+            [gray] => to_byte(*gray),
+            [red, green, blue] => get_luminance(to_byte(*red), to_byte(*green), to_byte(*blue)),
+            _ => unreachable!(),
+        })
+        .collect();
+
+    Ok(GrayImage {
+        width,
+        height,
+        pixels,
+    })
+}
+
+const FARBFELD_MAGIC: &[u8] = b"farbfeld";
+
+/// Decodes the farbfeld image that follows the magic value.
+fn decode_farbfeld(bytes: &[u8]) -> Result<GrayImage, &'static str> {
+    const INVALID: &str = "Invalid farbfeld image";
+
+    let read_u32 = |index: usize| -> Option<usize> {
+        let bytes = bytes.get(index..index + 4)?;
+        Some(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize)
+    };
+    let width = read_u32(0).ok_or(INVALID)?;
+    let height = read_u32(4).ok_or(INVALID)?;
+    if width == 0 || height == 0 {
+        return Err(INVALID);
+    }
+
+    // Every pixel consists of 16-bit red, green, blue and alpha values
+    let data_end = width
+        .checked_mul(height)
+        .and_then(|pixel_count| pixel_count.checked_mul(8))
+        .and_then(|data_len| data_len.checked_add(8))
+        .ok_or(INVALID)?;
+    let data = bytes.get(8..data_end).ok_or(INVALID)?;
+
+    let pixels = data
+        .chunks(8)
+        .map(|pixel| {
+            // Only the most significant byte of each value matters here
+            let (red, green, blue, alpha) = (pixel[0], pixel[2], pixel[4], pixel[6]);
+            let luminance = get_luminance(red, green, blue) as u32;
+            // Transparent pixels are considered white
+            (luminance * alpha as u32 / 255 + (255 - alpha as u32)) as u8
+        })
+        .collect();
+
+    Ok(GrayImage {
+        width,
+        height,
+        pixels,
+    })
+}
+
+impl GrayImage {
+    /// Scales the image to the given size by averaging the pixels that end up in the same place.
+    pub fn scale(&self, width: usize, height: usize) -> GrayImage {
+        /// Gets the range of source pixels that a target pixel covers, which is never empty.
+        fn get_range(index: usize, len: usize, source_len: usize) -> std::ops::Range<usize> {
+            let start = index * source_len / len;
+            let end = ((index + 1) * source_len / len).max(start + 1);
+            start..end
+        }
+
+        let mut pixels = Vec::<u8>::with_capacity(width * height);
+        for y in 0..height {
+            let y_range = get_range(y, height, self.height);
+            for x in 0..width {
+                let x_range = get_range(x, width, self.width);
+
+                let mut sum = 0;
+                for source_y in y_range.clone() {
+                    let row = &self.pixels[source_y * self.width..][..self.width];
+                    sum += row[x_range.clone()]
+                        .iter()
+                        .map(|pixel| *pixel as usize)
+                        .sum::<usize>();
+                }
+                pixels.push((sum / (y_range.len() * x_range.len())) as u8);
+            }
+        }
+
+        GrayImage {
+            width,
+            height,
+            pixels,
+        }
+    }
+}
+
+/// Finds the brightness that best separates the dark from the bright pixels using [Otsu's method](https://en.wikipedia.org/wiki/Otsu%27s_method).
+pub fn get_otsu_threshold(pixels: &[u8]) -> u8 {
+    let mut histogram = [0usize; 256];
+    for pixel in pixels {
+        histogram[*pixel as usize] += 1;
+    }
+
+    let total = pixels.len() as f64;
+    let total_sum: f64 = histogram
+        .iter()
+        .enumerate()
+        .map(|(brightness, count)| (brightness * count) as f64)
+        .sum();
+
+    let mut best_threshold = 0;
+    let mut best_variance = 0.0;
+    let mut dark_count = 0.0;
+    let mut dark_sum = 0.0;
+    for (brightness, count) in histogram.iter().enumerate() {
+        dark_count += *count as f64;
+        dark_sum += (brightness * count) as f64;
+
+        let bright_count = total - dark_count;
+        if dark_count == 0.0 || bright_count == 0.0 {
+            continue;
+        }
+
+        let dark_mean = dark_sum / dark_count;
+        let bright_mean = (total_sum - dark_sum) / bright_count;
+        let variance = dark_count * bright_count * (dark_mean - bright_mean).powi(2);
+        if variance > best_variance {
+            best_variance = variance;
+            best_threshold = brightness;
+        }
+    }
+
+    best_threshold as u8
+}
+
+/// Converts the image to a grid of the given width with the height following the aspect ratio, at most `max_height`.
+///
+/// Pixels at most as bright as the threshold become filled cells. Without a threshold, one is determined automatically.
+pub fn image_to_grid(
+    image: &GrayImage,
+    width: u16,
+    max_height: u16,
+    threshold: Option<u8>,
+) -> Result<Grid, GridError> {
+    // Cells are about as wide as they are high
+    let height = ((image.height * width as usize + image.width / 2) / image.width)
+        .clamp(1, max_height as usize) as u16;
+
+    let scaled_image = image.scale(width as usize, height as usize);
+    let threshold = threshold.unwrap_or_else(|| get_otsu_threshold(&scaled_image.pixels));
+
+    let bits: Vec<u8> = scaled_image
+        .pixels
+        .iter()
+        .map(|pixel| (*pixel <= threshold) as u8)
+        .collect();
+
+    Grid::from_bit_matrix(&bits, width as usize)
+}
+
+/// Checks whether most rows and columns of the grid are either completely filled or empty,
+/// which makes the grid trivial and usually means that the threshold does not fit the image.
+pub fn is_degenerate(grid: &Grid) -> bool {
+    let is_uniform = |clues: &Vec<u16>, len: u16| clues.is_empty() || clues[..] == [len];
+
+    let uniform_rows = grid
+        .horizontal_clues_solutions
+        .iter()
+        .filter(|clues| is_uniform(clues, grid.size.width))
+        .count();
+    let uniform_columns = grid
+        .vertical_clues_solutions
+        .iter()
+        .filter(|clues| is_uniform(clues, grid.size.height))
+        .count();
+
+    (uniform_rows + uniform_columns) * 2 > (grid.size.width + grid.size.height) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use terminal::util::Size;
+
+    fn get_image() -> GrayImage {
+        GrayImage {
+            width: 4,
+            height: 2,
+            pixels: vec![0, 0, 255, 255, 0, 100, 200, 255],
+        }
+    }
+
+    #[test]
+    fn test_decode_pgm() {
+        let plain = b"P2\n# A comment\n4 2\n255\n0 0 255 255\n0 100 200 255\n";
+        assert_eq!(decode(plain), Ok(get_image()));
+
+        let mut raw = b"P5 4 2 255\n".to_vec();
+        raw.extend([0, 0, 255, 255, 0, 100, 200, 255]);
+        assert_eq!(decode(&raw), Ok(get_image()));
+
+        // 16-bit samples
+        let mut raw = b"P5 1 1 65535\n".to_vec();
+        raw.extend([0xff, 0xff]);
+        assert_eq!(decode(&raw).unwrap().pixels, [255]);
+    }
+
+    #[test]
+    fn test_decode_ppm() {
+        let plain = b"P3 2 1 255 255 255 255 255 0 0";
+        assert_eq!(decode(plain).unwrap().pixels, [255, 76]);
+    }
+
+    #[test]
+    fn test_decode_farbfeld() {
+        let mut farbfeld = b"farbfeld".to_vec();
+        farbfeld.extend(2u32.to_be_bytes());
+        farbfeld.extend(1u32.to_be_bytes());
+        // An opaque black pixel and a transparent black pixel
+        farbfeld.extend([0, 0, 0, 0, 0, 0, 0xff, 0xff]);
+        farbfeld.extend([0, 0, 0, 0, 0, 0, 0, 0]);
+
+        assert_eq!(decode(&farbfeld).unwrap().pixels, [0, 255]);
+    }
+
+    #[test]
+    fn test_decode_invalid() {
+        assert!(decode(b"\x89PNG").is_err());
+        assert!(decode(b"P2 4 2 255 0 0").is_err());
+        assert!(decode(b"P5 4 2 255\n\0").is_err());
+        assert!(decode(b"P2 0 2 255").is_err());
+        assert!(decode(b"farbfeld\0\0\0\x01").is_err());
+    }
+
+    #[test]
+    fn test_scale() {
+        let image = get_image();
+
+        assert_eq!(image.scale(2, 1).pixels, [25, 241]);
+        assert_eq!(image.scale(4, 2), image);
+        assert_eq!(image.scale(8, 2).pixels[..4], [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_get_otsu_threshold() {
+        let threshold = get_otsu_threshold(&[10, 20, 30, 200, 210, 220]);
+        assert!((30..200).contains(&threshold));
+    }
+
+    #[test]
+    fn test_image_to_grid() {
+        let image = get_image();
+
+        let grid = image_to_grid(&image, 4, 99, None).unwrap();
+        assert_eq!(
+            grid.size,
+            Size {
+                width: 4,
+                height: 2
+            }
+        );
+        assert_eq!(grid.horizontal_clues_solutions, [vec![2], vec![2]]);
+
+        let grid = image_to_grid(&image, 4, 99, Some(0)).unwrap();
+        assert_eq!(grid.horizontal_clues_solutions, [vec![2], vec![1]]);
+
+        // The height is capped
+        let grid = image_to_grid(&image, 40, 10, None).unwrap();
+        assert_eq!(grid.size.height, 10);
+    }
+
+    #[test]
+    fn test_is_degenerate() {
+        #[rustfmt::skip]
+        let grid = Grid::from_lines(&[
+            "1 1",
+            " 1 ",
+            "11 ",
+        ]).unwrap();
+        assert!(!is_degenerate(&grid));
+
+        #[rustfmt::skip]
+        let grid = Grid::from_lines(&[
+            "111",
+            "111",
+            "   ",
+        ]).unwrap();
+        assert!(!is_degenerate(&grid));
+
+        assert!(is_degenerate(
+            &image_to_grid(&get_image(), 4, 99, Some(255)).unwrap()
+        ));
+    }
+}
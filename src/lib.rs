@@ -0,0 +1,1164 @@
+//! Play nonograms/picross in your terminal.
+//!
+//! Apart from the game itself, grids, their clues and the solver can be used without a terminal:
+//!
+//! ```
+//! use yayagram::{
+//!     grid::{Cell, Grid},
+//!     Size,
+//! };
+//!
+//! let size = Size {
+//!     width: 2,
+//!     height: 2,
+//! };
+//! let cells = vec![Cell::Filled, Cell::Empty, Cell::Filled, Cell::Filled];
+//! let mut grid = Grid::new(size, cells.clone()).unwrap();
+//!
+//! assert_eq!(grid.horizontal_clues_solutions, [vec![1], vec![2]]);
+//! assert!(grid.solve());
+//! assert_eq!(grid.cells, cells);
+//! ```
+
+mod args;
+mod bell;
+mod bot;
+mod config;
+#[cfg(debug_assertions)]
+mod debug;
+mod editor;
+mod event;
+pub mod grid;
+mod image_import;
+mod mode;
+mod records;
+pub mod source;
+mod tutorial;
+mod undo_redo_buffer;
+mod util;
+mod window_title;
+
+use config::Settings;
+use event::{
+    source::{EventSource, Stats},
+    AlertSeverity, State,
+};
+use grid::{
+    builder::Builder,
+    palette::{ColorSupport, Palette},
+    Cell, Grid, GridError,
+};
+use mode::Mode;
+use records::Records;
+use source::{
+    DirectoryPuzzleSource, FilePuzzleSource, PuzzleQueue, PuzzleSource, RandomPuzzleSource,
+    SequentialPuzzleSource,
+};
+use std::{
+    borrow::Cow,
+    cmp, fs,
+    io::{self, Read},
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+pub use terminal::util::{Point, Size};
+use terminal::{event::Key, util::Color, Terminal};
+use tutorial::TutorialState;
+use window_title::WindowTitle;
+
+// Wishlist:
+// - A main menu
+// - Currently whole clue rows are grayed out once all cells for those clues have been solved
+//  Make them gray out individually. (Maybe itertools' `pad_using` is helpful)
+// - Ability to save best times to the records file and determine new records with that
+// - Ability to continue after solving the puzzle/ability to play it again
+
+/// Runs the game as specified by the command line arguments.
+pub fn run() -> Result<(), Cow<'static, str>> {
+    let (arg, options) = args::parse()?;
+
+    let config_path = options
+        .config_path
+        .as_ref()
+        .map(PathBuf::from)
+        .or_else(config::get_default_path);
+    let (mut settings, config_errors) = if let Some(config_path) = &config_path {
+        config::load(config_path, options.config_path.is_some())?
+    } else {
+        (Settings::default(), Vec::new())
+    };
+    settings.apply(&options);
+
+    if options.bot {
+        // The bot plays with the mouse and shouldn't wait for animations
+        settings.mouse = true;
+        settings.animate_fill = false;
+    }
+
+    if options.write_config {
+        let config_path = config_path.ok_or("No config directory found; use --config")?;
+        config::write(&config_path, &settings)?;
+        println!("Settings written to {}", config_path.display());
+
+        return Ok(());
+    }
+
+    if let Some(path) = &options.export_svg_path {
+        return export_svg(arg, path);
+    }
+
+    // Shown when the first grid is played
+    let mut initial_alert_message: Option<Cow<'static, str>> = match config_errors.as_slice() {
+        [] => None,
+        [error] => Some(error.clone().into()),
+        [error, rest @ ..] => Some(format!("{} (and {} more)", error, rest.len()).into()),
+    };
+
+    let source = match arg {
+        Some(args::Arg::Help) => {
+            println!(concat!(
+                "Play nonograms/picross in your terminal.\n",
+                "For command line arguments please visit <https://github.com/r00ster91/yayagram#command-line-arguments>."
+            ));
+
+            return Ok(());
+        }
+        Some(args::Arg::Stats(path)) => {
+            println!("{}", get_stats(&path)?);
+
+            return Ok(());
+        }
+        Some(args::Arg::Version) => {
+            let version = env!("CARGO_PKG_VERSION");
+
+            println!("{version}");
+
+            return Ok(());
+        }
+        _ if options.image_path.is_some() => {
+            let path = options.image_path.clone().unwrap_or_default();
+            let grid = import_image(&path, &options)?;
+
+            if image_import::is_degenerate(&grid) {
+                initial_alert_message =
+                    Some("Grid is mostly uniform; try a different --threshold".into());
+            }
+
+            Box::new(FilePuzzleSource::new(path, vec![grid]))
+        }
+        arg => get_puzzle_source(arg, &options, &settings)?,
+    };
+    let source: Box<dyn PuzzleSource> = if options.tutorial {
+        Box::new(SequentialPuzzleSource::new(vec![
+            Box::new(FilePuzzleSource::new(
+                tutorial::NAME.into(),
+                vec![tutorial::get_grid()],
+            )),
+            source,
+        ]))
+    } else {
+        source
+    };
+
+    let stdout = io::stdout();
+    match get_terminal(stdout.lock(), &settings) {
+        Ok(mut terminal) if options.bot => {
+            let mut queue = PuzzleQueue::new(source);
+            let result = match queue.next_grid() {
+                Some(grid) => run_bot(&mut terminal, grid, &settings),
+                None => Err("No grid to play".into()),
+            };
+
+            terminal.deinitialize();
+
+            let report = result?;
+            println!("{}", report);
+        }
+        Ok(mut terminal) => {
+            let mut queue = PuzzleQueue::new(source);
+            let mut tutorial = options.tutorial;
+
+            let records_path = records::get_default_path();
+            let mut records = records_path
+                .as_deref()
+                .map(records::load)
+                .unwrap_or_default();
+
+            while let Some(grid) = queue.next_grid() {
+                let skipped = queue.take_skipped();
+                let alert_message = if skipped.is_empty() {
+                    initial_alert_message.take()
+                } else {
+                    Some(format!("Skipped {}", skipped.join(", ")).into())
+                };
+
+                let previous_records = records;
+                let next_action = start_game(
+                    &mut terminal,
+                    grid,
+                    &settings,
+                    GameOptions {
+                        // The tutorial grid is played, not edited
+                        editor_toggled: options.editor && !tutorial,
+                        has_next_grid: queue.has_next_grid(),
+                        alert_message,
+                        tutorial,
+                        // Neither the tutorial nor edited grids count towards the records
+                        records: (!tutorial && !options.editor).then_some(&mut records),
+                    },
+                );
+
+                if let Some(records_path) = &records_path {
+                    if records != previous_records && records::save(records_path, records).is_err()
+                    {
+                        initial_alert_message = Some("Records saving error".into());
+                    }
+                }
+
+                if tutorial && matches!(next_action, NextAction::Next) {
+                    initial_alert_message = Some(TutorialState::Completed.get_message().into());
+                }
+                tutorial = false;
+
+                match next_action {
+                    NextAction::Next => {}
+                    NextAction::Restart(grid) => queue.restart(*grid),
+                    NextAction::Exit => break,
+                }
+
+                terminal.clear();
+            }
+
+            terminal.deinitialize();
+        }
+        Err(err) => {
+            return Err(err.into());
+        }
+    }
+
+    Ok(())
+}
+
+/// What to do after a game ended.
+pub(crate) enum NextAction {
+    /// Play the next grid of the source.
+    Next,
+    /// Play the given grid before the next grid of the source, for example because it was loaded or resized to.
+    Restart(Box<Grid>),
+    /// Exit the program.
+    Exit,
+}
+
+/// How a single grid is played.
+pub(crate) struct GameOptions<'a> {
+    /// Whether the game starts in the editor.
+    pub editor_toggled: bool,
+    /// Whether the end screen offers to play the next grid.
+    pub has_next_grid: bool,
+    /// The warning that is shown when the game starts, if any.
+    pub alert_message: Option<Cow<'static, str>>,
+    /// Whether the steps of the tutorial are shown.
+    pub tutorial: bool,
+    /// If present, these are updated once the grid is solved or given up on.
+    pub records: Option<&'a mut Records>,
+}
+
+/// Plays a single grid and returns what to do next.
+pub(crate) fn start_game(
+    terminal: &mut Terminal,
+    grid: Grid,
+    settings: &Settings,
+    game_options: GameOptions,
+) -> NextAction {
+    let GameOptions {
+        editor_toggled,
+        has_next_grid,
+        alert_message,
+        tutorial,
+        mut records,
+    } = game_options;
+
+    if let State::Exit(_) =
+        event::input::window::await_fitting_size(terminal, &grid, settings.legend, None)
+    {
+        return NextAction::Exit;
+    }
+
+    let mut builder = Builder::new(terminal, grid, get_palette(settings), settings);
+
+    let all_clues_solved = builder.draw_all(terminal);
+    draw_basic_controls_help(terminal, &builder);
+    builder.refresh_difficulty_label(terminal, editor_toggled);
+
+    // Grids are never solved while editing them
+    let wants_next_grid = if all_clues_solved && !editor_toggled {
+        // Otherwise the event loop sets the title
+        WindowTitle::default().set(terminal, &mut builder, editor_toggled, false);
+
+        solved_screen(
+            terminal,
+            &builder,
+            Completion::Immediate,
+            has_next_grid,
+            None,
+        )
+    } else {
+        terminal.flush();
+
+        let state = event::r#loop(
+            terminal,
+            &mut builder,
+            settings,
+            editor_toggled,
+            alert_message,
+            tutorial,
+            &mut EventSource::Terminal,
+        );
+
+        if settings.mode.get_time_limit(builder.grid.size).is_some() {
+            clear_countdown(terminal, &builder);
+        }
+
+        if let Some(records) = records.as_deref_mut() {
+            update_records(records, &state, &builder);
+        }
+
+        match state {
+            State::Solved(duration) => solved_screen(
+                terminal,
+                &builder,
+                Completion::Solved(duration),
+                has_next_grid,
+                // The records don't apply to a grid that was edited
+                records.as_deref().filter(|_| !builder.edited_at_least_once),
+            ),
+            State::Revealed => solved_screen(
+                terminal,
+                &builder,
+                Completion::Revealed,
+                has_next_grid,
+                None,
+            ),
+            State::TimedOut => timed_out_screen(terminal, &builder, has_next_grid),
+            State::NewGrid(grid) => return NextAction::Restart(grid),
+            State::NextGrid => return NextAction::Next,
+            State::Exit(_) => return NextAction::Exit,
+            _ => unreachable!(),
+        }
+    };
+
+    if wants_next_grid {
+        NextAction::Next
+    } else {
+        NextAction::Exit
+    }
+}
+
+/// Updates the records after a game ended with the state.
+///
+/// Solving a grid that wasn't edited counts towards the streak. Giving up on the grid in any way, including loading another one, ends the streak.
+fn update_records(records: &mut Records, state: &State, builder: &Builder) {
+    match state {
+        State::Solved(_) if !builder.edited_at_least_once => {
+            records.update(builder.cleared_at_least_once)
+        }
+        State::Revealed | State::TimedOut | State::NewGrid(_) | State::Exit(_) => {
+            records.break_streak()
+        }
+        _ => {}
+    }
+}
+
+const BASIC_CONTROLS_HELP: &[&str] = &["A: Undo, D: Redo, C: Clear", "X: Measure, F: Fill"];
+
+/// Draws the lines of help below the grid, which are at most as many as [`BASIC_CONTROLS_HELP`] has.
+fn draw_bottom_help(terminal: &mut Terminal, builder: &Builder, help: &[&str]) {
+    terminal.set_foreground_color(Color::DarkGray);
+    for (index, text) in help.iter().enumerate() {
+        set_cursor_for_bottom_text(terminal, builder, text.len(), index as u16);
+        terminal.write(text);
+    }
+    terminal.reset_colors();
+}
+
+fn clear_bottom_help(terminal: &mut Terminal, builder: &Builder, help: &[&str]) {
+    for (index, text) in help.iter().enumerate() {
+        set_cursor_for_bottom_text(terminal, builder, text.len(), index as u16);
+        for _ in 0..text.len() {
+            terminal.write(" ");
+        }
+    }
+}
+
+fn draw_basic_controls_help(terminal: &mut Terminal, builder: &Builder) {
+    draw_bottom_help(terminal, builder, BASIC_CONTROLS_HELP);
+
+    if builder.legend {
+        draw_legend(terminal, builder);
+    }
+}
+
+fn clear_basic_controls_help(terminal: &mut Terminal, builder: &Builder) {
+    clear_bottom_help(terminal, builder, BASIC_CONTROLS_HELP);
+    if builder.legend {
+        clear_line(terminal, get_legend_y(builder));
+    }
+    clear_hover_status(terminal, builder);
+}
+
+/// The cells that the legend explains, in the order they are shown.
+const LEGEND_CELLS: [Cell; 4] = [
+    Cell::Filled,
+    Cell::Maybed,
+    Cell::Crossed,
+    Cell::Measured(None, None),
+];
+
+/// The space between the entries of the legend.
+const LEGEND_SEPARATOR: &str = "  ";
+
+/// Gets the length of a legend entry, which is a cell followed by its name.
+const fn get_legend_entry_len(cell: Cell) -> usize {
+    2 + " ".len() + get_cell_name(cell).len()
+}
+
+/// Gets how many entries of the legend fit into the width and how long they are together.
+///
+/// On narrow terminals, the entries that don't fit are left out.
+fn get_fitting_legend_entries(width: u16) -> (usize, usize) {
+    let mut len = 0;
+    for (index, cell) in LEGEND_CELLS.iter().enumerate() {
+        let separator_len = if index == 0 {
+            0
+        } else {
+            LEGEND_SEPARATOR.len()
+        };
+        let entry_len = separator_len + get_legend_entry_len(*cell);
+        if len + entry_len > width as usize {
+            return (index, len);
+        }
+        len += entry_len;
+    }
+    (LEGEND_CELLS.len(), len)
+}
+
+const fn get_legend_y(builder: &Builder) -> u16 {
+    builder.point.y
+        + builder.grid.size.height
+        + PROGRESS_BAR_HEIGHT
+        + BASIC_CONTROLS_HELP.len() as u16
+}
+
+/// Draws a line below the basic controls help that shows each cell the way it is drawn, followed by its name.
+fn draw_legend(terminal: &mut Terminal, builder: &Builder) {
+    let (entry_count, len) = get_fitting_legend_entries(terminal.size.width);
+    let center_x = builder.point.x + builder.grid.size.width;
+    let x = cmp::min(
+        center_x.saturating_sub(len as u16 / 2),
+        terminal.size.width - len as u16,
+    );
+
+    terminal.set_cursor(Point {
+        x,
+        y: get_legend_y(builder),
+    });
+    for (index, cell) in LEGEND_CELLS.iter().take(entry_count).enumerate() {
+        if index != 0 {
+            terminal.write(LEGEND_SEPARATOR);
+        }
+        cell.draw(terminal, Point::default(), false, builder.palette);
+        terminal.reset_colors();
+        terminal.set_foreground_color(Color::DarkGray);
+        terminal.write(&format!(" {}", get_cell_name(*cell)));
+    }
+    terminal.reset_colors();
+}
+
+/// Clears the whole line.
+fn clear_line(terminal: &mut Terminal, y: u16) {
+    util::print_at(
+        terminal,
+        Point { x: 0, y },
+        &" ".repeat(terminal.size.width as usize),
+    );
+}
+
+/// Gets the name of the cell as the player knows it.
+const fn get_cell_name(cell: Cell) -> &'static str {
+    match cell {
+        Cell::Empty => "empty",
+        Cell::Filled => "filled",
+        Cell::Maybed => "maybed",
+        Cell::Crossed => "crossed",
+        Cell::Measured(_, _) => "measured",
+    }
+}
+
+/// The length of the pending cell text, which is always the same so that it is easy to clear.
+const PENDING_CELL_TEXT_LEN: usize = "Next: measured".len();
+
+/// Draws the cell that the next placement places in the top left corner of the screen.
+pub(crate) fn draw_pending_cell(terminal: &mut Terminal, cell: Cell) {
+    let text = format!(
+        "{:<width$}",
+        format!("Next: {}", get_cell_name(cell)),
+        width = PENDING_CELL_TEXT_LEN
+    );
+    util::print_colored(terminal, Point::default(), Color::DarkGray, None, &text);
+}
+
+pub(crate) fn clear_pending_cell(terminal: &mut Terminal) {
+    util::print_at(
+        terminal,
+        Point::default(),
+        &" ".repeat(PENDING_CELL_TEXT_LEN),
+    );
+}
+
+/// The length of the countdown text, which is always formatted the same way.
+const COUNTDOWN_TEXT_LEN: usize = "00:00:00".len();
+
+/// Draws the remaining time of time attack above the alerts.
+pub(crate) fn draw_countdown(terminal: &mut Terminal, builder: &Builder, remaining_time: Duration) {
+    // Round up so that the countdown ends at zero
+    let remaining_seconds = remaining_time.as_secs_f64().ceil() as u64;
+
+    set_cursor_for_top_text(terminal, builder, COUNTDOWN_TEXT_LEN, 1, None);
+    terminal.write(&format_seconds(remaining_seconds));
+}
+
+pub(crate) fn clear_countdown(terminal: &mut Terminal, builder: &Builder) {
+    set_cursor_for_top_text(terminal, builder, COUNTDOWN_TEXT_LEN, 1, None);
+    terminal.write(&" ".repeat(COUNTDOWN_TEXT_LEN));
+}
+
+const fn get_hover_status_y(builder: &Builder) -> u16 {
+    builder.point.y
+        + builder.grid.size.height
+        + PROGRESS_BAR_HEIGHT
+        + get_bottom_help_height(builder.legend)
+}
+
+fn format_line_progress(progress: &grid::LineProgress) -> String {
+    if progress.solved {
+        "solved".to_string()
+    } else {
+        format!("{}/{} clues", progress.matching_clues, progress.clues)
+    }
+}
+
+/// Draws how the row and column of the hovered cell are doing below the basic controls help.
+pub(crate) fn draw_hover_status(terminal: &mut Terminal, builder: &Builder, cell_point: Point) {
+    let text = format!(
+        "Row {}: {} {} Col {}: {}",
+        cell_point.y + 1,
+        format_line_progress(&builder.grid.get_row_progress(cell_point.y)),
+        builder.glyphs.get_separator(),
+        cell_point.x + 1,
+        format_line_progress(&builder.grid.get_column_progress(cell_point.x)),
+    );
+
+    clear_hover_status(terminal, builder);
+
+    // On small windows the text is cut off
+    let text = util::truncate_to_width(&text, terminal.size.width as usize);
+    let text_len = util::get_display_width(&text) as u16;
+    let center_x = builder.point.x + builder.grid.size.width;
+    let x = cmp::min(
+        center_x.saturating_sub(text_len / 2),
+        terminal.size.width - text_len,
+    );
+
+    util::print_colored(
+        terminal,
+        Point {
+            x,
+            y: get_hover_status_y(builder),
+        },
+        Color::DarkGray,
+        None,
+        &text,
+    );
+}
+
+/// Clears the hover status. Nothing else is on its line so the whole line is cleared.
+pub(crate) fn clear_hover_status(terminal: &mut Terminal, builder: &Builder) {
+    clear_line(terminal, get_hover_status_y(builder));
+}
+
+/// The line at the top of the screen that compact clues are drawn on, which the top clues make way for.
+const COMPACT_CLUES_Y: u16 = 0;
+
+/// Draws the clues of the row and column of the hovered cell in a single line at the top, if the clues are compact.
+pub(crate) fn draw_compact_clues(terminal: &mut Terminal, builder: &Builder, cell_point: Point) {
+    if !builder.compact_clues {
+        return;
+    }
+
+    let text = builder.get_compact_clues_text(cell_point);
+
+    clear_compact_clues(terminal, builder);
+
+    // The text is cut off because the window is narrow
+    let text = util::truncate_to_width(&text, terminal.size.width as usize);
+    let text_len = util::get_display_width(&text) as u16;
+    let center_x = builder.point.x + builder.grid.size.width;
+    let x = cmp::min(
+        center_x.saturating_sub(text_len / 2),
+        terminal.size.width - text_len,
+    );
+
+    util::print_at(
+        terminal,
+        Point {
+            x,
+            y: COMPACT_CLUES_Y,
+        },
+        &text,
+    );
+}
+
+/// Clears the compact clues, if the clues are compact. Nothing else is on their line so the whole line is cleared.
+pub(crate) fn clear_compact_clues(terminal: &mut Terminal, builder: &Builder) {
+    if builder.compact_clues {
+        clear_line(terminal, COMPACT_CLUES_Y);
+    }
+}
+
+fn load_grid(filename: &str, content: &str) -> Result<Grid, Cow<'static, str>> {
+    match editor::load_grid(content) {
+        Ok(grid) => Ok(grid),
+        Err(err) => {
+            if let Some(line_number) = err.line_number {
+                Err(format!(
+                    "invalid grid data in {}:{}: {}",
+                    filename, line_number, err.message
+                )
+                .into())
+            } else {
+                Err(format!("invalid grid data in {}: {}", filename, err.message).into())
+            }
+        }
+    }
+}
+
+/// The name used in place of a filename for grids read from the standard input.
+const STDIN_NAME: &str = "stdin";
+/// The name used in place of a filename for the empty grid that is edited with `--editor`.
+const EDITOR_GRID_NAME: &str = "new grid";
+
+fn read_stdin() -> Result<String, Cow<'static, str>> {
+    let mut content = String::new();
+    io::stdin()
+        .read_to_string(&mut content)
+        .map_err(|_| "Standard input reading error")?;
+    Ok(content)
+}
+
+fn get_puzzle_source(
+    arg: Option<args::Arg>,
+    options: &args::Options,
+    settings: &Settings,
+) -> Result<Box<dyn PuzzleSource>, Cow<'static, str>> {
+    match arg {
+        Some(args::Arg::File {
+            name: filename,
+            content,
+        }) => {
+            let grid = load_grid(&filename, &content)?;
+            Ok(Box::new(FilePuzzleSource::new(filename, vec![grid])))
+        }
+        Some(args::Arg::Stdin) => {
+            let grid = load_grid(STDIN_NAME, &read_stdin()?)?;
+
+            // The standard input was used up by the grid so input has to be read from the controlling terminal
+            if !util::has_controlling_terminal() {
+                return Err("No controlling terminal to read input from".into());
+            }
+
+            Ok(Box::new(FilePuzzleSource::new(
+                STDIN_NAME.into(),
+                vec![grid],
+            )))
+        }
+        Some(args::Arg::Directory { name, paths }) => {
+            Ok(Box::new(DirectoryPuzzleSource::new(name, paths)))
+        }
+        arg => {
+            let grid_size = if let Some(args::Arg::GridSize(size)) = arg {
+                size
+            } else {
+                Size {
+                    width: 5,
+                    height: 5,
+                }
+            };
+            if options.editor {
+                let grid = Grid::new(grid_size, vec![Cell::Empty; grid_size.product() as usize])
+                    .map_err(GridError::message)?;
+                return Ok(Box::new(FilePuzzleSource::new(
+                    EDITOR_GRID_NAME.into(),
+                    vec![grid],
+                )));
+            }
+            Ok(Box::new(
+                RandomPuzzleSource::new(grid_size)
+                    .with_difficulty(options.difficulty)
+                    .with_empty_lines(settings.empty_lines),
+            ))
+        }
+    }
+}
+
+/// Generates a grid from the image at the path.
+fn import_image(path: &str, options: &args::Options) -> Result<Grid, Cow<'static, str>> {
+    let bytes = fs::read(path).map_err(|_| "Image reading error")?;
+    let image = image_import::decode(&bytes)?;
+
+    image_import::image_to_grid(
+        &image,
+        options
+            .image_grid_width
+            .unwrap_or(args::DEFAULT_IMAGE_GRID_WIDTH),
+        args::MAX_GRID_HEIGHT,
+        options.image_threshold,
+    )
+    .map_err(|err| format!("Image import error: {}", err.message()).into())
+}
+
+/// Solves the grid given by the argument and writes it as an SVG file to the path.
+fn export_svg(arg: Option<args::Arg>, path: &str) -> Result<(), Cow<'static, str>> {
+    let mut grid = match arg {
+        Some(args::Arg::File {
+            name: filename,
+            content,
+        }) => load_grid(&filename, &content)?,
+        Some(args::Arg::Stdin) => load_grid(STDIN_NAME, &read_stdin()?)?,
+        _ => return Err("A grid file to export is required".into()),
+    };
+
+    if !grid.solve() {
+        return Err("The grid has no solution".into());
+    }
+
+    fs::write(path, grid.to_svg()).map_err(|_| "SVG writing error")?;
+
+    Ok(())
+}
+
+fn get_palette(settings: &Settings) -> Palette {
+    let color_support = settings.color_support.unwrap_or_else(ColorSupport::detect);
+
+    Palette::new(color_support)
+        .with_high_contrast(settings.high_contrast)
+        .with_color_blind_mode(settings.color_blind_mode)
+}
+
+/// Lets the bot solve the grid through the normal event handling and reports how that went.
+///
+/// It is an error if the grid doesn't end up solved.
+fn run_bot(
+    terminal: &mut Terminal,
+    grid: Grid,
+    settings: &Settings,
+) -> Result<bot::Report, Cow<'static, str>> {
+    let solution = if let Some(solution) = &grid.solution {
+        solution.clone()
+    } else {
+        let mut solved_grid = grid.clone();
+        if !solved_grid.solve() {
+            return Err("The grid has no solution".into());
+        }
+        solved_grid.cells
+    };
+
+    if let State::Exit(_) =
+        event::input::window::await_fitting_size(terminal, &grid, settings.legend, None)
+    {
+        return Err("The bot was canceled".into());
+    }
+
+    let mut builder = Builder::new(terminal, grid, get_palette(settings), settings);
+    let starting_time = Instant::now();
+
+    // An empty grid is solved right away
+    if builder.draw_all(terminal) {
+        return Ok(bot::Report {
+            stats: Stats::default(),
+            duration: starting_time.elapsed(),
+        });
+    }
+
+    let mut event_source = EventSource::scripted(bot::get_script(&builder, &solution));
+    let state = event::r#loop(
+        terminal,
+        &mut builder,
+        settings,
+        false,
+        None,
+        false,
+        &mut event_source,
+    );
+
+    match (state, event_source.take_stats()) {
+        (State::Solved(_), Some(stats)) => Ok(bot::Report {
+            stats,
+            duration: starting_time.elapsed(),
+        }),
+        _ => Err("The bot did not solve the grid".into()),
+    }
+}
+
+/// Loads the grid file at the path and gets its stats.
+fn get_stats(path: &str) -> Result<grid::Stats, Cow<'static, str>> {
+    if !args::valid_extension(path) {
+        return Err(format!("Filename extension must be \"{}\"", args::FILE_EXTENSION).into());
+    }
+
+    let content = fs::read_to_string(path).map_err(|err| match err.kind() {
+        io::ErrorKind::NotFound => "File not found",
+        _ => "File reading error",
+    })?;
+
+    Ok(load_grid(path, &content)?.get_stats())
+}
+
+/// Creates a new initialized `Terminal` instance if possible and sets the window title.
+///
+/// This `Terminal` is what allows us to manipulate the terminal in all kinds of ways such as setting colors, writing data, moving the cursor etc.
+fn get_terminal<'a>(
+    stdout: io::StdoutLock<'a>,
+    settings: &Settings,
+) -> Result<Terminal<'a>, &'static str> {
+    if let Ok(mut terminal) = Terminal::new(stdout) {
+        terminal.initialize(Some(settings.mode.get_window_title()), settings.mouse);
+        Ok(terminal)
+    } else {
+        Err("This is not a terminal")
+    }
+}
+
+const PROGRESS_BAR_HEIGHT: u16 = 1;
+const TOP_TEXT_HEIGHT: u16 = 2;
+const HOVER_STATUS_HEIGHT: u16 = 1;
+
+/// Gets the height of the basic controls help along with the legend below it if it is shown.
+const fn get_bottom_help_height(legend: bool) -> u16 {
+    BASIC_CONTROLS_HELP.len() as u16 + legend as u16
+}
+
+fn total_height(grid: &Grid, legend: bool) -> u16 {
+    TOP_TEXT_HEIGHT
+        + cmp::max(get_picture_height(grid.size, 1), grid.max_clues_size.height)
+        + grid.size.height
+        + PROGRESS_BAR_HEIGHT
+        + get_bottom_help_height(legend)
+        + HOVER_STATUS_HEIGHT
+}
+
+/// Gets how many lines the picture takes at the zoom.
+///
+/// Zoomed in, every cell takes a line of its own.
+const fn get_picture_height(grid_size: Size, zoom: u8) -> u16 {
+    if zoom > 1 {
+        return grid_size.height;
+    }
+
+    let mut picture_height = grid_size.height / 2; // Divide by 2 because the picture is made of half blocks
+    if grid_size.height % 2 == 1 {
+        picture_height += 1;
+    }
+    picture_height
+}
+
+#[derive(Clone, Copy)]
+pub(crate) enum TopTextPosition {
+    /// The top text is positioned above the clues because it fits and does not overlap with the picture.
+    AboveClues,
+    /// The top text is positioned above the picture because it does not fit below ([`Self::AboveClues`]) and would overlap with the picture.
+    AbovePicture,
+}
+
+const fn get_top_text_position(grid_size: Size, text_len: usize) -> TopTextPosition {
+    if text_len as u16 <= grid_size.width * 2 {
+        // Above the clues
+        TopTextPosition::AboveClues
+    } else {
+        // Above the picture
+        TopTextPosition::AbovePicture
+    }
+}
+
+/// Gets the line of text on the top, counting upwards from above the clues or the picture with `y_alignment`.
+fn get_top_text_y(
+    builder: &Builder,
+    text_len: usize,
+    y_alignment: u16,
+    top_text_position: Option<TopTextPosition>,
+) -> u16 {
+    let picture_height = get_picture_height(builder.grid.size, builder.picture_zoom);
+
+    let height = match top_text_position
+        .unwrap_or_else(|| get_top_text_position(builder.grid.size, text_len))
+    {
+        TopTextPosition::AboveClues => builder.grid.max_clues_size.height,
+        TopTextPosition::AbovePicture => picture_height,
+    };
+
+    ((builder.point.y - height) - 1) - y_alignment
+}
+
+/// Gets how many columns text on the top can take at most so that it stays on the screen when it is centered above the grid.
+pub(crate) fn get_top_text_max_width(terminal: &Terminal, builder: &Builder) -> usize {
+    let center_x = builder.point.x + builder.grid.size.width;
+    let max_width = 2 * cmp::min(center_x, terminal.size.width.saturating_sub(center_x));
+    max_width as usize
+}
+
+/// Properly sets the cursor for drawing centered text on the top.
+pub(crate) fn set_cursor_for_top_text(
+    terminal: &mut Terminal,
+    builder: &Builder,
+    text_len: usize,
+    y_alignment: u16,
+    top_text_position: Option<TopTextPosition>,
+) {
+    terminal.set_cursor(Point {
+        x: builder.point.x + builder.grid.size.width - text_len as u16 / 2,
+        y: get_top_text_y(builder, text_len, y_alignment, top_text_position),
+    });
+}
+
+/// Properly sets the cursor for drawing text on the top that ends where the grid ends on the right.
+pub(crate) fn set_cursor_for_top_right_text(
+    terminal: &mut Terminal,
+    builder: &Builder,
+    text_len: usize,
+    y_alignment: u16,
+) {
+    terminal.set_cursor(Point {
+        x: (builder.point.x + builder.grid.size.width * 2).saturating_sub(text_len as u16),
+        y: get_top_text_y(builder, text_len, y_alignment, None),
+    });
+}
+
+/// Properly sets the cursor for drawing centered text on the bottom.
+pub(crate) fn set_cursor_for_bottom_text(
+    terminal: &mut Terminal,
+    builder: &Builder,
+    text_len: usize,
+    y_alignment: u16,
+) {
+    let mut y = builder.point.y + builder.grid.size.height;
+    y += 1; // Make way for the progress bar
+
+    terminal.set_cursor(Point {
+        x: builder.point.x + builder.grid.size.width - text_len as u16 / 2,
+        y: y + y_alignment,
+    });
+}
+
+/// One hour in seconds.
+const HOUR: u64 = 60 * 60;
+
+/// How the grid came to be solved.
+enum Completion {
+    /// The grid was already solved when the game started.
+    Immediate,
+    /// The player solved the grid in the given time.
+    Solved(Duration),
+    /// The player gave up and revealed the solution.
+    Revealed,
+}
+
+/// The screen that appears when the grid was solved. Returns whether the player wants to play the next grid.
+fn solved_screen(
+    terminal: &mut Terminal,
+    builder: &Builder,
+    completion: Completion,
+    has_next_grid: bool,
+    records: Option<&Records>,
+) -> bool {
+    if let Completion::Solved(_) = completion {
+        builder.bell.ring(terminal);
+    }
+
+    let text: Cow<'static, str> = match completion {
+        Completion::Immediate => "You won by doing nothing".into(),
+        // Zen mode has no timer
+        Completion::Solved(_) if builder.mode == Mode::Zen => "Solved".into(),
+        Completion::Solved(duration) => {
+            let total_elapsed_seconds = duration.as_secs();
+            if total_elapsed_seconds > HOUR * 99 {
+                "That took too long".into()
+            } else {
+                format!("Solved in {}", format_seconds(total_elapsed_seconds)).into()
+            }
+        }
+        Completion::Revealed => "Revealed".into(),
+    };
+    let text = match (completion, builder.check_count) {
+        (Completion::Revealed, _) | (_, 0) => text,
+        (_, 1) => format!("{}, used check once", text).into(),
+        (_, check_count) => format!("{}, used check {} times", text, check_count).into(),
+    };
+    let text = match records {
+        Some(records) => format!(
+            "{}, streak {} (best {})",
+            text, records.streak, records.best_streak
+        )
+        .into(),
+        None => text,
+    };
+
+    end_screen(terminal, builder, &text, has_next_grid)
+}
+
+/// The screen that appears when the time of time attack ran out. Returns whether the player wants to play the next grid.
+fn timed_out_screen(terminal: &mut Terminal, builder: &Builder, has_next_grid: bool) -> bool {
+    let line_count = builder.grid.size.width + builder.grid.size.height;
+    let percentage = builder.grid.get_solved_line_count() as u32 * 100 / line_count as u32;
+
+    let text = format!("Time's up: {}% completed", percentage);
+
+    end_screen(terminal, builder, &text, has_next_grid)
+}
+
+/// Shows the text above the grid and waits for the player to continue. Returns whether the player wants to play the next grid.
+fn end_screen(terminal: &mut Terminal, builder: &Builder, text: &str, has_next_grid: bool) -> bool {
+    terminal.reset_colors();
+
+    // This is always longer than `text` below.
+    let key_text = if has_next_grid {
+        "Press N for the next grid or Esc to exit"
+    } else {
+        "Press any key to continue"
+    };
+
+    let mut y_alignment = 0;
+    let top_text_position = get_top_text_position(builder.grid.size, key_text.len());
+
+    set_cursor_for_top_text(
+        terminal,
+        builder,
+        key_text.len(),
+        y_alignment,
+        Some(top_text_position),
+    );
+    terminal.write(key_text);
+
+    y_alignment += 1;
+
+    let text = util::truncate_to_width(text, get_top_text_max_width(terminal, builder));
+    terminal.set_foreground_color(Color::White);
+    set_cursor_for_top_text(
+        terminal,
+        builder,
+        util::get_display_width(&text),
+        y_alignment,
+        Some(top_text_position),
+    );
+    terminal.write(&text);
+    terminal.reset_colors();
+
+    terminal.flush();
+
+    if has_next_grid {
+        loop {
+            match event::input::key::r#await(terminal) {
+                Key::Char('n' | 'N') => break true,
+                Key::Esc => break false,
+                _ => {}
+            }
+        }
+    } else {
+        event::input::key::r#await(terminal);
+
+        false
+    }
+}
+
+/// Formats the given seconds to an hour, minute and second format.
+///
+/// # Examples
+///
+/// ```ignore
+/// assert_eq!(format_seconds(60 * 70 + 5), "01:10:05");
+/// assert_eq!(format_seconds(45 * 60 + 15), "00:45:15");
+/// assert_eq!(format_seconds(60 * 60 * 99), "99:00:00");
+/// assert_eq!(format_seconds(60 * 80), "01:20:00");
+/// assert_eq!(format_seconds(60 * 60 + 60 * 5 + 30), "01:05:30");
+/// ```
+fn format_seconds(total_seconds: u64) -> String {
+    let seconds = total_seconds % 60;
+    let minutes = total_seconds / 60 % 60;
+    let hours = total_seconds / HOUR;
+    format!("{:02}:{:02}:{:02}", hours, minutes, seconds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+    use terminal::event::Event;
+
+    /// Plays the grid from the start with the events, followed by the events that solve it.
+    fn play(terminal: &mut Terminal, events: &[Event]) -> (State, Builder) {
+        #[rustfmt::skip]
+        let grid = Grid::from_lines(&[
+            "1 1",
+            " 1 ",
+        ]).unwrap();
+        let solution = grid.solution.clone().unwrap();
+        let settings = Settings::default();
+        let mut builder = Builder::new(terminal, grid, Palette::default(), &settings);
+        let _ = builder.draw_all(terminal);
+
+        let mut script: VecDeque<Event> = events.iter().copied().collect();
+        script.extend(bot::get_script(&builder, &solution));
+        let state = event::r#loop(
+            terminal,
+            &mut builder,
+            &settings,
+            false,
+            None,
+            false,
+            &mut EventSource::scripted(script),
+        );
+
+        (state, builder)
+    }
+
+    #[test]
+    fn test_update_records() {
+        let mut terminal = Terminal::new(io::stdout().lock()).unwrap();
+        let mut records = Records::default();
+
+        let (state, mut builder) = play(&mut terminal, &[]);
+        assert!(matches!(state, State::Solved(_)));
+        update_records(&mut records, &state, &builder);
+        assert_eq!(records.streak, 1);
+
+        // A grid that was edited doesn't count
+        builder.edited_at_least_once = true;
+        update_records(&mut records, &state, &builder);
+        assert_eq!(records.streak, 1);
+
+        // Loading another grid is giving up on this one
+        let new_grid = State::NewGrid(Box::new(builder.grid.clone()));
+        update_records(&mut records, &new_grid, &builder);
+        assert_eq!(records.streak, 0);
+
+        let (state, builder) = play(&mut terminal, &[Event::Key(Key::Char('c'))]);
+        assert!(matches!(state, State::Solved(_)));
+        assert!(builder.cleared_at_least_once);
+        update_records(&mut records, &state, &builder);
+        assert_eq!(
+            records,
+            Records {
+                streak: 0,
+                best_streak: 1,
+            }
+        );
+    }
+}
@@ -0,0 +1,84 @@
+//! The ways the game can be played, from racing against the clock to playing without any pressure.
+
+use std::time::Duration;
+use terminal::util::Size;
+
+/// How much time time attack gives for every row and column of the grid.
+const TIME_PER_LINE: Duration = Duration::from_secs(10);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Mode {
+    /// The time it took to solve the grid is shown at the end.
+    #[default]
+    Normal,
+    /// The grid has to be solved before the time runs out.
+    TimeAttack,
+    /// There is no timer, no progress bar and solved clues are not grayed out.
+    Zen,
+}
+
+impl Mode {
+    /// Parses the value of the `--mode` option.
+    pub fn parse(str: &str) -> Option<Self> {
+        match str {
+            "normal" => Some(Self::Normal),
+            "timeattack" => Some(Self::TimeAttack),
+            "zen" => Some(Self::Zen),
+            _ => None,
+        }
+    }
+
+    /// The name that [`Mode::parse`] accepts.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Normal => "normal",
+            Self::TimeAttack => "timeattack",
+            Self::Zen => "zen",
+        }
+    }
+
+    pub const fn get_window_title(self) -> &'static str {
+        match self {
+            Self::Normal => "yayagram",
+            Self::TimeAttack => "yayagram Time attack",
+            Self::Zen => "yayagram Zen",
+        }
+    }
+
+    /// Gets the time that is given to solve a grid of the size, if the time is limited.
+    pub fn get_time_limit(self, grid_size: Size) -> Option<Duration> {
+        match self {
+            Self::TimeAttack => {
+                Some(TIME_PER_LINE * (grid_size.width as u32 + grid_size.height as u32))
+            }
+            Self::Normal | Self::Zen => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        for mode in [Mode::Normal, Mode::TimeAttack, Mode::Zen] {
+            assert_eq!(Mode::parse(mode.name()), Some(mode));
+        }
+        assert_eq!(Mode::parse("hard"), None);
+    }
+
+    #[test]
+    fn test_get_time_limit() {
+        let size = Size {
+            width: 5,
+            height: 10,
+        };
+        assert_eq!(
+            Mode::TimeAttack.get_time_limit(size),
+            Some(Duration::from_secs(150))
+        );
+        assert_eq!(Mode::Normal.get_time_limit(size), None);
+        assert_eq!(Mode::Zen.get_time_limit(size), None);
+    }
+}
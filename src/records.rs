@@ -0,0 +1,170 @@
+//! Keeps track of the player's records across games in a file next to the config file.
+//!
+//! Like the config file, the records file consists of `key = value` lines.
+//! The first line is the version of the format so that fields can be added later without breaking older files.
+
+use crate::config;
+use std::{
+    fmt::Write,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// The version of the format of the records file that is written.
+const VERSION: u32 = 1;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Records {
+    /// How many grids in a row were solved cleanly, which means without clearing the grid.
+    pub streak: u32,
+    /// The longest streak so far.
+    pub best_streak: u32,
+}
+
+impl Records {
+    /// Parses the records. If the content isn't versioned, the records start from scratch.
+    ///
+    /// Keys that aren't known are ignored because they might have been added by a newer version.
+    pub fn parse(content: &str) -> Self {
+        let mut records = Self::default();
+
+        let mut lines = content
+            .lines()
+            .filter_map(|line| line.split_once('='))
+            .map(|(key, value)| (key.trim(), value.trim()));
+
+        match lines.next() {
+            Some(("version", version)) if version.parse::<u32>().is_ok() => {}
+            _ => return records,
+        }
+
+        for (key, value) in lines {
+            let value = if let Ok(value) = value.parse::<u32>() {
+                value
+            } else {
+                continue;
+            };
+
+            match key {
+                "streak" => records.streak = value,
+                "best_streak" => records.best_streak = value,
+                _ => {}
+            }
+        }
+
+        records
+    }
+
+    /// Formats the records as the content of the records file.
+    pub fn to_records_file(self) -> String {
+        let mut content = String::new();
+
+        // Writing to a `String` can't fail
+        let _ = writeln!(content, "version = {}", VERSION);
+        let _ = writeln!(content, "streak = {}", self.streak);
+        let _ = writeln!(content, "best_streak = {}", self.best_streak);
+
+        content
+    }
+
+    /// Updates the streak after a grid was solved. The grid only counts towards the streak if it was never cleared.
+    pub fn update(&mut self, cleared_at_least_once: bool) {
+        if cleared_at_least_once {
+            self.streak = 0;
+        } else {
+            self.streak = self.streak.saturating_add(1);
+            self.best_streak = self.best_streak.max(self.streak);
+        }
+    }
+
+    /// Ends the streak because a grid was given up on.
+    pub fn break_streak(&mut self) {
+        self.streak = 0;
+    }
+}
+
+/// Gets the path of the records file in the platform's config directory, if there is one.
+pub fn get_default_path() -> Option<PathBuf> {
+    Some(config::get_directory()?.join("records"))
+}
+
+/// Loads the records from the file at the path. If the file can't be read, the records start from scratch.
+pub fn load(path: &Path) -> Records {
+    fs::read_to_string(path)
+        .map(|content| Records::parse(&content))
+        .unwrap_or_default()
+}
+
+/// Writes the records to the file at the path, creating its directory if necessary.
+pub fn save(path: &Path, records: Records) -> io::Result<()> {
+    if let Some(directory) = path.parent() {
+        fs::create_dir_all(directory)?;
+    }
+
+    fs::write(path, records.to_records_file())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_update() {
+        let mut records = Records::default();
+
+        records.update(false);
+        records.update(false);
+        assert_eq!(
+            records,
+            Records {
+                streak: 2,
+                best_streak: 2
+            }
+        );
+
+        // Clearing the grid doesn't count towards the streak
+        records.update(true);
+        assert_eq!(
+            records,
+            Records {
+                streak: 0,
+                best_streak: 2
+            }
+        );
+
+        // The streak stops growing at the highest number that can be saved
+        let mut longest_records = Records::parse("version = 1\nstreak = 4294967295\n");
+        longest_records.update(false);
+        assert_eq!(longest_records.streak, u32::MAX);
+        assert_eq!(longest_records.best_streak, u32::MAX);
+
+        records.update(false);
+        records.break_streak();
+        assert_eq!(records.streak, 0);
+        assert_eq!(records.best_streak, 2);
+    }
+
+    #[test]
+    fn test_parse() {
+        let records = Records {
+            streak: 3,
+            best_streak: 7,
+        };
+        assert_eq!(Records::parse(&records.to_records_file()), records);
+
+        // Fields of newer versions are skipped
+        let records =
+            Records::parse("version = 2\nstreak = 1\nfastest_solve = 9\nbest_streak = 4\n");
+        assert_eq!(
+            records,
+            Records {
+                streak: 1,
+                best_streak: 4
+            }
+        );
+
+        // Without a version, the content isn't trusted
+        assert_eq!(Records::parse("streak = 1\n"), Records::default());
+        assert_eq!(Records::parse(""), Records::default());
+    }
+}
@@ -0,0 +1,462 @@
+use crate::{
+    editor,
+    grid::{Difficulty, Grid},
+};
+use std::{fs, mem, path::PathBuf};
+use terminal::util::Size;
+
+/// Provides the grids to be played one after another.
+pub trait PuzzleSource {
+    /// Gets the next grid to play or `None` if there are no more grids.
+    fn next_grid(&mut self) -> Option<Grid>;
+
+    /// A short description of where the grids come from.
+    fn name(&self) -> &str;
+
+    /// Takes the names of the grids that had to be skipped because they could not be loaded.
+    fn take_skipped(&mut self) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+/// Grids loaded from a file.
+pub struct FilePuzzleSource {
+    name: String,
+    grids: Vec<Grid>,
+    index: usize,
+}
+
+impl FilePuzzleSource {
+    pub fn new(name: String, grids: Vec<Grid>) -> Self {
+        Self {
+            name,
+            grids,
+            index: 0,
+        }
+    }
+}
+
+impl PuzzleSource for FilePuzzleSource {
+    fn next_grid(&mut self) -> Option<Grid> {
+        let grid = self.grids.get(self.index)?.clone();
+        self.index += 1;
+        Some(grid)
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// Grid files in a directory, loaded one at a time as they are needed.
+pub struct DirectoryPuzzleSource {
+    name: String,
+    paths: Vec<PathBuf>,
+    index: usize,
+    skipped: Vec<String>,
+}
+
+impl DirectoryPuzzleSource {
+    pub fn new(name: String, paths: Vec<PathBuf>) -> Self {
+        Self {
+            name,
+            paths,
+            index: 0,
+            skipped: Vec::new(),
+        }
+    }
+}
+
+impl PuzzleSource for DirectoryPuzzleSource {
+    fn next_grid(&mut self) -> Option<Grid> {
+        while let Some(path) = self.paths.get(self.index) {
+            self.index += 1;
+
+            let grid = fs::read_to_string(path)
+                .ok()
+                .and_then(|content| editor::load_grid(&content).ok());
+
+            if let Some(grid) = grid {
+                return Some(grid);
+            } else {
+                let filename = path
+                    .file_name()
+                    .unwrap_or(path.as_os_str())
+                    .to_string_lossy()
+                    .into_owned();
+                self.skipped.push(filename);
+            }
+        }
+
+        None
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn take_skipped(&mut self) -> Vec<String> {
+        mem::take(&mut self.skipped)
+    }
+}
+
+/// How many random grids are generated at most to find one of the requested difficulty.
+const MAX_DIFFICULTY_ATTEMPTS: usize = 100;
+
+/// An endless amount of random grids of the same size.
+pub struct RandomPuzzleSource {
+    size: Size,
+    seed_rng: fastrand::Rng,
+    /// If present, grids are generated until one with this estimated difficulty is found or too many attempts were made.
+    difficulty: Option<Difficulty>,
+    /// Whether the grids may have rows and columns without any filled cells.
+    empty_lines: bool,
+}
+
+impl RandomPuzzleSource {
+    pub fn new(size: Size) -> Self {
+        Self {
+            size,
+            seed_rng: fastrand::Rng::new(),
+            difficulty: None,
+            empty_lines: true,
+        }
+    }
+
+    /// Creates a source that always provides the same grids for the same seed.
+    pub fn with_seed(size: Size, seed: u64) -> Self {
+        Self {
+            size,
+            seed_rng: fastrand::Rng::with_seed(seed),
+            difficulty: None,
+            empty_lines: true,
+        }
+    }
+
+    /// Makes the source prefer grids of the given difficulty.
+    pub fn with_difficulty(self, difficulty: Option<Difficulty>) -> Self {
+        Self { difficulty, ..self }
+    }
+
+    /// Makes the source provide only grids where every row and column has at least one filled cell if `empty_lines` is `false`.
+    pub fn with_empty_lines(self, empty_lines: bool) -> Self {
+        Self {
+            empty_lines,
+            ..self
+        }
+    }
+}
+
+impl PuzzleSource for RandomPuzzleSource {
+    fn next_grid(&mut self) -> Option<Grid> {
+        // There are no grids of a size with a width or height of 0
+        let mut grid =
+            Grid::random_with_rng(self.size, self.empty_lines, &mut self.seed_rng).ok()?;
+
+        if let Some(difficulty) = self.difficulty {
+            // If no grid of the difficulty is found, the last one is played anyway
+            for _ in 1..MAX_DIFFICULTY_ATTEMPTS {
+                if grid.estimate_difficulty() == difficulty {
+                    break;
+                }
+                grid =
+                    Grid::random_with_rng(self.size, self.empty_lines, &mut self.seed_rng).ok()?;
+            }
+        }
+
+        Some(grid)
+    }
+
+    fn name(&self) -> &str {
+        "random"
+    }
+}
+
+/// Provides the grids of multiple sources, one source after another.
+pub struct SequentialPuzzleSource {
+    sources: Vec<Box<dyn PuzzleSource>>,
+    /// The skipped grids of the sources that were already used up.
+    skipped: Vec<String>,
+}
+
+impl SequentialPuzzleSource {
+    pub fn new(sources: Vec<Box<dyn PuzzleSource>>) -> Self {
+        Self {
+            sources,
+            skipped: Vec::new(),
+        }
+    }
+}
+
+impl PuzzleSource for SequentialPuzzleSource {
+    fn next_grid(&mut self) -> Option<Grid> {
+        while let Some(source) = self.sources.first_mut() {
+            if let Some(grid) = source.next_grid() {
+                return Some(grid);
+            }
+            self.skipped.append(&mut source.take_skipped());
+            self.sources.remove(0);
+        }
+        None
+    }
+
+    /// The name of the current source.
+    fn name(&self) -> &str {
+        self.sources
+            .first()
+            .map_or("nothing", |source| source.name())
+    }
+
+    fn take_skipped(&mut self) -> Vec<String> {
+        let mut skipped = mem::take(&mut self.skipped);
+        if let Some(source) = self.sources.first_mut() {
+            skipped.append(&mut source.take_skipped());
+        }
+        skipped
+    }
+}
+
+/// Decides which grid is played next.
+pub struct PuzzleQueue {
+    source: Box<dyn PuzzleSource>,
+    /// The next grid of the source, which is fetched in advance so that it is known whether there is one.
+    upcoming_grid: Option<Grid>,
+    /// The grids skipped to fetch the upcoming grid, which are only reported once the upcoming grid is reached.
+    upcoming_skipped: Vec<String>,
+    /// The skipped grids to report.
+    skipped: Vec<String>,
+    /// A grid that is played before the upcoming grid.
+    requested_grid: Option<Grid>,
+}
+
+impl PuzzleQueue {
+    pub fn new(mut source: Box<dyn PuzzleSource>) -> Self {
+        let upcoming_grid = source.next_grid();
+        let upcoming_skipped = source.take_skipped();
+
+        Self {
+            source,
+            upcoming_grid,
+            upcoming_skipped,
+            skipped: Vec::new(),
+            requested_grid: None,
+        }
+    }
+
+    /// Gets the grid to play next or `None` if there are no more grids.
+    pub fn next_grid(&mut self) -> Option<Grid> {
+        if let Some(grid) = self.requested_grid.take() {
+            return Some(grid);
+        }
+
+        self.skipped.append(&mut self.upcoming_skipped);
+        let grid = self.upcoming_grid.take()?;
+
+        self.upcoming_grid = self.source.next_grid();
+        self.upcoming_skipped = self.source.take_skipped();
+
+        Some(grid)
+    }
+
+    /// Whether there is another grid after the current one.
+    pub fn has_next_grid(&self) -> bool {
+        self.upcoming_grid.is_some()
+    }
+
+    /// Plays the given grid next, before the upcoming grid of the source.
+    pub fn restart(&mut self, grid: Grid) {
+        self.requested_grid = Some(grid);
+    }
+
+    /// Takes the names of the grids that were skipped to get to the current grid.
+    pub fn take_skipped(&mut self) -> Vec<String> {
+        mem::take(&mut self.skipped)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{path::Path, process};
+
+    /// Creates an empty temporary directory that no other test or test run uses at the same time.
+    fn get_temp_directory(name: &str) -> PathBuf {
+        let directory =
+            std::env::temp_dir().join(format!("yayagram_test_{}_{}", name, process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        directory
+    }
+
+    fn get_example_path() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("example.yaya")
+    }
+
+    #[test]
+    fn test_file_puzzle_source() {
+        let mut source = FilePuzzleSource::new(
+            "test.yaya".into(),
+            vec![
+                Grid::from_lines(&["1 "]).unwrap(),
+                Grid::from_lines(&[" 1"]).unwrap(),
+            ],
+        );
+
+        assert_eq!(source.name(), "test.yaya");
+        assert_eq!(
+            source.next_grid().unwrap().vertical_clues_solutions,
+            [vec![1], vec![]]
+        );
+        assert_eq!(
+            source.next_grid().unwrap().vertical_clues_solutions,
+            [vec![], vec![1]]
+        );
+        assert!(source.next_grid().is_none());
+    }
+
+    #[test]
+    fn test_random_puzzle_source() {
+        let size = Size {
+            width: 10,
+            height: 10,
+        };
+        let mut first_source = RandomPuzzleSource::with_seed(size, 42);
+        let mut second_source = RandomPuzzleSource::with_seed(size, 42);
+
+        for _ in 0..3 {
+            let first_grid = first_source.next_grid().unwrap();
+            let second_grid = second_source.next_grid().unwrap();
+            assert_eq!(first_grid.size, size);
+            assert_eq!(
+                first_grid.horizontal_clues_solutions,
+                second_grid.horizontal_clues_solutions
+            );
+        }
+    }
+
+    #[test]
+    fn test_random_puzzle_source_difficulty() {
+        let size = Size {
+            width: 5,
+            height: 5,
+        };
+
+        for difficulty in [Difficulty::Easy, Difficulty::Medium, Difficulty::Hard] {
+            let mut source =
+                RandomPuzzleSource::with_seed(size, 42).with_difficulty(Some(difficulty));
+            for _ in 0..3 {
+                assert_eq!(
+                    source.next_grid().unwrap().estimate_difficulty(),
+                    difficulty
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_sequential_puzzle_source() {
+        let mut source = SequentialPuzzleSource::new(vec![
+            Box::new(FilePuzzleSource::new(
+                "first.yaya".into(),
+                vec![Grid::from_lines(&["1"]).unwrap()],
+            )),
+            Box::new(FilePuzzleSource::new("empty.yaya".into(), vec![])),
+            Box::new(FilePuzzleSource::new(
+                "last.yaya".into(),
+                vec![Grid::from_lines(&[" "]).unwrap()],
+            )),
+        ]);
+
+        assert_eq!(source.name(), "first.yaya");
+        assert_eq!(
+            source.next_grid().unwrap().horizontal_clues_solutions,
+            [vec![1]]
+        );
+        assert_eq!(
+            source.next_grid().unwrap().horizontal_clues_solutions,
+            [vec![]]
+        );
+        assert_eq!(source.name(), "last.yaya");
+        assert!(source.next_grid().is_none());
+        assert_eq!(source.name(), "nothing");
+    }
+
+    #[test]
+    fn test_directory_puzzle_source() {
+        let directory = get_temp_directory("directory_puzzle_source");
+        let broken_path = directory.join("broken.yaya");
+        let example_path = directory.join("example.yaya");
+        fs::write(&broken_path, "not a grid").unwrap();
+        fs::copy(get_example_path(), &example_path).unwrap();
+
+        let mut source = DirectoryPuzzleSource::new(
+            "directory".into(),
+            vec![broken_path, example_path.clone(), example_path],
+        );
+
+        assert!(source.next_grid().is_some());
+        assert_eq!(source.take_skipped(), ["broken.yaya"]);
+        assert!(source.next_grid().is_some());
+        assert!(source.take_skipped().is_empty());
+        assert!(source.next_grid().is_none());
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn test_puzzle_queue_skipped() {
+        let directory = get_temp_directory("puzzle_queue_skipped");
+        let broken_path = directory.join("broken.yaya");
+        fs::write(&broken_path, "not a grid").unwrap();
+
+        let mut queue = PuzzleQueue::new(Box::new(DirectoryPuzzleSource::new(
+            "directory".into(),
+            vec![get_example_path(), broken_path, get_example_path()],
+        )));
+
+        // The broken grid is skipped while fetching the second grid in advance but only reported once that grid is reached
+        assert!(queue.next_grid().is_some());
+        assert!(queue.take_skipped().is_empty());
+        queue.restart(Grid::from_lines(&["1"]).unwrap());
+        assert!(queue.next_grid().is_some());
+        assert!(queue.take_skipped().is_empty());
+        assert!(queue.next_grid().is_some());
+        assert_eq!(queue.take_skipped(), ["broken.yaya"]);
+        assert!(queue.next_grid().is_none());
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn test_puzzle_queue() {
+        let mut queue = PuzzleQueue::new(Box::new(FilePuzzleSource::new(
+            "test.yaya".into(),
+            vec![
+                Grid::from_lines(&["1 "]).unwrap(),
+                Grid::from_lines(&[" 1"]).unwrap(),
+            ],
+        )));
+
+        assert_eq!(
+            queue.next_grid().unwrap().vertical_clues_solutions,
+            [vec![1], vec![]]
+        );
+        assert!(queue.has_next_grid());
+
+        // Restarting many times neither consumes the source nor nests anything
+        for _ in 0..1000 {
+            queue.restart(Grid::from_lines(&["11"]).unwrap());
+            assert_eq!(
+                queue.next_grid().unwrap().vertical_clues_solutions,
+                [vec![1], vec![1]]
+            );
+            assert!(queue.has_next_grid());
+        }
+
+        assert_eq!(
+            queue.next_grid().unwrap().vertical_clues_solutions,
+            [vec![], vec![1]]
+        );
+        assert!(!queue.has_next_grid());
+        assert!(queue.next_grid().is_none());
+    }
+}
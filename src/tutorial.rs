@@ -0,0 +1,160 @@
+//! A walkthrough of the rules and the most important controls on a small grid, for players new to nonograms.
+
+use crate::{
+    editor,
+    grid::{builder::Builder, Cell, CellPlacement, Grid},
+};
+
+/// The grid of the tutorial, which is shaped like a house so that the picture is easy to recognize.
+const GRID: &str = concat!(
+    "title: Tutorial\n",
+    "+--------------------+\n",
+    "|        1111        |\n",
+    "|        1111        |\n",
+    "|    111111111111    |\n",
+    "|    111111111111    |\n",
+    "|11111111111111111111|\n",
+    "|11111111111111111111|\n",
+    "|    1111    1111    |\n",
+    "|    1111    1111    |\n",
+    "|    111111111111    |\n",
+    "|    111111111111    |\n",
+    "+--------------------+\n",
+);
+
+/// The name of the tutorial as a source of grids.
+pub const NAME: &str = "tutorial";
+
+pub fn get_grid() -> Grid {
+    editor::load_grid(GRID).ok().unwrap() // The grid is known to be valid
+}
+
+/// The steps of the tutorial, in order. Each step explains something and is done once the player did it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TutorialState {
+    /// What the clues mean.
+    Clues,
+    /// How to fill a cell.
+    FilledCell,
+    /// How to cross out a cell.
+    CrossedCell,
+    /// How to use the measurement tool.
+    Measurement,
+    /// What the picture shows.
+    Picture,
+    /// All steps are done and a random grid is played next.
+    Completed,
+}
+
+impl TutorialState {
+    /// Gets the message that explains the step, which is shown as an alert until the step is done.
+    pub const fn get_message(self) -> &'static str {
+        match self {
+            Self::Clues => "Clues are the runs of filled cells in their line. Hover a cell",
+            Self::FilledCell => "Left-click a cell or press Q to fill it",
+            Self::CrossedCell => "Right-click a cell or press E to cross it out as empty",
+            Self::Measurement => "Press X on a cell and on another to count the cells between",
+            Self::Picture => "The top left shows the picture in small. Press B or Z to change it",
+            Self::Completed => "Tutorial completed",
+        }
+    }
+
+    const fn next(self) -> Self {
+        match self {
+            Self::Clues => Self::FilledCell,
+            Self::FilledCell => Self::CrossedCell,
+            Self::CrossedCell => Self::Measurement,
+            Self::Measurement => Self::Picture,
+            Self::Picture | Self::Completed => Self::Completed,
+        }
+    }
+
+    /// Checks whether the player did what the step explains.
+    fn is_done(self, builder: &Builder, cell_placement: &CellPlacement) -> bool {
+        let grid_contains = |predicate: fn(&Cell) -> bool| builder.grid.cells.iter().any(predicate);
+
+        match self {
+            // Hovering or selecting a cell highlights the clues of its row and column
+            Self::Clues => {
+                cell_placement.hovered_cell_point.is_some()
+                    || cell_placement.selected_cell_point.is_some()
+            }
+            Self::FilledCell => grid_contains(|cell| *cell == Cell::Filled),
+            Self::CrossedCell => grid_contains(|cell| *cell == Cell::Crossed),
+            Self::Measurement => grid_contains(|cell| matches!(cell, Cell::Measured(_, _))),
+            // With ASCII, the picture can't be drawn with braille but it can be zoomed
+            Self::Picture => builder.braille_picture || builder.picture_zoom > 1,
+            Self::Completed => false,
+        }
+    }
+
+    /// Moves on to the next step if the current one is done and returns whether it did.
+    pub fn advance(&mut self, builder: &Builder, cell_placement: &CellPlacement) -> bool {
+        if self.is_done(builder, cell_placement) {
+            *self = self.next();
+            true
+        } else {
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::Settings, grid::palette::Palette};
+    use std::io;
+    use terminal::{util::Point, Terminal};
+
+    #[test]
+    fn test_get_grid() {
+        let grid = get_grid();
+        assert_eq!(grid.metadata["title"], "Tutorial");
+        assert_eq!(
+            grid.horizontal_clues_solutions,
+            [vec![1], vec![3], vec![5], vec![1, 1], vec![3]]
+        );
+        assert_eq!(
+            grid.vertical_clues_solutions,
+            [vec![1], vec![4], vec![3, 1], vec![4], vec![1]]
+        );
+    }
+
+    #[test]
+    fn test_advance() {
+        let stdout = io::stdout();
+        let terminal = Terminal::new(stdout.lock()).unwrap();
+        let mut builder = Builder::new(
+            &terminal,
+            get_grid(),
+            Palette::default(),
+            &Settings::default(),
+        );
+        let mut cell_placement = CellPlacement::default();
+
+        let mut state = TutorialState::Clues;
+        assert!(!state.advance(&builder, &cell_placement));
+
+        cell_placement.hovered_cell_point = Some(builder.point);
+        assert!(state.advance(&builder, &cell_placement));
+        assert_eq!(state, TutorialState::FilledCell);
+
+        // Crossing out a cell doesn't count as filling one
+        *builder.grid.get_mut_cell(Point { x: 0, y: 0 }) = Cell::Crossed;
+        assert!(!state.advance(&builder, &cell_placement));
+        *builder.grid.get_mut_cell(Point { x: 2, y: 0 }) = Cell::Filled;
+        assert!(state.advance(&builder, &cell_placement));
+        // The cell was already crossed out before
+        assert!(state.advance(&builder, &cell_placement));
+        assert_eq!(state, TutorialState::Measurement);
+
+        *builder.grid.get_mut_cell(Point { x: 4, y: 0 }) = Cell::Measured(Some(1), None);
+        assert!(state.advance(&builder, &cell_placement));
+        assert!(!state.advance(&builder, &cell_placement));
+
+        builder.braille_picture = true;
+        assert!(state.advance(&builder, &cell_placement));
+        assert_eq!(state, TutorialState::Completed);
+        assert!(!state.advance(&builder, &cell_placement));
+    }
+}
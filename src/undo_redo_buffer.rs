@@ -1,4 +1,4 @@
-use crate::grid::{self, Cell, Grid};
+use crate::grid::{self, tools::transform::Transform, Cell, Grid};
 use terminal::util::Point;
 
 #[derive(Clone, Debug)]
@@ -7,16 +7,28 @@ pub enum Operation {
         point: Point,
         cell: Cell,
     },
+    /// Multiple cells that were placed at once, such as by the rectangle fill.
+    SetCells(Vec<(Point, Cell)>),
     Measure(Vec<Point>),
     Clear,
+    /// The cells were replaced with the solution.
+    Reveal,
     Fill {
         point: Point,
         first_cell: Cell,
         fill_cell: Cell,
     },
+    /// The cells of one kind connected to the point were flood-filled.
+    FillTarget {
+        point: Point,
+        target_cell: Cell,
+        fill_cell: Cell,
+    },
+    /// The whole grid was mirrored or rotated, including the clues.
+    Transform(Transform),
 }
 
-#[derive(Default, Debug)]
+#[derive(Default, Clone, Debug)]
 pub struct UndoRedoBuffer {
     pub buffer: Vec<Operation>,
     pub index: usize,
@@ -30,43 +42,123 @@ impl UndoRedoBuffer {
         self.buffer.push(operation);
         self.index += 1;
     }
+
+    /// Pushes multiple cells that were placed at once so that they are undone in one step.
+    ///
+    /// If `merge` is `true` and the last operation also placed multiple cells, the cells are added to that operation instead.
+    pub fn push_cells(&mut self, changes: Vec<(Point, Cell)>, merge: bool) {
+        if merge {
+            self.buffer.truncate(self.index);
+            if let Some(Operation::SetCells(cells)) = self.buffer.last_mut() {
+                cells.extend(changes);
+                return;
+            }
+        }
+
+        self.push(Operation::SetCells(changes));
+    }
+
+    /// Gets the operation that the next undo undoes, if any.
+    pub fn get_undo_operation(&self) -> Option<&Operation> {
+        self.index
+            .checked_sub(1)
+            .and_then(|index| self.buffer.get(index))
+    }
+
+    /// Gets the operation that the next redo redoes, if any.
+    pub fn get_redo_operation(&self) -> Option<&Operation> {
+        self.buffer.get(self.index)
+    }
 }
 
 impl Grid {
     /// Tries to undo the last placed cell and returns `true` if that was successful.
     pub fn undo_last_cell(&mut self) -> bool {
-        if self.undo_redo_buffer.index > 0 {
-            self.undo_redo_buffer.index -= 1;
-
-            self.rebuild();
-            true
-        } else {
-            false
-        }
+        self.undo_redo_buffer.index > 0 && self.jump_to(self.undo_redo_buffer.index - 1)
     }
 
     /// Tries to redo the last undone cell and returns `true` if that was successful.
     pub fn redo_last_cell(&mut self) -> bool {
-        if self.undo_redo_buffer.index != self.undo_redo_buffer.buffer.len() {
-            self.undo_redo_buffer.index += 1;
+        self.undo_redo_buffer.index != self.undo_redo_buffer.buffer.len()
+            && self.jump_to(self.undo_redo_buffer.index + 1)
+    }
 
-            self.rebuild();
-            true
+    /// Undoes or redoes operations until only the operations before the index are done
+    /// and returns `true` if the index is within the buffer.
+    pub fn jump_to(&mut self, index: usize) -> bool {
+        let current_index = self.undo_redo_buffer.index;
+        let buffer = &self.undo_redo_buffer.buffer;
+        if index > buffer.len() {
+            return false;
+        }
+
+        // The clues aren't rebuilt from the operations so they are transformed directly
+        let transforms: Vec<Transform> = if index < current_index {
+            buffer[index..current_index]
+                .iter()
+                .rev()
+                .filter_map(|operation| match operation {
+                    Operation::Transform(transform) => Some(transform.inverse()),
+                    _ => None,
+                })
+                .collect()
         } else {
-            false
+            buffer[current_index..index]
+                .iter()
+                .filter_map(|operation| match operation {
+                    Operation::Transform(transform) => Some(*transform),
+                    _ => None,
+                })
+                .collect()
+        };
+        for transform in transforms {
+            self.transform(transform);
         }
+
+        self.undo_redo_buffer.index = index;
+        self.rebuild();
+
+        true
+    }
+
+    /// Gets how many operations there are to undo and redo.
+    pub fn operation_count(&self) -> usize {
+        self.undo_redo_buffer.buffer.len()
+    }
+
+    /// Gets how many operations are done, which is the index of the next operation to redo.
+    pub fn current_operation_index(&self) -> usize {
+        self.undo_redo_buffer.index
     }
 
     fn rebuild(&mut self) {
         self.clear();
 
-        for operation in self.undo_redo_buffer.buffer.clone()[..self.undo_redo_buffer.index].iter()
-        {
+        let operations = self.undo_redo_buffer.buffer[..self.undo_redo_buffer.index].to_vec();
+
+        // The operations start out with the size from before any rotations.
+        // The cells are all empty so only the size needs to change for that.
+        let rotation_count = operations
+            .iter()
+            .filter(|operation| {
+                matches!(operation, Operation::Transform(transform) if transform.swaps_size())
+            })
+            .count();
+        if rotation_count % 2 == 1 {
+            self.transform_cells(Transform::RotateClockwise);
+        }
+
+        for operation in &operations {
             match operation {
                 Operation::SetCell { point, cell } => {
                     let grid_cell = self.get_mut_cell(*point);
                     *grid_cell = *cell;
                 }
+                Operation::SetCells(cells) => {
+                    for (point, cell) in cells {
+                        *self.get_mut_cell(*point) = *cell;
+                    }
+                }
                 Operation::Measure(line_points) => {
                     grid::set_measured_cells(self, line_points);
                 }
@@ -74,11 +166,189 @@ impl Grid {
                     point,
                     first_cell,
                     fill_cell,
-                } => grid::tools::fill::fill(self, *point, *first_cell, *fill_cell),
+                } => {
+                    grid::tools::fill::fill(self, *point, *first_cell, *fill_cell);
+                }
+                Operation::FillTarget {
+                    point,
+                    target_cell,
+                    fill_cell,
+                } => {
+                    grid::tools::fill::fill_target(self, *point, *target_cell, *fill_cell);
+                }
                 Operation::Clear => {
                     self.clear();
                 }
+                Operation::Reveal => {
+                    self.set_solution_cells();
+                }
+                Operation::Transform(transform) => {
+                    self.transform_cells(*transform);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use terminal::util::Size;
+
+    fn get_grid() -> Grid {
+        let size = Size {
+            width: 5,
+            height: 2,
+        };
+        Grid::new(size, vec![Cell::Filled; size.product() as usize]).unwrap()
+    }
+
+    fn get_changes(y: u16, cell: Cell) -> Vec<(Point, Cell)> {
+        (0..5).map(|x| (Point { x, y }, cell)).collect()
+    }
+
+    #[test]
+    fn test_set_cells() {
+        let mut grid = get_grid();
+        let point = Point { x: 0, y: 0 };
+        *grid.get_mut_cell(point) = Cell::Crossed;
+        grid.undo_redo_buffer.push(Operation::SetCell {
+            point,
+            cell: Cell::Crossed,
+        });
+        let original_cells = grid.cells.clone();
+
+        let mut changes = get_changes(0, Cell::Filled);
+        changes.extend(get_changes(1, Cell::Maybed));
+        assert_eq!(changes.len(), 10);
+        for (point, cell) in &changes {
+            *grid.get_mut_cell(*point) = *cell;
+        }
+        grid.undo_redo_buffer.push_cells(changes, false);
+        let changed_cells = grid.cells.clone();
+
+        // All 10 cells are undone in one step, restoring the state before them
+        assert!(grid.undo_last_cell());
+        assert_eq!(grid.undo_redo_buffer.index, 1);
+        assert_eq!(grid.cells, original_cells);
+
+        assert!(grid.redo_last_cell());
+        assert_eq!(grid.cells, changed_cells);
+
+        assert!(grid.undo_last_cell());
+        assert!(grid.undo_last_cell());
+        assert!(grid.cells.iter().all(|cell| *cell == Cell::Empty));
+        assert!(!grid.undo_last_cell());
+    }
+
+    #[test]
+    fn test_push_cells_merge() {
+        let mut grid = get_grid();
+
+        grid.undo_redo_buffer
+            .push_cells(get_changes(0, Cell::Filled), true);
+        grid.undo_redo_buffer
+            .push_cells(get_changes(1, Cell::Filled), true);
+        assert_eq!(grid.undo_redo_buffer.buffer.len(), 1);
+
+        grid.undo_redo_buffer
+            .push_cells(get_changes(1, Cell::Crossed), false);
+        assert_eq!(grid.undo_redo_buffer.buffer.len(), 2);
+
+        // The undone operation is dropped and the cells are merged into the one before it
+        assert!(grid.undo_last_cell());
+        grid.undo_redo_buffer
+            .push_cells(get_changes(1, Cell::Maybed), true);
+        assert_eq!(grid.undo_redo_buffer.buffer.len(), 1);
+        assert_eq!(grid.undo_redo_buffer.index, 1);
+
+        assert!(!grid.redo_last_cell());
+        assert!(grid.undo_last_cell());
+        assert!(grid.cells.iter().all(|cell| *cell == Cell::Empty));
+    }
+
+    #[test]
+    fn test_transform() {
+        let mut grid = get_grid();
+        let point = Point { x: 4, y: 0 };
+        *grid.get_mut_cell(point) = Cell::Crossed;
+        grid.undo_redo_buffer.push(Operation::SetCell {
+            point,
+            cell: Cell::Crossed,
+        });
+
+        grid.transform(Transform::RotateClockwise);
+        grid.undo_redo_buffer
+            .push(Operation::Transform(Transform::RotateClockwise));
+        let rotated_point = Point { x: 1, y: 4 };
+        assert_eq!(grid.get_cell(rotated_point), Cell::Crossed);
+
+        // Cells placed after the rotation are undone in the rotated grid
+        let point = Point { x: 0, y: 0 };
+        *grid.get_mut_cell(point) = Cell::Maybed;
+        grid.undo_redo_buffer.push(Operation::SetCell {
+            point,
+            cell: Cell::Maybed,
+        });
+        assert!(grid.undo_last_cell());
+        assert_eq!(grid.size.width, 2);
+        assert_eq!(grid.get_cell(point), Cell::Empty);
+        assert_eq!(grid.get_cell(rotated_point), Cell::Crossed);
+
+        assert!(grid.undo_last_cell());
+        assert_eq!(grid.size.width, 5);
+        assert_eq!(grid.get_cell(Point { x: 4, y: 0 }), Cell::Crossed);
+        assert_eq!(grid.vertical_clues_solutions.len(), 5);
+
+        assert!(grid.redo_last_cell());
+        assert!(grid.redo_last_cell());
+        assert_eq!(grid.size.width, 2);
+        assert_eq!(grid.horizontal_clues_solutions.len(), 5);
+        assert_eq!(grid.get_cell(point), Cell::Maybed);
+        assert_eq!(grid.get_cell(rotated_point), Cell::Crossed);
+    }
+
+    #[test]
+    fn test_jump_to() {
+        let mut grid = get_grid();
+        for y in 0..2 {
+            let changes = get_changes(y, Cell::Crossed);
+            for (point, cell) in &changes {
+                *grid.get_mut_cell(*point) = *cell;
             }
+            grid.undo_redo_buffer.push_cells(changes, false);
         }
+        grid.transform(Transform::RotateClockwise);
+        grid.undo_redo_buffer
+            .push(Operation::Transform(Transform::RotateClockwise));
+        let latest_grid = grid.clone();
+        assert_eq!(grid.operation_count(), 3);
+        assert_eq!(grid.current_operation_index(), 3);
+
+        assert!(grid.jump_to(0));
+        assert_eq!(grid.current_operation_index(), 0);
+        assert_eq!(grid.size.width, 5);
+        assert!(grid.cells.iter().all(|cell| *cell == Cell::Empty));
+
+        assert!(grid.jump_to(1));
+        assert_eq!(
+            grid.cells
+                .iter()
+                .filter(|cell| **cell == Cell::Crossed)
+                .count(),
+            5
+        );
+
+        assert!(grid.jump_to(grid.operation_count()));
+        assert_eq!(grid.size, latest_grid.size);
+        assert_eq!(grid.cells, latest_grid.cells);
+        assert_eq!(
+            grid.horizontal_clues_solutions,
+            latest_grid.horizontal_clues_solutions
+        );
+
+        assert!(!grid.jump_to(4));
+        assert_eq!(grid.current_operation_index(), 3);
+        assert_eq!(grid.cells, latest_grid.cells);
     }
 }
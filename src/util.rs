@@ -1,8 +1,47 @@
 use std::{
+    borrow::Cow,
+    collections::HashSet,
     fs,
     io::{self, Read, Seek},
 };
-use terminal::util::Point;
+use terminal::{
+    util::{Color, Point, Size},
+    Terminal,
+};
+
+/// Formats the number padded with spaces to the width like `format!("{:<width$}", number)` or `format!("{:>width$}", number)`
+/// but into the buffer instead of a new string, which matters where many numbers are drawn often, like the clues.
+///
+/// Numbers that are longer than the width are not cut off. The width is at most the length of the buffer.
+pub fn format_padded_number(
+    buffer: &mut [u8; 8],
+    number: u16,
+    width: usize,
+    left_aligned: bool,
+) -> &str {
+    // The digits in reverse order
+    let mut digits = [0; 5];
+    let mut len = 0;
+    let mut rest = number;
+    loop {
+        digits[len] = b'0' + (rest % 10) as u8;
+        len += 1;
+        rest /= 10;
+        if rest == 0 {
+            break;
+        }
+    }
+
+    let width = width.clamp(len, buffer.len());
+    let start = if left_aligned { 0 } else { width - len };
+
+    buffer[..width].fill(b' ');
+    for (index, digit) in digits[..len].iter().rev().enumerate() {
+        buffer[start + index] = *digit;
+    }
+
+    std::str::from_utf8(&buffer[..width]).expect("the buffer only contains ASCII")
+}
 
 /// Returns an iterator over the points from `start_point` to `end_point`.
 pub fn get_line_points(start_point: Point, end_point: Point) -> impl Iterator<Item = Point> {
@@ -16,11 +55,57 @@ pub fn get_line_points(start_point: Point, end_point: Point) -> impl Iterator<It
     })
 }
 
+/// Returns the points of the line from `start_point` to `end_point`, made `thickness` points thick by copies of the line next to it.
+///
+/// The copies are offset perpendicularly to the line, alternating between both sides:
+/// horizontally if the line is steep and vertically otherwise.
+/// Points outside of `bounds` are left out and every point is returned only once, in the order of the line.
+pub fn get_thick_line_points(
+    start_point: Point,
+    end_point: Point,
+    thickness: u16,
+    bounds: Size,
+) -> Vec<Point> {
+    let steep = start_point.y.abs_diff(end_point.y) > start_point.x.abs_diff(end_point.x);
+    // 0, 1, -1, 2, -2 and so on
+    let offsets = (0..thickness as i32).map(|index| {
+        if index % 2 == 1 {
+            (index + 1) / 2
+        } else {
+            -(index / 2)
+        }
+    });
+
+    let mut points = Vec::<Point>::new();
+    let mut seen_points = HashSet::<Point>::new();
+    for point in get_line_points(start_point, end_point) {
+        for offset in offsets.clone() {
+            let (x, y) = if steep {
+                (point.x as i32 + offset, point.y as i32)
+            } else {
+                (point.x as i32, point.y as i32 + offset)
+            };
+
+            if (0..bounds.width as i32).contains(&x) && (0..bounds.height as i32).contains(&y) {
+                let point = Point {
+                    x: x as u16,
+                    y: y as u16,
+                };
+                if seen_points.insert(point) {
+                    points.push(point);
+                }
+            }
+        }
+    }
+
+    points
+}
+
 /// Checks whether `str` is a number consisting of ASCII digits, regardless of the length, negative or not.
 ///
 /// Note that an empty string returns `true`.
 ///
-/// ```
+/// ```ignore
 /// assert!(is_numeric("---123"));
 /// assert!(is_numeric("-123456789012345678901234567890"));
 /// assert!(is_numeric("123"));
@@ -55,6 +140,33 @@ pub fn read_file_content(file: &mut fs::File) -> io::Result<String> {
     Ok(string)
 }
 
+/// Creates a new file named `{name}-{index}.{extension}` with the lowest index from 1 to 9 that isn't taken yet
+/// and returns its filename together with the file.
+///
+/// If all of these files exist already, `too_many_files_error` is returned.
+pub fn create_numbered_file(
+    name: &str,
+    extension: &str,
+    too_many_files_error: &'static str,
+) -> Result<(String, fs::File), &'static str> {
+    let mut open_options = fs::OpenOptions::new();
+    open_options.create_new(true).write(true);
+
+    for index in 1..=9 {
+        let filename = format!("{}-{}.{}", name, index, extension);
+        match open_options.open(&filename) {
+            Ok(file) => return Ok((filename, file)),
+            Err(err) => match err.kind() {
+                io::ErrorKind::AlreadyExists => continue,
+                io::ErrorKind::PermissionDenied => return Err("Permission denied"),
+                _ => return Err("File saving error"),
+            },
+        }
+    }
+
+    Err(too_many_files_error)
+}
+
 /// Erases all of the writer's file's contents.
 pub fn clear_file(writer: &mut io::BufWriter<fs::File>) -> Result<(), &'static str> {
     fn inner(writer: &mut io::BufWriter<fs::File>) -> io::Result<()> {
@@ -73,3 +185,265 @@ pub fn clear_file(writer: &mut io::BufWriter<fs::File>) -> Result<(), &'static s
         Err(_) => Err("File clear failed"),
     }
 }
+
+/// Encodes the bytes as Base64.
+///
+/// ```ignore
+/// assert_eq!(encode_base64(b""), "");
+/// assert_eq!(encode_base64(b"yaya"), "eWF5YQ==");
+/// assert_eq!(encode_base64(b"gram"), "Z3JhbQ==");
+/// assert_eq!(encode_base64(b"yayagram"), "eWF5YWdyYW0=");
+/// ```
+pub fn encode_base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let first = chunk[0] as u32;
+        let second = chunk.get(1).copied().unwrap_or_default() as u32;
+        let third = chunk.get(2).copied().unwrap_or_default() as u32;
+        let group = first << 16 | second << 8 | third;
+
+        for index in 0..4 {
+            if index <= chunk.len() {
+                encoded.push(ALPHABET[(group >> (18 - index * 6) & 0b111111) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+/// Writes the text at the point, leaving the cursor right after the text.
+pub fn print_at(terminal: &mut Terminal, point: Point, text: &str) {
+    terminal.set_cursor(point);
+    terminal.write(text);
+}
+
+/// Writes the text at the point in the colors and resets the colors afterwards.
+///
+/// If `background_color` is not present, the background color is kept.
+pub fn print_colored(
+    terminal: &mut Terminal,
+    point: Point,
+    foreground_color: Color,
+    background_color: Option<Color>,
+    text: &str,
+) {
+    terminal.set_foreground_color(foreground_color);
+    if let Some(background_color) = background_color {
+        terminal.set_background_color(background_color);
+    }
+    print_at(terminal, point, text);
+    terminal.reset_colors();
+}
+
+/// Makes the text written from now on bold or normal again. [`Terminal::reset_colors`] does not do that.
+///
+/// NOTE: this should be replaced with a `Terminal::set_bold` method once tanmatsu has one.
+pub fn set_bold(terminal: &mut Terminal, on: bool) {
+    terminal.write(if on { "\x1b[1m" } else { "\x1b[22m" });
+}
+
+/// Makes the text written from now on underlined or not underlined again. [`Terminal::reset_colors`] does not do that.
+///
+/// NOTE: this should be replaced with a `Terminal::set_underline` method once tanmatsu has one.
+pub fn set_underline(terminal: &mut Terminal, on: bool) {
+    terminal.write(if on { "\x1b[4m" } else { "\x1b[24m" });
+}
+
+/// Writes the text to the clipboard using the OSC 52 escape sequence which many terminals support.
+pub fn clipboard_write(terminal: &mut Terminal, text: &str) {
+    terminal.write(&format!("\x1b]52;c;{}\x07", encode_base64(text.as_bytes())));
+}
+
+/// The ranges of characters that take no column, such as combining marks and variation selectors.
+const ZERO_WIDTH_RANGES: &[(char, char)] = &[
+    ('\u{300}', '\u{36F}'),
+    ('\u{200B}', '\u{200F}'),
+    ('\u{20D0}', '\u{20FF}'),
+    ('\u{FE00}', '\u{FE0F}'),
+];
+
+/// The ranges of characters that take two columns, such as CJK characters and most emoji.
+///
+/// This is a minimal version of the East Asian Wide and Fullwidth categories of Unicode.
+const DOUBLE_WIDTH_RANGES: &[(char, char)] = &[
+    ('\u{1100}', '\u{115F}'),
+    ('\u{2E80}', '\u{303E}'),
+    ('\u{3041}', '\u{33FF}'),
+    ('\u{3400}', '\u{4DBF}'),
+    ('\u{4E00}', '\u{9FFF}'),
+    ('\u{A000}', '\u{A4CF}'),
+    ('\u{AC00}', '\u{D7A3}'),
+    ('\u{F900}', '\u{FAFF}'),
+    ('\u{FE30}', '\u{FE4F}'),
+    ('\u{FF00}', '\u{FF60}'),
+    ('\u{FFE0}', '\u{FFE6}'),
+    ('\u{1F300}', '\u{1F64F}'),
+    ('\u{1F680}', '\u{1F6FF}'),
+    ('\u{1F900}', '\u{1F9FF}'),
+    ('\u{20000}', '\u{3FFFD}'),
+];
+
+/// Gets how many columns the character takes in the terminal.
+fn get_char_width(char: char) -> usize {
+    let within = |ranges: &[(char, char)]| {
+        ranges
+            .iter()
+            .any(|(start, end)| (*start..=*end).contains(&char))
+    };
+
+    if within(ZERO_WIDTH_RANGES) {
+        0
+    } else if within(DOUBLE_WIDTH_RANGES) {
+        2
+    } else {
+        1
+    }
+}
+
+/// Gets how many columns the text takes in the terminal, which unlike its length in bytes is what matters for positioning it.
+pub fn get_display_width(text: &str) -> usize {
+    text.chars().map(get_char_width).sum()
+}
+
+/// The character that truncated text ends in.
+const ELLIPSIS: char = '…';
+
+/// Cuts the text off with an ellipsis so that it takes at most `max_width` columns in the terminal.
+pub fn truncate_to_width(text: &str, max_width: usize) -> Cow<'_, str> {
+    if get_display_width(text) <= max_width {
+        return Cow::Borrowed(text);
+    }
+
+    let mut truncated_text = String::new();
+    let mut width = 0;
+    for char in text.chars() {
+        let char_width = get_char_width(char);
+        // Leave room for the ellipsis
+        if width + char_width >= max_width {
+            break;
+        }
+        truncated_text.push(char);
+        width += char_width;
+    }
+    if max_width != 0 {
+        truncated_text.push(ELLIPSIS);
+    }
+    Cow::Owned(truncated_text)
+}
+
+/// Checks whether the process has a terminal that input can be read from even if the standard input is redirected.
+pub fn has_controlling_terminal() -> bool {
+    #[cfg(unix)]
+    {
+        fs::File::open("/dev/tty").is_ok()
+    }
+    #[cfg(windows)]
+    {
+        fs::File::open("CONIN$").is_ok()
+    }
+    #[cfg(not(any(unix, windows)))]
+    {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_display_width() {
+        assert_eq!(get_display_width("grid.yaya"), 9);
+        assert_eq!(get_display_width("パズル.yaya"), 11);
+        assert_eq!(get_display_width("🧩.yaya"), 7);
+        // "é" made of "e" and a combining acute accent
+        assert_eq!(get_display_width("e\u{301}"), 1);
+        assert_eq!(get_display_width(""), 0);
+    }
+
+    #[test]
+    fn test_truncate_to_width() {
+        assert_eq!(truncate_to_width("grid.yaya", 9), "grid.yaya");
+        assert_eq!(truncate_to_width("grid.yaya", 5), "grid…");
+        // The double-width character doesn't fit next to the ellipsis anymore
+        assert_eq!(truncate_to_width("パズル.yaya", 6), "パズ…");
+        assert_eq!(truncate_to_width("パズル.yaya", 5), "パズ…");
+        assert_eq!(truncate_to_width("パズル.yaya", 4), "パ…");
+        assert_eq!(get_display_width(&truncate_to_width("🧩🧩🧩", 4)), 3);
+        assert_eq!(truncate_to_width("grid.yaya", 1), "…");
+        assert_eq!(truncate_to_width("grid.yaya", 0), "");
+    }
+
+    #[test]
+    fn test_format_padded_number() {
+        let mut buffer = [0; 8];
+        for (number, width) in [
+            (0, 2),
+            (7, 2),
+            (42, 2),
+            (123, 2),
+            (999, 3),
+            (65535, 3),
+            (5, 0),
+        ] {
+            assert_eq!(
+                format_padded_number(&mut buffer, number, width, true),
+                format!("{:<width$}", number, width = width)
+            );
+            assert_eq!(
+                format_padded_number(&mut buffer, number, width, false),
+                format!("{:>width$}", number, width = width)
+            );
+        }
+    }
+
+    #[test]
+    fn test_get_thick_line_points() {
+        let bounds = Size {
+            width: 5,
+            height: 5,
+        };
+
+        // A thickness of 1 is the line itself
+        assert_eq!(
+            get_thick_line_points(Point { x: 0, y: 0 }, Point { x: 3, y: 3 }, 1, bounds),
+            get_line_points(Point { x: 0, y: 0 }, Point { x: 3, y: 3 }).collect::<Vec<Point>>()
+        );
+
+        // A horizontal line is offset vertically, first below and then above
+        assert_eq!(
+            get_thick_line_points(Point { x: 1, y: 2 }, Point { x: 2, y: 2 }, 3, bounds),
+            [
+                Point { x: 1, y: 2 },
+                Point { x: 1, y: 3 },
+                Point { x: 1, y: 1 },
+                Point { x: 2, y: 2 },
+                Point { x: 2, y: 3 },
+                Point { x: 2, y: 1 },
+            ]
+        );
+
+        // A steep line at the left edge is clipped instead of wrapping around
+        assert_eq!(
+            get_thick_line_points(Point { x: 0, y: 0 }, Point { x: 0, y: 1 }, 3, bounds),
+            [
+                Point { x: 0, y: 0 },
+                Point { x: 1, y: 0 },
+                Point { x: 0, y: 1 },
+                Point { x: 1, y: 1 },
+            ]
+        );
+
+        // The copies of a diagonal line overlap but every point is only there once
+        let points = get_thick_line_points(Point { x: 0, y: 0 }, Point { x: 4, y: 4 }, 5, bounds);
+        let unique_points: HashSet<Point> = points.iter().copied().collect();
+        assert_eq!(points.len(), unique_points.len());
+        assert!(points
+            .iter()
+            .all(|point| point.x < bounds.width && point.y < bounds.height));
+    }
+}
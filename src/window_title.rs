@@ -0,0 +1,182 @@
+//! Composes the window title from the state of the game so that it is set in only one place.
+
+use crate::{
+    editor::{self, Editor},
+    grid::{builder::Builder, Difficulty, LineStatus},
+    mode::Mode,
+};
+use std::time::{Duration, Instant};
+use terminal::Terminal;
+
+/// How often the progress in the window title is updated at most.
+const PROGRESS_UPDATE_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Default)]
+pub struct WindowTitle {
+    /// The estimated difficulty of the grid, which is only estimated again when the grid might have changed.
+    difficulty: Option<Difficulty>,
+    /// The title as it was last set.
+    title: String,
+    /// When the progress was last updated.
+    progress_update_time: Option<Instant>,
+}
+
+impl WindowTitle {
+    /// Sets the title, estimating the difficulty again because the grid might have changed.
+    ///
+    /// `unsaved` is whether the grid in the editor has changes that weren't saved yet.
+    pub fn set(
+        &mut self,
+        terminal: &mut Terminal,
+        builder: &mut Builder,
+        editor_toggled: bool,
+        unsaved: bool,
+    ) {
+        self.difficulty = (!editor_toggled).then(|| builder.grid.estimate_difficulty());
+        self.progress_update_time = Some(Instant::now());
+        self.set_title(terminal, builder, editor_toggled, unsaved);
+    }
+
+    /// Sets the title if the progress or, in the editor, whether there are unsaved changes changed,
+    /// checking at most once per [`PROGRESS_UPDATE_INTERVAL`] because checking for unsaved changes serializes the grid.
+    pub fn update_progress(
+        &mut self,
+        terminal: &mut Terminal,
+        builder: &mut Builder,
+        editor: &Editor,
+    ) {
+        let now = Instant::now();
+        if self
+            .progress_update_time
+            .is_some_and(|progress_update_time| {
+                now.saturating_duration_since(progress_update_time) < PROGRESS_UPDATE_INTERVAL
+            })
+        {
+            return;
+        }
+        self.progress_update_time = Some(now);
+
+        let unsaved = editor.toggled && editor.has_unsaved_changes(&builder.grid);
+        self.set_title(terminal, builder, editor.toggled, unsaved);
+    }
+
+    /// Sets the title if it changed.
+    fn set_title(
+        &mut self,
+        terminal: &mut Terminal,
+        builder: &mut Builder,
+        editor_toggled: bool,
+        unsaved: bool,
+    ) {
+        let title = compose(builder, editor_toggled, unsaved, self.difficulty);
+        if title != self.title {
+            terminal.set_title(&title);
+            self.title = title;
+        }
+    }
+}
+
+/// Gets how many percent of the rows and columns are solved.
+fn get_solved_percentage(builder: &mut Builder) -> usize {
+    let size = builder.grid.size;
+    let solved_line_count = (0..size.height)
+        .filter(|y| builder.grid.get_row_status(*y) == LineStatus::Solved)
+        .count()
+        + (0..size.width)
+            .filter(|x| builder.grid.get_column_status(*x) == LineStatus::Solved)
+            .count();
+
+    solved_line_count * 100 / (size.width + size.height) as usize
+}
+
+/// Composes the title from the mode or the editor, the size and title of the grid and, in the editor, whether there are unsaved changes
+/// or, outside the editor, the difficulty and, unless in zen mode, the progress.
+fn compose(
+    builder: &mut Builder,
+    editor_toggled: bool,
+    unsaved: bool,
+    difficulty: Option<Difficulty>,
+) -> String {
+    let size = builder.grid.size;
+    let name = if editor_toggled {
+        editor::WINDOW_TITLE
+    } else {
+        builder.mode.get_window_title()
+    };
+    let mut title = format!("{} {}x{}", name, size.width, size.height);
+
+    if let Some(grid_title) = builder.grid.metadata.get("title") {
+        title.push_str(&format!(" - {}", grid_title));
+    }
+
+    if editor_toggled {
+        if unsaved {
+            title.push_str(" (unsaved)");
+        }
+        return title;
+    }
+
+    if let Some(difficulty) = difficulty {
+        title.push_str(&format!(" - Difficulty: {}", difficulty.name()));
+    }
+    if builder.mode != Mode::Zen {
+        title.push_str(&format!(" - {}%", get_solved_percentage(builder)));
+    }
+
+    title
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::Settings,
+        editor::Editor,
+        grid::{palette::Palette, Cell, Grid},
+    };
+    use terminal::util::Point;
+
+    #[test]
+    fn test_compose() {
+        let stdout = std::io::stdout();
+        let terminal = Terminal::new(stdout.lock()).unwrap();
+
+        #[rustfmt::skip]
+        let grid = Grid::from_lines(&[
+            "1 1",
+            "111",
+        ]).unwrap();
+        let mut builder = Builder::new(&terminal, grid, Palette::default(), &Settings::default());
+        Editor::set_metadata(&mut builder.grid, "title", "Two rows");
+
+        assert_eq!(
+            compose(&mut builder, true, false, None),
+            "yayagram Editor 3x2 - Two rows"
+        );
+        assert_eq!(
+            compose(&mut builder, true, true, None),
+            "yayagram Editor 3x2 - Two rows (unsaved)"
+        );
+
+        // Every row and column has clues so none is solved yet
+        assert_eq!(
+            compose(&mut builder, false, false, Some(Difficulty::Easy)),
+            "yayagram 3x2 - Two rows - Difficulty: easy - 0%"
+        );
+
+        for x in 0..3 {
+            *builder.grid.get_mut_cell(Point { x, y: 1 }) = Cell::Filled;
+        }
+        // The second row and the middle column are solved
+        assert_eq!(
+            compose(&mut builder, false, false, None),
+            "yayagram 3x2 - Two rows - 40%"
+        );
+
+        builder.mode = Mode::Zen;
+        assert_eq!(
+            compose(&mut builder, false, false, None),
+            "yayagram Zen 3x2 - Two rows"
+        );
+    }
+}